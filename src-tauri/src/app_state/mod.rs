@@ -3,7 +3,7 @@ pub mod store;
 
 use chrono::Utc;
 use model::RecentProject;
-pub use store::{load_state, save_state};
+pub use store::{app_data_dir, load_state, load_state_from, save_state};
 
 pub fn add_recent_project(
     app: &tauri::AppHandle,
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct AppState {
@@ -28,6 +29,59 @@ pub struct Preferences {
     pub default_editor: Option<String>,
     pub tilt_path: Option<String>,
     pub editor_path: Option<String>,
+    /// Extra regexes masked in captured Tilt logs
+    #[serde(default)]
+    pub log_redaction_patterns: Vec<String>,
+    /// Rotation and retention of captured Tilt logs
+    #[serde(default)]
+    pub log_retention: LogRetention,
+    /// Desktop notifications for running environments
+    #[serde(default)]
+    pub notifications: NotificationPreferences,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotificationPreferences {
+    pub enabled: bool,
+    /// The same notification for the same resource is not repeated within this window
+    pub debounce_secs: u64,
+    /// Workspace paths of projects that never notify
+    #[serde(default)]
+    pub muted_projects: Vec<String>,
+    /// Muted service names, keyed by workspace path
+    #[serde(default)]
+    pub muted_services: HashMap<String, Vec<String>>,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            debounce_secs: 60,
+            muted_projects: Vec::new(),
+            muted_services: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LogRetention {
+    /// Rotate the live log once it reaches this size, 0 disables rotation
+    pub max_file_bytes: u64,
+    /// Previous sessions kept per environment
+    pub max_sessions: usize,
+    /// Sessions older than this are deleted, regardless of count
+    pub max_age_days: Option<u64>,
+}
+
+impl Default for LogRetention {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: 10 * 1024 * 1024,
+            max_sessions: 10,
+            max_age_days: Some(14),
+        }
+    }
 }
 
 impl Default for WindowState {
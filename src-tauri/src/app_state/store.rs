@@ -1,23 +1,32 @@
 use super::model::AppState;
 use crate::backend::errors::AppError;
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 use tauri::Manager;
 
-fn state_file(app_handle: &tauri::AppHandle) -> Result<PathBuf, AppError> {
-    let dir = app_handle
+pub fn app_data_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, AppError> {
+    app_handle
         .path()
         .app_data_dir()
-        .map_err(|e| AppError::Invalid(format!("App data dir error: {}", e)))?;
-    Ok(dir.join("state.json"))
+        .map_err(|e| AppError::Invalid(format!("App data dir error: {}", e)))
+}
+
+fn state_file(app_handle: &tauri::AppHandle) -> Result<PathBuf, AppError> {
+    Ok(app_data_dir(app_handle)?.join("state.json"))
 }
 
 pub fn load_state(app: &tauri::AppHandle) -> AppState {
-    let path = match state_file(app) {
-        Ok(p) => p,
-        Err(_) => return AppState::default(),
-    };
+    match app_data_dir(app) {
+        Ok(dir) => load_state_from(&dir),
+        Err(_) => AppState::default(),
+    }
+}
 
-    fs::read_to_string(path)
+/// Read the app state from a data dir, for callers without an `AppHandle`
+pub fn load_state_from(data_dir: &Path) -> AppState {
+    fs::read_to_string(data_dir.join("state.json"))
         .ok()
         .and_then(|data| serde_json::from_str(&data).ok())
         .unwrap_or_default()
@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::backend::tilt_resources::{parse_tilt_time, UiResource};
+
+/// Sessions whose metrics are kept per environment
+const MAX_METRIC_SESSIONS: usize = 50;
+
+/// Samples kept per resource and session, oldest are dropped first
+const MAX_SAMPLES: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BuildSample {
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub failed: bool,
+}
+
+/// Time from the start of a build until the resource reported ready
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReadySample {
+    pub build_started_at: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ResourceMetrics {
+    pub builds: Vec<BuildSample>,
+    pub ready: Vec<ReadySample>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SessionMetrics {
+    pub session: String,
+    pub resources: BTreeMap<String, ResourceMetrics>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, TS)]
+pub struct Percentiles {
+    pub count: usize,
+    #[ts(type = "number")]
+    pub p50: u64,
+    #[ts(type = "number")]
+    pub p90: u64,
+    #[ts(type = "number")]
+    pub p99: u64,
+    #[ts(type = "number")]
+    pub max: u64,
+    #[ts(type = "number")]
+    pub mean: u64,
+}
+
+/// Median build time of a resource in one session
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+pub struct TrendPoint {
+    pub session: String,
+    pub builds: usize,
+    pub failures: usize,
+    #[ts(type = "number")]
+    pub median_ms: u64,
+}
+
+/// Aggregates of one resource over the requested sessions
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+pub struct ResourceStats {
+    pub resource: String,
+    pub updates: usize,
+    pub failures: usize,
+    pub build_duration: Percentiles,
+    pub time_to_ready: Percentiles,
+    /// Oldest session first
+    pub trend: Vec<TrendPoint>,
+    /// Change of the latest session's median against the average of the
+    /// earlier ones, in percent. Positive means builds got slower.
+    pub trend_change_pct: Option<f64>,
+}
+
+fn metrics_path(workspace: &str, env: &str) -> PathBuf {
+    Path::new(workspace)
+        .join(".tooling")
+        .join(format!("{}_metrics.json", env))
+}
+
+/// Stored metrics of an environment, oldest session first
+pub fn read_metrics(workspace: &str, env: &str) -> Vec<SessionMetrics> {
+    fs::read_to_string(metrics_path(workspace, env))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn write_metrics(workspace: &str, env: &str, metrics: &[SessionMetrics]) -> io::Result<()> {
+    let path = metrics_path(workspace, env);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(metrics)?)
+}
+
+/// Merge a poll of Tilt's resources into the session's metrics.
+/// Tilt only keeps the last few builds, so every poll adds what is new.
+pub fn record_snapshot(
+    workspace: &str,
+    env: &str,
+    session: &str,
+    resources: &[UiResource],
+) -> io::Result<()> {
+    let mut metrics = read_metrics(workspace, env);
+
+    if metrics.last().map(|m| m.session.as_str()) != Some(session) {
+        metrics.push(SessionMetrics {
+            session: session.to_string(),
+            resources: BTreeMap::new(),
+        });
+        let excess = metrics.len().saturating_sub(MAX_METRIC_SESSIONS);
+        metrics.drain(..excess);
+    }
+
+    let Some(current) = metrics.last_mut() else {
+        return Ok(());
+    };
+
+    let mut changed = false;
+    for resource in resources {
+        let entry = current
+            .resources
+            .entry(resource.name().to_string())
+            .or_default();
+        changed |= merge_resource(entry, resource);
+    }
+
+    if changed {
+        write_metrics(workspace, env, &metrics)?;
+    }
+
+    Ok(())
+}
+
+fn merge_resource(metrics: &mut ResourceMetrics, resource: &UiResource) -> bool {
+    let mut changed = false;
+
+    // History is newest first
+    for build in resource.status.build_history.iter().rev() {
+        let (Some(start), Some(finish)) = (
+            parse_tilt_time(build.start_time.as_deref()),
+            parse_tilt_time(build.finish_time.as_deref()),
+        ) else {
+            continue;
+        };
+
+        let started_at = start.to_rfc3339();
+        if metrics.builds.iter().any(|b| b.started_at == started_at) {
+            continue;
+        }
+
+        metrics.builds.push(BuildSample {
+            started_at,
+            duration_ms: (finish - start).num_milliseconds().max(0) as u64,
+            failed: build.error.as_deref().is_some_and(|e| !e.is_empty()),
+        });
+        changed = true;
+    }
+
+    // Readiness after the latest successful build
+    let latest = resource
+        .status
+        .build_history
+        .first()
+        .filter(|b| b.error.as_deref().is_none_or(str::is_empty));
+    let ready = parse_tilt_time(resource.ready_since());
+
+    if let (Some(build), Some(ready)) = (latest, ready) {
+        if let Some(start) = parse_tilt_time(build.start_time.as_deref()) {
+            let build_started_at = start.to_rfc3339();
+            let recorded = metrics
+                .ready
+                .iter()
+                .any(|r| r.build_started_at == build_started_at);
+
+            if ready >= start && !recorded {
+                metrics.ready.push(ReadySample {
+                    build_started_at,
+                    duration_ms: (ready - start).num_milliseconds().max(0) as u64,
+                });
+                changed = true;
+            }
+        }
+    }
+
+    let excess = metrics.builds.len().saturating_sub(MAX_SAMPLES);
+    metrics.builds.drain(..excess);
+    let excess = metrics.ready.len().saturating_sub(MAX_SAMPLES);
+    metrics.ready.drain(..excess);
+
+    changed
+}
+
+/// Nearest-rank percentiles of the samples
+pub fn percentiles(mut samples: Vec<u64>) -> Percentiles {
+    if samples.is_empty() {
+        return Percentiles::default();
+    }
+
+    samples.sort_unstable();
+    let rank = |p: f64| {
+        let index = ((p / 100.0) * samples.len() as f64).ceil() as usize;
+        samples[index.clamp(1, samples.len()) - 1]
+    };
+
+    Percentiles {
+        count: samples.len(),
+        p50: rank(50.0),
+        p90: rank(90.0),
+        p99: rank(99.0),
+        max: samples[samples.len() - 1],
+        mean: samples.iter().sum::<u64>() / samples.len() as u64,
+    }
+}
+
+/// Per-resource statistics over the last `sessions` sessions, all when not set
+pub fn build_stats(workspace: &str, env: &str, sessions: Option<usize>) -> Vec<ResourceStats> {
+    let metrics = read_metrics(workspace, env);
+    let skip = sessions
+        .map(|n| metrics.len().saturating_sub(n))
+        .unwrap_or(0);
+    let metrics = &metrics[skip..];
+
+    let mut names: Vec<&String> = metrics.iter().flat_map(|m| m.resources.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let per_session: Vec<(&str, &ResourceMetrics)> = metrics
+                .iter()
+                .filter_map(|m| m.resources.get(name).map(|r| (m.session.as_str(), r)))
+                .collect();
+
+            let builds: Vec<&BuildSample> =
+                per_session.iter().flat_map(|(_, r)| &r.builds).collect();
+
+            let trend: Vec<TrendPoint> = per_session
+                .iter()
+                .filter(|(_, r)| !r.builds.is_empty())
+                .map(|(session, r)| TrendPoint {
+                    session: session.to_string(),
+                    builds: r.builds.len(),
+                    failures: r.builds.iter().filter(|b| b.failed).count(),
+                    median_ms: percentiles(r.builds.iter().map(|b| b.duration_ms).collect()).p50,
+                })
+                .collect();
+
+            ResourceStats {
+                resource: name.clone(),
+                updates: builds.len(),
+                failures: builds.iter().filter(|b| b.failed).count(),
+                build_duration: percentiles(builds.iter().map(|b| b.duration_ms).collect()),
+                time_to_ready: percentiles(
+                    per_session
+                        .iter()
+                        .flat_map(|(_, r)| r.ready.iter().map(|s| s.duration_ms))
+                        .collect(),
+                ),
+                trend_change_pct: trend_change(&trend),
+                trend,
+            }
+        })
+        .collect()
+}
+
+fn trend_change(trend: &[TrendPoint]) -> Option<f64> {
+    let (latest, earlier) = trend.split_last()?;
+    if earlier.is_empty() {
+        return None;
+    }
+
+    let baseline = earlier.iter().map(|p| p.median_ms as f64).sum::<f64>() / earlier.len() as f64;
+    if baseline == 0.0 {
+        return None;
+    }
+
+    Some((latest.median_ms as f64 - baseline) / baseline * 100.0)
+}
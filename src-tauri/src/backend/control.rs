@@ -0,0 +1,256 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use tauri::async_runtime::JoinHandle;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::app_state::app_data_dir;
+use crate::backend::errors::{ErrorCode, IpcError};
+use crate::backend::events::{self, BackendEvent};
+use crate::backend::ipc::handle_ipc;
+
+/// Owner-only directory in the app data dir that holds the socket
+const SOCKET_DIR: &str = "control";
+const SOCKET_NAME: &str = "control.sock";
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const COMMAND_FAILED: i64 = -32000;
+
+pub fn socket_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(SOCKET_DIR).join(SOCKET_NAME)
+}
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    /// Missing for notifications, which get no response
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize, Default)]
+struct SubscribeParams {
+    /// Event types to receive (`status`, `log`, `job`, `job_log`), all when empty
+    #[serde(default)]
+    events: Vec<String>,
+    workspace_path: Option<String>,
+    env: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UnsubscribeParams {
+    subscription: u64,
+}
+
+fn response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.into() },
+    })
+}
+
+/// A failed command, with the `IpcError` as `data` for clients that match on its code
+fn command_error_response(id: Value, error: IpcError) -> Value {
+    let code = match error.code {
+        ErrorCode::UnknownCommand => METHOD_NOT_FOUND,
+        ErrorCode::InvalidInput => INVALID_PARAMS,
+        _ => COMMAND_FAILED,
+    };
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": error.message, "data": error },
+    })
+}
+
+/// Accept connections on the control socket until the app exits.
+///
+/// Speaks JSON-RPC 2.0, one message per line. Every `handle_ipc` command is
+/// a method with the same arguments, plus `subscribe`/`unsubscribe` for
+/// event notifications. Only the owner of the app data dir may connect.
+pub async fn serve(app: tauri::AppHandle) -> io::Result<()> {
+    let data_dir = app_data_dir(&app).map_err(|e| io::Error::other(e.to_string()))?;
+    let path = socket_path(&data_dir);
+
+    // The socket is bound with the umask's permissions and only restricted
+    // after, the directory keeps others out in between
+    let socket_dir = data_dir.join(SOCKET_DIR);
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(&socket_dir)?;
+    fs::set_permissions(&socket_dir, fs::Permissions::from_mode(0o700))?;
+
+    // Left over from a previous run; a second instance never gets this far
+    if path.exists() {
+        fs::remove_file(&path)?;
+    }
+
+    let listener = UnixListener::bind(&path)?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tauri::async_runtime::spawn(handle_connection(app.clone(), stream));
+    }
+}
+
+async fn handle_connection(app: tauri::AppHandle, stream: UnixStream) {
+    let (reader, mut writer) = stream.into_split();
+    let (tx, mut rx) = mpsc::unbounded_channel::<Value>();
+
+    // Responses and notifications share the socket, one writer keeps lines whole
+    let writes = tauri::async_runtime::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            let mut line = message.to_string();
+            line.push('\n');
+            if writer.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut subscriptions: HashMap<u64, JoinHandle<()>> = HashMap::new();
+    let mut next_subscription = 0;
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let request: Request = match serde_json::from_str::<Value>(&line) {
+            Err(e) => {
+                let _ = tx.send(error_response(Value::Null, PARSE_ERROR, e.to_string()));
+                continue;
+            }
+            Ok(value) => {
+                let id = value.get("id").cloned().unwrap_or(Value::Null);
+                match serde_json::from_value::<Request>(value) {
+                    Ok(request) if request.jsonrpc == "2.0" => request,
+                    Ok(_) => {
+                        let _ = tx.send(error_response(id, INVALID_REQUEST, "jsonrpc must be 2.0"));
+                        continue;
+                    }
+                    Err(e) => {
+                        let _ = tx.send(error_response(id, INVALID_REQUEST, e.to_string()));
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let Request {
+            id, method, params, ..
+        } = request;
+        let reply = match method.as_str() {
+            "subscribe" => {
+                let params = if params.is_null() {
+                    Ok(SubscribeParams::default())
+                } else {
+                    serde_json::from_value::<SubscribeParams>(params)
+                };
+                match params {
+                    Ok(params) => {
+                        next_subscription += 1;
+                        let forward = forward_events(
+                            next_subscription,
+                            params,
+                            events::subscribe(),
+                            tx.clone(),
+                        );
+                        subscriptions
+                            .insert(next_subscription, tauri::async_runtime::spawn(forward));
+                        Some(Ok(json!({ "subscription": next_subscription })))
+                    }
+                    Err(e) => Some(Err((INVALID_PARAMS, e.to_string()))),
+                }
+            }
+            "unsubscribe" => match serde_json::from_value::<UnsubscribeParams>(params) {
+                Ok(params) => {
+                    let found = subscriptions.remove(&params.subscription);
+                    if let Some(task) = &found {
+                        task.abort();
+                    }
+                    Some(Ok(json!(found.is_some())))
+                }
+                Err(e) => Some(Err((INVALID_PARAMS, e.to_string()))),
+            },
+            _ => {
+                // Commands can run for a while (start, clone), keep reading meanwhile
+                let (app, tx, id) = (app.clone(), tx.clone(), id.clone());
+                tauri::async_runtime::spawn(async move {
+                    let result = handle_ipc(app, &method, params).await;
+                    if let Some(id) = id {
+                        let _ = tx.send(match result {
+                            Ok(value) => response(id, value),
+                            Err(e) => command_error_response(id, e),
+                        });
+                    }
+                });
+                None
+            }
+        };
+
+        if let (Some(id), Some(reply)) = (id, reply) {
+            let _ = tx.send(match reply {
+                Ok(value) => response(id, value),
+                Err((code, message)) => error_response(id, code, message),
+            });
+        }
+    }
+
+    for task in subscriptions.into_values() {
+        task.abort();
+    }
+    drop(tx);
+    let _ = writes.await;
+}
+
+/// Send matching events as `event` notifications until the client goes away
+async fn forward_events(
+    subscription: u64,
+    params: SubscribeParams,
+    mut events: broadcast::Receiver<BackendEvent>,
+    tx: mpsc::UnboundedSender<Value>,
+) {
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            // Slow readers miss events rather than holding up Tilt
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        let wanted = params.events.is_empty() || params.events.iter().any(|e| e == event.kind());
+        if !wanted || !event.concerns(params.workspace_path.as_deref(), params.env.as_deref()) {
+            continue;
+        }
+
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "event",
+            "params": { "subscription": subscription, "event": event },
+        });
+        if tx.send(notification).is_err() {
+            break;
+        }
+    }
+}
@@ -1,14 +1,302 @@
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
+use ts_rs::TS;
 
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+    Io(#[from] io::Error),
+
+    #[error("{}: {source}", .path.display())]
+    File { path: PathBuf, source: io::Error },
 
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("Invalid JSON in {}: {source}", .path.display())]
+    InvalidJson {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
     #[error("Invalid config: {0}")]
-    #[allow(dead_code)]
     Invalid(String),
+
+    #[error("No project found at {}", .path.display())]
+    ProjectNotFound { path: PathBuf },
+
+    #[error("Environment {env} not found")]
+    EnvNotFound { env: String },
+
+    #[error("Environment {env} already exists")]
+    EnvExists { env: String },
+
+    #[error("Environment {env} is running. Stop Tilt before changing it")]
+    EnvRunning { env: String },
+
+    #[error("Service {service} not found{}", in_env(.env))]
+    ServiceNotFound {
+        service: String,
+        env: Option<String>,
+    },
+
+    #[error("Service {service} already exists in environment {env}")]
+    ServiceExists { service: String, env: String },
+
+    #[error("Tilt is not installed")]
+    TiltNotInstalled,
+
+    #[error("Git clone of {url} failed")]
+    GitFailed { url: String },
+
+    #[error("Job {job} not found")]
+    JobNotFound { job: String },
+
+    #[error("Cancelled")]
+    Cancelled,
+
+    #[error("{0}")]
+    Other(String),
+}
+
+fn in_env(env: &Option<String>) -> String {
+    env.as_ref()
+        .map(|e| format!(" in environment {}", e))
+        .unwrap_or_default()
+}
+
+/// Stable identifier of an error kind, safe for the frontend to match on
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Io,
+    NotFound,
+    PermissionDenied,
+    AlreadyExists,
+    InvalidJson,
+    InvalidInput,
+    ProjectNotFound,
+    EnvNotFound,
+    EnvExists,
+    EnvRunning,
+    ServiceNotFound,
+    ServiceExists,
+    TiltNotInstalled,
+    GitFailed,
+    JobNotFound,
+    Cancelled,
+    UnknownCommand,
+    Internal,
+}
+
+/// What an error was about, each part only when it applies
+#[derive(Serialize, Debug, Clone, Default, PartialEq, TS)]
+pub struct ErrorContext {
+    pub env: Option<String>,
+    pub service: Option<String>,
+    pub path: Option<String>,
+}
+
+/// An error as it crosses IPC and the control socket
+#[derive(Serialize, Debug, Clone, TS)]
+pub struct IpcError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// What the user can do about it
+    pub hint: Option<String>,
+    // Boxed so results carrying an `IpcError` stay small
+    pub context: Box<ErrorContext>,
+}
+
+impl AppError {
+    /// An IO error on `path`
+    pub fn file(path: &Path, source: io::Error) -> Self {
+        AppError::File {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub fn env_not_found(env: &str) -> Self {
+        AppError::EnvNotFound {
+            env: env.to_string(),
+        }
+    }
+
+    pub fn service_not_found(service: &str, env: Option<&str>) -> Self {
+        AppError::ServiceNotFound {
+            service: service.to_string(),
+            env: env.map(str::to_string),
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::Io(e) | AppError::File { source: e, .. } => io_code(e),
+            AppError::Json(_) | AppError::InvalidJson { .. } => ErrorCode::InvalidJson,
+            AppError::Invalid(_) => ErrorCode::InvalidInput,
+            AppError::ProjectNotFound { .. } => ErrorCode::ProjectNotFound,
+            AppError::EnvNotFound { .. } => ErrorCode::EnvNotFound,
+            AppError::EnvExists { .. } => ErrorCode::EnvExists,
+            AppError::EnvRunning { .. } => ErrorCode::EnvRunning,
+            AppError::ServiceNotFound { .. } => ErrorCode::ServiceNotFound,
+            AppError::ServiceExists { .. } => ErrorCode::ServiceExists,
+            AppError::TiltNotInstalled => ErrorCode::TiltNotInstalled,
+            AppError::GitFailed { .. } => ErrorCode::GitFailed,
+            AppError::JobNotFound { .. } => ErrorCode::JobNotFound,
+            AppError::Cancelled => ErrorCode::Cancelled,
+            AppError::Other(_) => ErrorCode::Internal,
+        }
+    }
+
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            AppError::Io(e) | AppError::File { source: e, .. }
+                if e.kind() == io::ErrorKind::PermissionDenied =>
+            {
+                "Check that you have access to this path"
+            }
+            AppError::InvalidJson { .. } => {
+                "The file may have been edited by hand. Fix the JSON or restore it from version control"
+            }
+            AppError::ProjectNotFound { .. } => {
+                "Open the folder that contains project.json, or initialize this folder as a project"
+            }
+            AppError::EnvExists { .. } | AppError::ServiceExists { .. } => "Choose a different name",
+            AppError::EnvRunning { .. } => "Stop Tilt for this environment and try again",
+            AppError::TiltNotInstalled => {
+                "Install Tilt from https://docs.tilt.dev/install.html or set its path in Settings"
+            }
+            AppError::GitFailed { .. } => {
+                "Check the repository URL, the branch and your Git credentials"
+            }
+            _ => return None,
+        };
+        Some(hint.to_string())
+    }
+
+    pub fn context(&self) -> ErrorContext {
+        let path = |p: &Path| Some(p.display().to_string());
+        match self {
+            AppError::File { path: p, .. }
+            | AppError::InvalidJson { path: p, .. }
+            | AppError::ProjectNotFound { path: p } => ErrorContext {
+                path: path(p),
+                ..Default::default()
+            },
+            AppError::EnvNotFound { env }
+            | AppError::EnvExists { env }
+            | AppError::EnvRunning { env } => ErrorContext {
+                env: Some(env.clone()),
+                ..Default::default()
+            },
+            AppError::ServiceNotFound { service, env } => ErrorContext {
+                env: env.clone(),
+                service: Some(service.clone()),
+                ..Default::default()
+            },
+            AppError::ServiceExists { service, env } => ErrorContext {
+                env: Some(env.clone()),
+                service: Some(service.clone()),
+                ..Default::default()
+            },
+            _ => ErrorContext::default(),
+        }
+    }
+}
+
+fn io_code(e: &io::Error) -> ErrorCode {
+    match e.kind() {
+        io::ErrorKind::NotFound => ErrorCode::NotFound,
+        io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+        io::ErrorKind::AlreadyExists => ErrorCode::AlreadyExists,
+        io::ErrorKind::InvalidInput => ErrorCode::InvalidInput,
+        _ => ErrorCode::Io,
+    }
+}
+
+/// Keeps the `AppError` of an `anyhow` chain, so `?` does not flatten it to a message
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<AppError>() {
+            Ok(app) => app,
+            Err(e) => match e.downcast::<io::Error>() {
+                Ok(io) => AppError::Io(io),
+                Err(e) => AppError::Other(format!("{:#}", e)),
+            },
+        }
+    }
+}
+
+impl IpcError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        IpcError {
+            code,
+            message: message.into(),
+            hint: None,
+            context: Box::default(),
+        }
+    }
+}
+
+impl From<&AppError> for IpcError {
+    fn from(e: &AppError) -> Self {
+        IpcError {
+            code: e.code(),
+            message: e.to_string(),
+            hint: e.hint(),
+            context: Box::new(e.context()),
+        }
+    }
+}
+
+impl From<AppError> for IpcError {
+    fn from(e: AppError) -> Self {
+        IpcError::from(&e)
+    }
+}
+
+/// Takes code, hint and context from the `AppError` or `io::Error` in the
+/// chain, and keeps the context added on top of it in the message
+impl From<anyhow::Error> for IpcError {
+    fn from(e: anyhow::Error) -> Self {
+        let mut error = if let Some(app) = find_app_error(&e) {
+            IpcError::from(app)
+        } else if let Some(io) = e.downcast_ref::<io::Error>() {
+            IpcError::new(io_code(io), "")
+        } else {
+            IpcError::new(ErrorCode::Internal, "")
+        };
+
+        // Both already print their source, the rest of the chain would repeat it
+        let mut parts = Vec::new();
+        for cause in e.chain() {
+            parts.push(cause.to_string());
+            if cause.is::<AppError>() || cause.is::<io::Error>() {
+                break;
+            }
+        }
+        error.message = parts.join(": ");
+        error
+    }
+}
+
+/// The `AppError` behind `e`, also when an `io::Result` function wrapped it
+fn find_app_error(e: &anyhow::Error) -> Option<&AppError> {
+    e.chain().find_map(|cause| {
+        cause.downcast_ref::<AppError>().or_else(|| {
+            cause
+                .downcast_ref::<io::Error>()
+                .and_then(|io| io.get_ref())
+                .and_then(|inner| inner.downcast_ref::<AppError>())
+        })
+    })
+}
+
+impl std::fmt::Display for IpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
 }
@@ -0,0 +1,90 @@
+use serde::Serialize;
+use std::sync::LazyLock;
+use tokio::sync::broadcast;
+
+use crate::backend::jobs::Job;
+use crate::backend::log_records::LogStream;
+
+/// Events kept for subscribers that fall behind
+const EVENT_BUFFER: usize = 1024;
+
+/// Something that happened to a running environment or a job
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BackendEvent {
+    /// The recorded Tilt status changed (`starting`, `running`, `stopped`)
+    Status {
+        workspace_path: String,
+        env: String,
+        status: String,
+    },
+    /// Tilt printed a line, already redacted
+    Log {
+        workspace_path: String,
+        env: String,
+        stream: LogStream,
+        line: String,
+    },
+    /// A job started, made progress or finished. Boxed, jobs are much larger
+    /// than the other events.
+    Job { job: Box<Job> },
+    /// A job printed a line, such as git's clone output
+    #[serde(rename = "job_log")]
+    JobLog {
+        id: String,
+        workspace_path: Option<String>,
+        env: Option<String>,
+        line: String,
+    },
+}
+
+impl BackendEvent {
+    /// Name subscribers filter on
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BackendEvent::Status { .. } => "status",
+            BackendEvent::Log { .. } => "log",
+            BackendEvent::Job { .. } => "job",
+            BackendEvent::JobLog { .. } => "job_log",
+        }
+    }
+
+    /// Jobs outside any workspace or env only match when there is no filter on it
+    pub fn concerns(&self, workspace: Option<&str>, environment: Option<&str>) -> bool {
+        let (workspace_path, env) = match self {
+            BackendEvent::Status {
+                workspace_path,
+                env,
+                ..
+            }
+            | BackendEvent::Log {
+                workspace_path,
+                env,
+                ..
+            } => (Some(workspace_path.as_str()), Some(env.as_str())),
+            BackendEvent::Job { job } => (job.workspace_path.as_deref(), job.env.as_deref()),
+            BackendEvent::JobLog {
+                workspace_path,
+                env,
+                ..
+            } => (workspace_path.as_deref(), env.as_deref()),
+        };
+
+        workspace.is_none_or(|w| workspace_path == Some(w))
+            && environment.is_none_or(|e| env == Some(e))
+    }
+}
+
+static EVENTS: LazyLock<broadcast::Sender<BackendEvent>> =
+    LazyLock::new(|| broadcast::channel(EVENT_BUFFER).0);
+
+/// Send an event to every current subscriber
+pub fn publish(event: BackendEvent) {
+    // Nobody listening is not an error
+    let _ = EVENTS.send(event);
+}
+
+/// Receive every event published from now on
+pub fn subscribe() -> broadcast::Receiver<BackendEvent> {
+    EVENTS.subscribe()
+}
@@ -5,6 +5,9 @@ use std::path::Path;
 
 // Assuming these are defined elsewhere in your crate
 use crate::backend::dependency_graph::{detect_cycles, topo_sort};
+use crate::backend::errors::AppError;
+use crate::backend::inheritance::resolve_environment;
+use crate::backend::interpolation::{secret_keys, service_env, InterpolationContext};
 use crate::backend::project::{Project, Service};
 
 // =============================================================================
@@ -35,7 +38,7 @@ for svc in services:
 "#;
 
 const SERVICE_TILTFILE_TEMPLATE: &str = r#"# GENERATED — DO NOT EDIT
-
+{{HELPERS}}
 def {{SERVICE_NAME_SNAKE}}():
 {{DOCKER_SECTION}}{{K8S_SECTION}}
     k8s_resource(
@@ -76,7 +79,7 @@ spec:
         envFrom:
         - configMapRef:
             name: {{SERVICE_NAME}}-config
-        resources:
+{{SECRET_ENV_FROM}}        resources:
           requests:
             memory: "128Mi"
             cpu: "100m"
@@ -97,6 +100,17 @@ spec:
   type: ClusterIP
 "#;
 
+/// Sets the replica count of the service's workload in the YAML it deploys,
+/// so every environment can run its own count off the same manifests
+const WITH_REPLICAS_HELPER: &str = r#"
+def _with_replicas(yaml, name, replicas):
+    objects = decode_yaml_stream(yaml)
+    for o in objects:
+        if o.get("kind") in ["Deployment", "StatefulSet"] and o["metadata"]["name"] == name:
+            o["spec"]["replicas"] = replicas
+    return encode_yaml_stream(objects)
+"#;
+
 // Template helper functions
 fn render_template(template: &str, replacements: &[(&str, &str)]) -> String {
     let mut result = template.to_string();
@@ -107,10 +121,8 @@ fn render_template(template: &str, replacements: &[(&str, &str)]) -> String {
 }
 
 pub fn generate_tiltfiles(project: &Project, env_name: &str) -> Result<()> {
-    let env_config = project
-        .environments
-        .get(env_name)
-        .ok_or_else(|| anyhow::anyhow!("Environment {} not found", env_name))?;
+    // Apply `extends` and overrides so the generator only sees effective values
+    let env_config = resolve_environment(project, env_name)?;
 
     let workspace = &project.project.workspace_path;
     let services_path = project.project.services_path.as_deref().unwrap_or("repos");
@@ -129,11 +141,45 @@ pub fn generate_tiltfiles(project: &Project, env_name: &str) -> Result<()> {
 
     if let Some(cycle) = detect_cycles(&enabled_services) {
         let cycle_str: String = cycle.join(" -> ");
-        return Err(anyhow::anyhow!("Dependency cycle detected: {}", cycle_str));
+        return Err(AppError::Invalid(format!("Dependency cycle detected: {}", cycle_str)).into());
     }
 
     let sorted_services = topo_sort(&enabled_services);
 
+    // Resolve ${...} references up front so nothing is written when one is broken
+    let mut service_envs: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut secret_services: HashMap<String, Vec<String>> = HashMap::new();
+    let mut errors = Vec::new();
+    for svc in &sorted_services {
+        // Secret values are rendered as a Kubernetes Secret, never into the
+        // ConfigMap or the .env file
+        let secret_keys = secret_keys(&env_config, svc);
+        let ctx = InterpolationContext {
+            env_name,
+            service: svc,
+            services: &env_config.services,
+            secret_keys: &secret_keys,
+        };
+
+        match service_env(&env_config.shared_env, &ctx) {
+            Ok(mut env) => {
+                env.retain(|k, _| !secret_keys.contains(k));
+                service_envs.insert(svc.name.clone(), env);
+            }
+            Err(mut service_errors) => errors.append(&mut service_errors),
+        }
+        if !secret_keys.is_empty() {
+            secret_services.insert(svc.name.clone(), secret_keys);
+        }
+    }
+
+    if !errors.is_empty() {
+        let details: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        return Err(
+            AppError::Invalid(format!("Invalid env references: {}", details.join("; "))).into(),
+        );
+    }
+
     // Generate root Tiltfile using template
     let loads = sorted_services
         .iter()
@@ -168,8 +214,18 @@ pub fn generate_tiltfiles(project: &Project, env_name: &str) -> Result<()> {
 
     // Per-service Tiltfiles and additional files
     for svc in sorted_services {
-        let service_tiltfile_content =
-            generate_service_tiltfile(&env_config.shared_env, &svc, services_path);
+        let secret_keys = secret_services
+            .get(&svc.name)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let has_secrets = !secret_keys.is_empty();
+        let service_tiltfile_content = generate_service_tiltfile(
+            &env_config.shared_env,
+            &svc,
+            services_path,
+            env_name,
+            has_secrets,
+        );
         let service_file_path = services_dir.join(format!("{}.tilt.py", svc.name));
 
         fs::write(&service_file_path, service_tiltfile_content)
@@ -179,9 +235,10 @@ pub fn generate_tiltfiles(project: &Project, env_name: &str) -> Result<()> {
         generate_service_files(
             workspace,
             env_name,
-            &env_config.shared_env,
+            &service_envs[&svc.name],
             &svc,
             services_path,
+            secret_keys,
         )?;
     }
 
@@ -192,6 +249,8 @@ fn generate_service_tiltfile(
     shared_env: &HashMap<String, String>,
     svc: &Service,
     services_path: &str,
+    env_name: &str,
+    has_secrets: bool,
 ) -> String {
     let mut env: HashMap<String, String> = shared_env.clone();
     if let Some(svc_env) = &svc.env {
@@ -237,31 +296,96 @@ fn generate_service_tiltfile(
         String::new()
     };
 
-    // Build k8s_yaml section based on what's configured
-    let k8s_section = if let Some(helm) = &svc.helm {
-        format!(
-            r#"    helm(
+    // The YAML each deployment type produces, as a Tiltfile expression
+    let yaml = if let Some(helm) = &svc.helm {
+        // Scalar values become `--set key=value` flags, sorted for stable output
+        let mut set_values: Vec<String> = helm
+            .values
+            .iter()
+            .flatten()
+            .map(|(k, v)| match v {
+                serde_json::Value::String(s) => format!("{}={}", k, s),
+                other => format!("{}={}", k, other),
+            })
+            .collect();
+        set_values.sort();
+
+        let set_line = if set_values.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "        set={},\n",
+                serde_json::to_string(&set_values).unwrap()
+            )
+        };
+
+        let chart = match helm.chart.as_str() {
+            "." => format!("../../{}/{}", services_path, svc.name),
+            chart => match chart.strip_prefix("./") {
+                Some(dir) => format!("../../{}/{}/{}", services_path, svc.name, dir),
+                None => chart.to_string(),
+            },
+        };
+
+        Some(format!(
+            r#"helm(
         "{}",
         namespace="{}",
-    )
-"#,
-            helm.chart,
-            helm.namespace.as_deref().unwrap_or("default")
-        )
+{}    )"#,
+            chart,
+            helm.namespace.as_deref().unwrap_or("default"),
+            set_line
+        ))
     } else if let Some(kustomize) = &svc.kustomize {
-        format!(
-            r#"    k8s_yaml(kustomize("../../{}/{}/{}"))
-"#,
+        Some(format!(
+            r#"kustomize("../../{}/{}/{}")"#,
             services_path, svc.name, kustomize.path
-        )
+        ))
     } else if svc.k8s.is_some() {
+        let path = format!("../../{}/{}/{}", services_path, svc.name, manifests_path);
+        Some(match svc.replicas {
+            // Patching needs the YAML itself rather than a path
+            Some(_) if manifests_path.ends_with(".yaml") || manifests_path.ends_with(".yml") => {
+                format!(r#"read_file("{}")"#, path)
+            }
+            Some(_) => format!(
+                r#""\n---\n".join([str(read_file(f)) for f in listdir("{}") if f.endswith((".yaml", ".yml"))])"#,
+                path
+            ),
+            None => format!(r#""{}""#, path),
+        })
+    } else {
+        None
+    };
+
+    // Replicas are set here rather than in the manifests, which every
+    // environment shares
+    let (helpers, k8s_section) = match (yaml, svc.replicas) {
+        (Some(yaml), Some(replicas)) => (
+            WITH_REPLICAS_HELPER,
+            format!(
+                "    k8s_yaml(_with_replicas({}, \"{}\", {}))\n",
+                yaml, svc.name, replicas
+            ),
+        ),
+        (Some(yaml), None) => ("", format!("    k8s_yaml({})\n", yaml)),
+        (None, _) => ("", String::new()),
+    };
+
+    // Secrets are materialized to .tooling at start and turned into a Secret here,
+    // so their values never appear in a generated file
+    let k8s_section = if has_secrets && !k8s_section.is_empty() {
         format!(
-            r#"    k8s_yaml("../../{}/{}/{}")
+            r#"{}    k8s_yaml(local(
+        "kubectl create secret generic {}-secrets --from-env-file=../../.tooling/secrets/{}/{}.env --dry-run=client -o yaml",
+        quiet=True,
+        echo_off=True,
+    ))
 "#,
-            services_path, svc.name, manifests_path
+            k8s_section, svc.name, env_name, svc.name
         )
     } else {
-        String::new()
+        k8s_section
     };
 
     // Use template for service Tiltfile
@@ -270,6 +394,7 @@ fn generate_service_tiltfile(
         &[
             ("{{SERVICE_NAME}}", &svc.name),
             ("{{SERVICE_NAME_SNAKE}}", &svc.name.replace("-", "_")),
+            ("{{HELPERS}}", helpers),
             ("{{DOCKER_SECTION}}", &docker_section),
             ("{{K8S_SECTION}}", &k8s_section),
             ("{{PORT}}", &svc.port.to_string()),
@@ -284,20 +409,15 @@ fn generate_service_tiltfile(
 fn generate_service_files(
     workspace: &str,
     env_name: &str,
-    shared_env: &HashMap<String, String>,
+    env: &HashMap<String, String>,
     svc: &Service,
     services_path: &str,
+    secret_keys: &[String],
 ) -> Result<()> {
     let service_dir = Path::new(workspace).join(services_path).join(&svc.name);
 
-    // Merge shared and service env vars
-    let mut env: HashMap<String, String> = shared_env.clone();
-    if let Some(svc_env) = &svc.env {
-        env.extend(svc_env.clone());
-    }
-
     // Always generate .env file for the service
-    generate_env_file(&service_dir, env_name, &env)?;
+    generate_env_file(&service_dir, env_name, env)?;
 
     // If k8s is specified, generate k8s YAML file (with merged ConfigMap)
     if svc.k8s.is_some() {
@@ -306,7 +426,7 @@ fn generate_service_files(
             .with_context(|| format!("Failed to create k8s directory: {}", k8s_dir.display()))?;
 
         // Generate Deployment with embedded ConfigMap
-        generate_k8s_deployment(&k8s_dir, &svc.name, svc.port, &env)?;
+        generate_k8s_deployment(&k8s_dir, &svc.name, svc.port, secret_keys, env)?;
     }
 
     Ok(())
@@ -316,20 +436,31 @@ fn generate_k8s_deployment(
     k8s_dir: &Path,
     service_name: &str,
     port: u16,
+    secret_keys: &[String],
     env: &HashMap<String, String>,
 ) -> Result<()> {
     let deployment_path = k8s_dir.join(format!("{}-deployment.yaml", service_name));
 
-    // Only generate if file doesn't exist
+    let mut env_data: Vec<String> = env
+        .iter()
+        .map(|(k, v)| format!("  {}: \"{}\"", k, v.replace("\"", "\\\"")))
+        .collect();
+    env_data.sort();
+
+    // An edited deployment is kept, only its ConfigMap and secrets are updated
     if deployment_path.exists() {
-        return Ok(());
+        return patch_k8s_deployment(&deployment_path, service_name, secret_keys, &env_data);
     }
+    let env_data = env_data.join("\n");
 
-    let env_data = env
-        .iter()
-        .map(|(k, v)| format!("  {}: \"{}\"", k, v.replace("\"", "\\\"")))
-        .collect::<Vec<_>>()
-        .join("\n");
+    let secret_env_from = if !secret_keys.is_empty() {
+        format!(
+            "        - secretRef:\n            name: {}-secrets\n",
+            service_name
+        )
+    } else {
+        String::new()
+    };
 
     // Use template for Deployment with embedded ConfigMap
     let deployment = render_template(
@@ -338,6 +469,7 @@ fn generate_k8s_deployment(
             ("{{SERVICE_NAME}}", service_name),
             ("{{PORT}}", &port.to_string()),
             ("{{ENV_DATA}}", &env_data),
+            ("{{SECRET_ENV_FROM}}", &secret_env_from),
         ],
     );
 
@@ -347,6 +479,144 @@ fn generate_k8s_deployment(
     Ok(())
 }
 
+/// Point a deployment written before its service had secrets at the generated
+/// Secret, and drop the secret keys from its ConfigMap so their old plain-text
+/// values stop reaching the cluster. Everything else in the file is left as is.
+/// Move a renamed service's generated deployment to its new file name and
+/// rename the objects in it. Returns the old and new manifest paths, relative
+/// to the service directory, when there was one to move.
+pub fn rename_k8s_deployment(
+    service_dir: &Path,
+    old_name: &str,
+    new_name: &str,
+) -> Result<Option<(String, String)>> {
+    let old_file = format!("k8s/{}-deployment.yaml", old_name);
+    let new_file = format!("k8s/{}-deployment.yaml", new_name);
+    let old_path = service_dir.join(&old_file);
+    if !old_path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&old_path)
+        .with_context(|| format!("Failed to read deployment: {}", old_path.display()))?;
+    let renamed = [
+        (old_name.to_string(), new_name.to_string()),
+        (
+            format!("{}-config", old_name),
+            format!("{}-config", new_name),
+        ),
+        (
+            format!("{}-secrets", old_name),
+            format!("{}-secrets", new_name),
+        ),
+    ];
+
+    // Only the fields the template fills in with the service name
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let Some((key, value)) = line.split_once(": ") else {
+                return line.to_string();
+            };
+            let field = key.trim_start().trim_start_matches("- ");
+            if !matches!(field, "name" | "app" | "image") {
+                return line.to_string();
+            }
+            match renamed.iter().find(|(old, _)| value.trim() == old) {
+                Some((_, new)) => format!("{}: {}", key, new),
+                None => line.to_string(),
+            }
+        })
+        .collect();
+    if content.ends_with('\n') {
+        lines.push(String::new());
+    }
+
+    let new_path = service_dir.join(&new_file);
+    fs::write(&new_path, lines.join("\n"))
+        .with_context(|| format!("Failed to write deployment: {}", new_path.display()))?;
+    fs::remove_file(&old_path)
+        .with_context(|| format!("Failed to remove deployment: {}", old_path.display()))?;
+
+    Ok(Some((old_file, new_file)))
+}
+
+/// Bring an existing deployment up to date without losing edits to it: the
+/// ConfigMap data follows the env and the secrets get wired in
+fn patch_k8s_deployment(
+    path: &Path,
+    service_name: &str,
+    secret_keys: &[String],
+    env_data: &[String],
+) -> Result<()> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read deployment: {}", path.display()))?;
+    let config_name = format!("  name: {}-config", service_name);
+    let config_ref = format!("name: {}-config", service_name);
+    let secret_ref = format!("name: {}-secrets", service_name);
+
+    let mut referenced = secret_keys.is_empty() || content.contains(&secret_ref);
+    let mut in_config_map = false;
+    let mut in_config_data = false;
+    let mut in_config_ref = false;
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        if line.starts_with("---") {
+            in_config_map = false;
+        }
+        if in_config_data {
+            if line.starts_with(' ') {
+                continue;
+            }
+            in_config_data = false;
+        }
+        if line == config_name {
+            in_config_map = true;
+        }
+
+        lines.push(line.to_string());
+
+        if in_config_map && line == "data:" {
+            lines.extend(env_data.iter().cloned());
+            in_config_data = true;
+        }
+
+        // The secretRef goes in the same envFrom list as the configMapRef
+        if !referenced && in_config_ref && line.trim() == config_ref {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let item_indent = &indent[..indent.len().saturating_sub(4)];
+            lines.push(format!("{}- secretRef:", item_indent));
+            lines.push(format!("{}{}", indent, secret_ref));
+            referenced = true;
+        }
+        in_config_ref = line.trim() == "- configMapRef:";
+    }
+
+    if !referenced {
+        return Err(AppError::Invalid(format!(
+            "{} has no envFrom for {}-config to add its secrets to. Add a secretRef to {}-secrets by hand",
+            path.display(),
+            service_name,
+            service_name
+        ))
+        .into());
+    }
+
+    let mut patched = lines.join("\n");
+    if content.ends_with('\n') {
+        patched.push('\n');
+    }
+    if patched != content {
+        fs::write(path, patched)
+            .with_context(|| format!("Failed to write deployment: {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// `.env.<env>` of a service, rewritten on every generation so it follows the env.
+/// Secrets never go in it.
 fn generate_env_file(
     service_dir: &Path,
     env_name: &str,
@@ -354,18 +624,10 @@ fn generate_env_file(
 ) -> Result<()> {
     let env_file_path = service_dir.join(format!(".env.{}", env_name));
 
-    // Only generate if file doesn't exist
-    if env_file_path.exists() {
-        return Ok(());
-    }
-
-    let env_content = env
-        .iter()
-        .map(|(k, v)| format!("{}={}", k, v))
-        .collect::<Vec<_>>()
-        .join("\n");
+    let mut lines: Vec<String> = env.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    lines.sort();
 
-    fs::write(&env_file_path, env_content)
+    fs::write(&env_file_path, lines.join("\n"))
         .with_context(|| format!("Failed to write env file: {}", env_file_path.display()))?;
 
     Ok(())
@@ -375,7 +637,7 @@ pub fn reorder_services(project: &mut Project, env: &str, new_order: Vec<String>
     let env_config = project
         .environments
         .get_mut(env)
-        .ok_or_else(|| anyhow::anyhow!("Environment {} not found", env))?;
+        .ok_or_else(|| AppError::env_not_found(env))?;
 
     env_config.services.sort_by(|a, b| {
         let a_idx = new_order
@@ -398,10 +660,9 @@ pub fn reorder_services(project: &mut Project, env: &str, new_order: Vec<String>
         .collect();
     if let Some(cycle) = detect_cycles(&enabled_services) {
         let cycle_str: String = cycle.join(" -> ");
-        return Err(anyhow::anyhow!(
-            "Invalid reorder: cycle detected {}",
-            cycle_str
-        ));
+        return Err(
+            AppError::Invalid(format!("Invalid reorder: cycle detected {}", cycle_str)).into(),
+        );
     }
 
     // Regenerate Tiltfiles
@@ -1,14 +1,141 @@
-use std::process::Command;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-pub async fn clone_repo(url: &str, target_dir: &str, branch: Option<&str>) -> std::io::Result<()> {
+use crate::backend::errors::AppError;
+use crate::backend::jobs::{JobHandle, RepoStatus};
+use crate::backend::process::{ProcessEvent, ProcessRunner};
+
+/// A service repository to clone into the workspace
+#[derive(Debug, Clone)]
+pub struct RepoCheckout {
+    pub service: String,
+    pub url: String,
+    pub branch: Option<String>,
+    pub dir: PathBuf,
+}
+
+pub async fn clone_repo<R: ProcessRunner>(
+    runner: &R,
+    url: &str,
+    target_dir: &str,
+    branch: Option<&str>,
+) -> Result<(), AppError> {
+    let branch = branch.unwrap_or("main");
+    let status = runner
+        .status("git", &["clone", url, target_dir, "--branch", branch], None)
+        .await?;
+
+    if status == Some(0) {
+        Ok(())
+    } else {
+        Err(AppError::GitFailed {
+            url: url.to_string(),
+        })
+    }
+}
+
+/// Clone like `clone_repo` as part of a job. Git's output goes to the job's
+/// log; cancelling the job kills git and removes the partial clone.
+pub async fn clone_repo_in_job<R: ProcessRunner>(
+    runner: &R,
+    url: &str,
+    target_dir: &Path,
+    branch: Option<&str>,
+    job: &JobHandle,
+) -> Result<(), AppError> {
+    job.check_cancelled()?;
+
+    let target = target_dir
+        .to_str()
+        .ok_or_else(|| AppError::Invalid(format!("Invalid clone path {}", target_dir.display())))?;
+    // Git only clones into a missing or empty directory, which makes it safe to remove
+    let fresh = target_dir
+        .read_dir()
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(true);
     let branch = branch.unwrap_or("main");
-    let status = Command::new("git")
-        .args(["clone", url, target_dir, "--branch", branch])
-        .status()?;
 
-    if status.success() {
+    let mut process = runner.spawn(
+        "git",
+        &["clone", "--progress", url, target, "--branch", branch],
+        None,
+    )?;
+
+    let code = loop {
+        tokio::select! {
+            event = process.events.recv() => match event {
+                Some(ProcessEvent::Stdout(line) | ProcessEvent::Stderr(line)) => {
+                    // Progress counters rewrite the line, keep where they ended
+                    job.log(line.rsplit('\r').next().unwrap_or_default());
+                }
+                Some(ProcessEvent::Terminated(code)) => break code,
+                None => break None,
+            },
+            _ = job.cancel_token().cancelled() => {
+                let _ = runner.kill(process.pid).await;
+                if fresh {
+                    let _ = fs::remove_dir_all(target_dir);
+                }
+                return Err(AppError::Cancelled);
+            }
+        }
+    };
+
+    if code == Some(0) {
         Ok(())
     } else {
-        Err(std::io::Error::other("Git clone failed"))
+        Err(AppError::GitFailed {
+            url: url.to_string(),
+        })
+    }
+}
+
+/// Clone every checkout for a job, reporting each repository's status.
+/// A failed clone does not stop the others; the first failure is returned
+/// once all have been tried.
+pub async fn clone_repos_in_job<R: ProcessRunner>(
+    runner: &R,
+    checkouts: &[RepoCheckout],
+    job: &JobHandle,
+) -> Result<(), AppError> {
+    job.set_repos(
+        checkouts
+            .iter()
+            .map(|checkout| (checkout.service.clone(), checkout.url.clone())),
+    );
+
+    let total = checkouts.len() as u32;
+    let mut first_error = None;
+    for (done, checkout) in checkouts.iter().enumerate() {
+        if job.is_cancelled() {
+            job.repo_status(&checkout.service, RepoStatus::Cancelled, None);
+            continue;
+        }
+
+        job.step(format!("Cloning {}", checkout.service));
+        job.repo_status(&checkout.service, RepoStatus::Cloning, None);
+        let result = clone_repo_in_job(
+            runner,
+            &checkout.url,
+            &checkout.dir,
+            checkout.branch.as_deref(),
+            job,
+        )
+        .await;
+
+        match result {
+            Ok(()) => job.repo_status(&checkout.service, RepoStatus::Cloned, None),
+            Err(AppError::Cancelled) => {
+                job.repo_status(&checkout.service, RepoStatus::Cancelled, None)
+            }
+            Err(e) => {
+                job.repo_status(&checkout.service, RepoStatus::Failed, Some(e.to_string()));
+                first_error.get_or_insert(e);
+            }
+        }
+        job.progress(done as u32 + 1, total);
     }
+
+    job.check_cancelled()?;
+    first_error.map_or(Ok(()), Err)
 }
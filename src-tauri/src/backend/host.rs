@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::io;
+use std::path::PathBuf;
+
+use crate::app_state::model::Preferences;
+use crate::app_state::{app_data_dir, load_state_from};
+use crate::backend::notifier::{notify_resource_changes, notify_tilt_exited};
+use crate::backend::process::{ProcessRunner, ShellRunner};
+use crate::backend::tilt_resources::UiResource;
+use crate::tray_icon::tray_manager::set_tray_health;
+
+/// Overall health of an environment, shown as a badge on the tray icon
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TrayHealth {
+    #[default]
+    Idle,
+    Starting,
+    Healthy,
+    Degraded,
+    Failed,
+}
+
+impl TrayHealth {
+    /// Health of a running Tilt from its resources
+    pub fn from_resources(resources: &[UiResource]) -> Self {
+        let failing = |r: &&UiResource| {
+            r.status.update_status == "error" || r.status.runtime_status == "error"
+        };
+        let failed = resources.iter().filter(failing).count();
+        let pending = resources.iter().any(|r| {
+            matches!(r.status.update_status.as_str(), "pending" | "in_progress")
+                || r.status.runtime_status == "pending"
+        });
+
+        match failed {
+            _ if resources.is_empty() => TrayHealth::Starting,
+            0 if pending => TrayHealth::Starting,
+            0 => TrayHealth::Healthy,
+            n if n == resources.len() => TrayHealth::Failed,
+            _ => TrayHealth::Degraded,
+        }
+    }
+}
+
+/// What the Tilt lifecycle needs from the program running it: a process runner,
+/// the per-user data dir and a place to report what happened.
+/// The desktop app implements it on `AppHandle`, the CLI on its own host.
+pub trait TiltHost: Clone + Send + Sync + 'static {
+    type Runner: ProcessRunner;
+
+    /// Holds the app state, the secrets key and other per-user data
+    fn data_dir(&self) -> io::Result<PathBuf>;
+
+    fn preferences(&self) -> Preferences {
+        self.data_dir()
+            .map(|dir| load_state_from(&dir).preferences)
+            .unwrap_or_default()
+    }
+
+    /// Runs Tilt and the commands talking to it
+    fn runner(&self) -> Self::Runner;
+
+    /// Tilt exited without being asked to
+    fn tilt_exited(
+        &self,
+        workspace: &str,
+        env: &str,
+        exit_code: Option<i32>,
+    ) -> impl Future<Output = ()> + Send;
+
+    /// Overall health of an environment changed
+    fn health_changed(
+        &self,
+        workspace: &str,
+        env: &str,
+        health: TrayHealth,
+    ) -> impl Future<Output = ()> + Send;
+
+    /// The resources of a running Tilt, read on every poll of its watcher
+    fn resources_changed(
+        &self,
+        workspace: &str,
+        env: &str,
+        resources: &[UiResource],
+    ) -> impl Future<Output = ()> + Send;
+}
+
+impl TiltHost for tauri::AppHandle {
+    type Runner = ShellRunner;
+
+    fn data_dir(&self) -> io::Result<PathBuf> {
+        app_data_dir(self).map_err(|e| io::Error::other(e.to_string()))
+    }
+
+    fn runner(&self) -> ShellRunner {
+        ShellRunner::new(self.clone())
+    }
+
+    async fn tilt_exited(&self, workspace: &str, env: &str, exit_code: Option<i32>) {
+        notify_tilt_exited(self, workspace, env, exit_code);
+        set_tray_health(self, workspace, env, TrayHealth::Failed).await;
+    }
+
+    async fn health_changed(&self, workspace: &str, env: &str, health: TrayHealth) {
+        set_tray_health(self, workspace, env, health).await;
+    }
+
+    async fn resources_changed(&self, workspace: &str, env: &str, resources: &[UiResource]) {
+        notify_resource_changes(self, workspace, env, resources);
+    }
+}
@@ -0,0 +1,319 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use ts_rs::TS;
+
+use crate::backend::errors::AppError;
+use crate::backend::project::{Environment, Project, SecretRef, Service, ServiceOverride};
+
+/// Where an effective value was set
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueKind {
+    /// Defined directly in the environment's `shared_env` or `services`
+    Defined,
+    /// Set by an entry in the environment's `overrides`
+    Override,
+}
+
+/// A single effective value and the environment it came from
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ValueOrigin {
+    /// Dotted path such as `shared_env.LOG_LEVEL` or `services.api.port`
+    pub key: String,
+    pub value: serde_json::Value,
+    pub env: String,
+    pub kind: ValueKind,
+}
+
+/// Walk `extends` from `env_name` up to the root, returning the chain root first
+pub fn inheritance_chain(project: &Project, env_name: &str) -> Result<Vec<String>> {
+    let mut chain = Vec::new();
+    let mut seen = HashSet::new();
+    let mut current = Some(env_name.to_string());
+
+    while let Some(name) = current {
+        if !seen.insert(name.clone()) {
+            chain.push(name);
+            chain.reverse();
+            return Err(AppError::Invalid(format!(
+                "Environment inheritance cycle detected: {}",
+                chain.join(" -> ")
+            ))
+            .into());
+        }
+
+        let env = project
+            .environments
+            .get(&name)
+            .ok_or_else(|| AppError::env_not_found(&name))?;
+
+        current = env.extends.clone();
+        chain.push(name);
+    }
+
+    chain.reverse();
+    Ok(chain)
+}
+
+/// Compute the effective environment after applying every parent and override
+pub fn resolve_environment(project: &Project, env_name: &str) -> Result<Environment> {
+    resolve(project, env_name).map(|(env, _)| env)
+}
+
+/// List every effective value of an environment with the environment that set it
+pub fn explain_environment(project: &Project, env_name: &str) -> Result<Vec<ValueOrigin>> {
+    let (_, origins) = resolve(project, env_name)?;
+
+    let mut origins: Vec<ValueOrigin> = origins.into_values().collect();
+    origins.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(origins)
+}
+
+fn resolve(
+    project: &Project,
+    env_name: &str,
+) -> Result<(Environment, HashMap<String, ValueOrigin>)> {
+    let chain = inheritance_chain(project, env_name)?;
+
+    let mut shared_env = HashMap::new();
+    let mut secrets: Vec<SecretRef> = Vec::new();
+    let mut services: Vec<Service> = Vec::new();
+    let mut origins = HashMap::new();
+
+    for name in &chain {
+        let env = &project.environments[name];
+
+        for (key, value) in &env.shared_env {
+            shared_env.insert(key.clone(), value.clone());
+            record(
+                &mut origins,
+                format!("shared_env.{}", key),
+                value,
+                name,
+                ValueKind::Defined,
+            );
+        }
+
+        for secret in env.secrets.iter().flatten() {
+            secrets.retain(|s| s.key != secret.key);
+            secrets.push(secret.clone());
+        }
+
+        // Services redefined in a child replace the inherited definition entirely
+        for svc in &env.services {
+            let prefix = format!("services.{}.", svc.name);
+            origins.retain(|k, _| !k.starts_with(&prefix));
+
+            match services.iter_mut().find(|s| s.name == svc.name) {
+                Some(existing) => *existing = svc.clone(),
+                None => services.push(svc.clone()),
+            }
+
+            record_service(&mut origins, svc, name);
+        }
+
+        if let Some(overrides) = &env.overrides {
+            for (service_name, patch) in overrides {
+                let svc = services
+                    .iter_mut()
+                    .find(|s| &s.name == service_name)
+                    .ok_or_else(|| {
+                        AppError::Invalid(format!(
+                            "Environment {} overrides unknown service {}",
+                            name, service_name
+                        ))
+                    })?;
+
+                apply_override(svc, patch, &mut origins, name)?;
+            }
+        }
+    }
+
+    // A key marked secret anywhere in the chain never keeps an inherited plain value
+    shared_env.retain(|k, _| !secrets.iter().any(|s| &s.key == k));
+
+    let environment = Environment {
+        shared_env,
+        services,
+        extends: None,
+        overrides: None,
+        secrets: (!secrets.is_empty()).then_some(secrets),
+    };
+
+    Ok((environment, origins))
+}
+
+fn apply_override(
+    svc: &mut Service,
+    patch: &ServiceOverride,
+    origins: &mut HashMap<String, ValueOrigin>,
+    env_name: &str,
+) -> Result<()> {
+    let prefix = format!("services.{}", svc.name);
+
+    if let Some(port) = patch.port {
+        svc.port = port;
+        record(
+            origins,
+            format!("{}.port", prefix),
+            &port,
+            env_name,
+            ValueKind::Override,
+        );
+    }
+
+    if let Some(enabled) = patch.enabled {
+        svc.enabled = enabled;
+        record(
+            origins,
+            format!("{}.enabled", prefix),
+            &enabled,
+            env_name,
+            ValueKind::Override,
+        );
+    }
+
+    if let Some(replicas) = patch.replicas {
+        svc.replicas = Some(replicas);
+        record(
+            origins,
+            format!("{}.replicas", prefix),
+            &replicas,
+            env_name,
+            ValueKind::Override,
+        );
+    }
+
+    if let Some(branch) = &patch.branch {
+        let repo = svc.repo.as_mut().ok_or_else(|| {
+            AppError::Invalid(format!(
+                "Environment {} overrides the branch of {}, which has no repo",
+                env_name, svc.name
+            ))
+        })?;
+        repo.branch = Some(branch.clone());
+        record(
+            origins,
+            format!("{}.branch", prefix),
+            branch,
+            env_name,
+            ValueKind::Override,
+        );
+    }
+
+    if let Some(env) = &patch.env {
+        let svc_env = svc.env.get_or_insert_with(HashMap::new);
+        for (key, value) in env {
+            svc_env.insert(key.clone(), value.clone());
+            record(
+                origins,
+                format!("{}.env.{}", prefix, key),
+                value,
+                env_name,
+                ValueKind::Override,
+            );
+        }
+    }
+
+    if let Some(values) = &patch.helm_values {
+        let helm = svc.helm.as_mut().ok_or_else(|| {
+            AppError::Invalid(format!(
+                "Environment {} overrides the Helm values of {}, which has no Helm chart",
+                env_name, svc.name
+            ))
+        })?;
+        let helm_values = helm.values.get_or_insert_with(HashMap::new);
+        for (key, value) in values {
+            helm_values.insert(key.clone(), value.clone());
+            record(
+                origins,
+                format!("{}.helm.values.{}", prefix, key),
+                value,
+                env_name,
+                ValueKind::Override,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn record_service(origins: &mut HashMap<String, ValueOrigin>, svc: &Service, env_name: &str) {
+    let prefix = format!("services.{}", svc.name);
+
+    record(
+        origins,
+        format!("{}.port", prefix),
+        &svc.port,
+        env_name,
+        ValueKind::Defined,
+    );
+    record(
+        origins,
+        format!("{}.enabled", prefix),
+        &svc.enabled,
+        env_name,
+        ValueKind::Defined,
+    );
+
+    if let Some(replicas) = svc.replicas {
+        record(
+            origins,
+            format!("{}.replicas", prefix),
+            &replicas,
+            env_name,
+            ValueKind::Defined,
+        );
+    }
+
+    if let Some(branch) = svc.repo.as_ref().and_then(|r| r.branch.as_ref()) {
+        record(
+            origins,
+            format!("{}.branch", prefix),
+            branch,
+            env_name,
+            ValueKind::Defined,
+        );
+    }
+
+    for (key, value) in svc.env.iter().flatten() {
+        record(
+            origins,
+            format!("{}.env.{}", prefix, key),
+            value,
+            env_name,
+            ValueKind::Defined,
+        );
+    }
+
+    let helm_values = svc.helm.as_ref().and_then(|h| h.values.as_ref());
+    for (key, value) in helm_values.into_iter().flatten() {
+        record(
+            origins,
+            format!("{}.helm.values.{}", prefix, key),
+            value,
+            env_name,
+            ValueKind::Defined,
+        );
+    }
+}
+
+fn record<T: Serialize>(
+    origins: &mut HashMap<String, ValueOrigin>,
+    key: String,
+    value: &T,
+    env_name: &str,
+    kind: ValueKind,
+) {
+    let value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+    origins.insert(
+        key.clone(),
+        ValueOrigin {
+            key,
+            value,
+            env: env_name.to_string(),
+            kind,
+        },
+    );
+}
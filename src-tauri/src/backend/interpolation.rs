@@ -0,0 +1,243 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use ts_rs::TS;
+
+use crate::backend::inheritance::resolve_environment;
+use crate::backend::project::{Environment, Project, Service};
+
+/// A reference that could not be resolved, reported per env key
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct InterpolationError {
+    pub service: String,
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for InterpolationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}: {}", self.service, self.key, self.message)
+    }
+}
+
+/// What `${...}` references can see besides the other keys of the merged map
+pub struct InterpolationContext<'a> {
+    pub env_name: &'a str,
+    pub service: &'a Service,
+    pub services: &'a [Service],
+    /// Keys whose values are secrets, see [`secret_keys`]
+    pub secret_keys: &'a [String],
+}
+
+/// Keys stored as secrets for a service, by its environment or itself.
+/// Their values only reach the service at runtime, so they cannot be referenced.
+pub fn secret_keys(environment: &Environment, service: &Service) -> Vec<String> {
+    environment
+        .secrets
+        .iter()
+        .flatten()
+        .chain(service.secrets.iter().flatten())
+        .map(|s| s.key.clone())
+        .collect()
+}
+
+impl InterpolationContext<'_> {
+    /// `${service.name}`, `${service.port}`, `${env.name}` and `${services.<name>.port}`
+    fn special(&self, name: &str) -> Option<String> {
+        match name {
+            "service.name" => Some(self.service.name.clone()),
+            "service.port" => Some(self.service.port.to_string()),
+            "env.name" => Some(self.env_name.to_string()),
+            _ => {
+                let service_name = name.strip_prefix("services.")?.strip_suffix(".port")?;
+                self.services
+                    .iter()
+                    .find(|s| s.name == service_name)
+                    .map(|s| s.port.to_string())
+            }
+        }
+    }
+}
+
+/// Resolve `${VAR}` and `${VAR:-default}` references in a merged env map.
+///
+/// `$${` is kept as a literal `${`. Every key that fails is reported, so a
+/// single call surfaces all undefined references and cycles.
+pub fn interpolate_env(
+    vars: &HashMap<String, String>,
+    ctx: &InterpolationContext,
+) -> Result<HashMap<String, String>, Vec<InterpolationError>> {
+    let mut resolver = Resolver {
+        vars,
+        ctx,
+        resolved: HashMap::new(),
+        stack: Vec::new(),
+    };
+
+    let mut keys: Vec<&String> = vars.keys().collect();
+    keys.sort();
+
+    let mut errors = Vec::new();
+    for key in keys {
+        resolver.stack.clear();
+        if let Err(message) = resolver.resolve_key(key) {
+            errors.push(InterpolationError {
+                service: ctx.service.name.clone(),
+                key: key.clone(),
+                message,
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(resolver.resolved)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Merge shared and service env vars and resolve references for one service
+pub fn service_env(
+    shared_env: &HashMap<String, String>,
+    ctx: &InterpolationContext,
+) -> Result<HashMap<String, String>, Vec<InterpolationError>> {
+    let mut env = shared_env.clone();
+    if let Some(svc_env) = &ctx.service.env {
+        env.extend(svc_env.clone());
+    }
+
+    interpolate_env(&env, ctx)
+}
+
+/// Check every service of an environment for undefined references and cycles
+pub fn validate_interpolation(
+    project: &Project,
+    env_name: &str,
+) -> Result<Vec<InterpolationError>> {
+    let environment = resolve_environment(project, env_name)?;
+
+    let mut errors = Vec::new();
+    for svc in &environment.services {
+        let secret_keys = secret_keys(&environment, svc);
+        let ctx = InterpolationContext {
+            env_name,
+            service: svc,
+            services: &environment.services,
+            secret_keys: &secret_keys,
+        };
+
+        if let Err(mut service_errors) = service_env(&environment.shared_env, &ctx) {
+            errors.append(&mut service_errors);
+        }
+    }
+
+    Ok(errors)
+}
+
+struct Resolver<'a> {
+    vars: &'a HashMap<String, String>,
+    ctx: &'a InterpolationContext<'a>,
+    resolved: HashMap<String, String>,
+    stack: Vec<String>,
+}
+
+impl Resolver<'_> {
+    fn resolve_key(&mut self, key: &str) -> Result<String, String> {
+        if let Some(value) = self.resolved.get(key) {
+            return Ok(value.clone());
+        }
+
+        if let Some(pos) = self.stack.iter().position(|k| k == key) {
+            let mut cycle = self.stack[pos..].to_vec();
+            cycle.push(key.to_string());
+            return Err(format!("Reference cycle: {}", cycle.join(" -> ")));
+        }
+
+        self.stack.push(key.to_string());
+        let raw = self.vars[key].clone();
+        let value = self.expand(&raw)?;
+        self.stack.pop();
+
+        self.resolved.insert(key.to_string(), value.clone());
+        Ok(value)
+    }
+
+    fn expand(&mut self, input: &str) -> Result<String, String> {
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+
+        while let Some(pos) = rest.find('$') {
+            out.push_str(&rest[..pos]);
+            let after = &rest[pos..];
+
+            if let Some(tail) = after.strip_prefix("$${") {
+                out.push_str("${");
+                rest = tail;
+            } else if let Some(body) = after.strip_prefix("${") {
+                let end = matching_brace(body)
+                    .ok_or_else(|| format!("Unterminated reference in '{}'", input))?;
+                let inner = &body[..end];
+
+                let (name, default) = match inner.split_once(":-") {
+                    Some((name, default)) => (name.trim(), Some(default)),
+                    None => (inner.trim(), None),
+                };
+
+                out.push_str(&self.lookup(name, default)?);
+                rest = &body[end + 1..];
+            } else {
+                out.push('$');
+                rest = &after[1..];
+            }
+        }
+
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    fn lookup(&mut self, name: &str, default: Option<&str>) -> Result<String, String> {
+        if name.is_empty() {
+            return Err("Empty reference '${}'".to_string());
+        }
+
+        // Interpolating would copy the secret into the ConfigMap and .env file
+        if self.ctx.secret_keys.iter().any(|k| k == name) {
+            return Err(format!(
+                "${{{}}} refers to a secret, which is only passed to the service at runtime",
+                name
+            ));
+        }
+
+        let value = if self.vars.contains_key(name) {
+            Some(self.resolve_key(name)?)
+        } else {
+            self.ctx.special(name)
+        };
+
+        match (value, default) {
+            (Some(v), Some(d)) if v.is_empty() => self.expand(d),
+            (Some(v), _) => Ok(v),
+            (None, Some(d)) => self.expand(d),
+            (None, None) => Err(format!("Undefined variable ${{{}}}", name)),
+        }
+    }
+}
+
+/// Index of the `}` closing a reference body, allowing nested `${...}` in defaults
+fn matching_brace(body: &str) -> Option<usize> {
+    let mut depth = 1;
+    for (i, c) in body.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
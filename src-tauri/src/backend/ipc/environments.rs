@@ -0,0 +1,183 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::IpcCommand;
+use crate::app_state::app_data_dir;
+use crate::backend::inheritance::{explain_environment, resolve_environment, ValueOrigin};
+use crate::backend::interpolation::{validate_interpolation, InterpolationError};
+use crate::backend::project::{Environment, Project};
+use crate::backend::promotion::{diff_environments, EnvDiff, PromoteSelection};
+use crate::project::environments::{
+    clone_environment, create_environment, delete_environment, promote_environment,
+    rename_environment,
+};
+
+#[derive(Deserialize, TS)]
+pub struct ResolveEnvironment {
+    project: Project,
+    env: String,
+}
+
+impl IpcCommand for ResolveEnvironment {
+    const NAME: &'static str = "resolveEnvironment";
+    type Response = Environment;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Environment> {
+        resolve_environment(&self.project, &self.env)
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct ExplainEnvironment {
+    project: Project,
+    env: String,
+}
+
+impl IpcCommand for ExplainEnvironment {
+    const NAME: &'static str = "explainEnvironment";
+    type Response = Vec<ValueOrigin>;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Vec<ValueOrigin>> {
+        explain_environment(&self.project, &self.env)
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct ValidateInterpolation {
+    project: Project,
+    env: String,
+}
+
+#[derive(Serialize, TS)]
+pub struct InterpolationReport {
+    valid: bool,
+    errors: Vec<InterpolationError>,
+}
+
+impl IpcCommand for ValidateInterpolation {
+    const NAME: &'static str = "validateInterpolation";
+    type Response = InterpolationReport;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<InterpolationReport> {
+        let errors = validate_interpolation(&self.project, &self.env)?;
+        Ok(InterpolationReport {
+            valid: errors.is_empty(),
+            errors,
+        })
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct CreateEnvironment {
+    workspace_path: String,
+    env: String,
+}
+
+impl IpcCommand for CreateEnvironment {
+    const NAME: &'static str = "createEnvironment";
+    type Response = Project;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Project> {
+        create_environment(&self.workspace_path, &self.env).context("Failed to create environment")
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct CloneEnvironment {
+    workspace_path: String,
+    source: String,
+    target: String,
+}
+
+impl IpcCommand for CloneEnvironment {
+    const NAME: &'static str = "cloneEnvironment";
+    type Response = Project;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Project> {
+        clone_environment(
+            &app_data_dir(&app)?,
+            &self.workspace_path,
+            &self.source,
+            &self.target,
+        )
+        .context("Failed to clone environment")
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct RenameEnvironment {
+    workspace_path: String,
+    env: String,
+    new_name: String,
+}
+
+impl IpcCommand for RenameEnvironment {
+    const NAME: &'static str = "renameEnvironment";
+    type Response = Project;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Project> {
+        rename_environment(
+            &app_data_dir(&app)?,
+            &self.workspace_path,
+            &self.env,
+            &self.new_name,
+        )
+        .context("Failed to rename environment")
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct DeleteEnvironment {
+    workspace_path: String,
+    env: String,
+}
+
+impl IpcCommand for DeleteEnvironment {
+    const NAME: &'static str = "deleteEnvironment";
+    type Response = Project;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Project> {
+        delete_environment(&app_data_dir(&app)?, &self.workspace_path, &self.env)
+            .context("Failed to delete environment")
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct DiffEnvironments {
+    project: Project,
+    source: String,
+    target: String,
+}
+
+impl IpcCommand for DiffEnvironments {
+    const NAME: &'static str = "diffEnvironments";
+    type Response = EnvDiff;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<EnvDiff> {
+        diff_environments(&self.project, &self.source, &self.target)
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct PromoteEnvironment {
+    workspace_path: String,
+    source: String,
+    target: String,
+    selection: PromoteSelection,
+}
+
+impl IpcCommand for PromoteEnvironment {
+    const NAME: &'static str = "promoteEnvironment";
+    type Response = Project;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Project> {
+        promote_environment(
+            &self.workspace_path,
+            &self.source,
+            &self.target,
+            &self.selection,
+        )
+        .context("Failed to promote changes")
+    }
+}
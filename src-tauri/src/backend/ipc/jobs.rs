@@ -0,0 +1,61 @@
+use anyhow::Result;
+use serde::Deserialize;
+use ts_rs::TS;
+
+use super::IpcCommand;
+use crate::backend::jobs::{cancel_job, get_job, job_logs, list_jobs, Job};
+
+/// Running jobs and the ones finished since the app started
+#[derive(Deserialize, TS)]
+pub struct ListJobs {}
+
+impl IpcCommand for ListJobs {
+    const NAME: &'static str = "listJobs";
+    type Response = Vec<Job>;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Vec<Job>> {
+        Ok(list_jobs())
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct GetJob {
+    job_id: String,
+}
+
+impl IpcCommand for GetJob {
+    const NAME: &'static str = "getJob";
+    type Response = Job;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Job> {
+        Ok(get_job(&self.job_id)?)
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct GetJobLogs {
+    job_id: String,
+}
+
+impl IpcCommand for GetJobLogs {
+    const NAME: &'static str = "getJobLogs";
+    type Response = Vec<String>;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Vec<String>> {
+        Ok(job_logs(&self.job_id)?)
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct CancelJob {
+    job_id: String,
+}
+
+impl IpcCommand for CancelJob {
+    const NAME: &'static str = "cancelJob";
+    type Response = Job;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Job> {
+        Ok(cancel_job(&self.job_id)?)
+    }
+}
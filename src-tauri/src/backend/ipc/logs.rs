@@ -0,0 +1,172 @@
+use anyhow::Result;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+use ts_rs::TS;
+
+use super::{log_redactor, IpcCommand};
+use crate::backend::errors::AppError;
+use crate::backend::log_records::LogFilter;
+use crate::backend::log_search::{
+    search_logs, LogSearchBatch, SearchTarget, DEFAULT_BATCH_SIZE, DEFAULT_CONTEXT_LINES,
+};
+use crate::backend::log_store::LogSession;
+use crate::backend::project::Project;
+use crate::backend::session_history::{read_history, SessionRecord};
+use crate::backend::tilt_manager::{
+    get_tilt_logs, list_log_sessions, read_state, LogQuery, TiltLog,
+};
+
+#[derive(Deserialize, TS)]
+pub struct GetTiltLogs {
+    project: Project,
+    env: String,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    query: LogQuery,
+}
+
+impl IpcCommand for GetTiltLogs {
+    const NAME: &'static str = "getTiltLogs";
+    type Response = TiltLog;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<TiltLog> {
+        let redactor = log_redactor(&app, &self.project, &self.env)?;
+        Ok(get_tilt_logs(
+            &self.project.project.workspace_path,
+            &self.env,
+            &self.query,
+            &redactor,
+        )?)
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct ListLogSessions {
+    project: Project,
+    env: String,
+}
+
+impl IpcCommand for ListLogSessions {
+    const NAME: &'static str = "listLogSessions";
+    type Response = Vec<LogSession>;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Vec<LogSession>> {
+        Ok(list_log_sessions(
+            &self.project.project.workspace_path,
+            &self.env,
+        )?)
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct GetSessionHistory {
+    project: Project,
+    env: String,
+}
+
+impl IpcCommand for GetSessionHistory {
+    const NAME: &'static str = "getSessionHistory";
+    type Response = Vec<SessionRecord>;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Vec<SessionRecord>> {
+        Ok(read_history(
+            &self.project.project.workspace_path,
+            &self.env,
+        ))
+    }
+}
+
+#[derive(Deserialize, TS)]
+#[ts(optional_fields)]
+pub struct SearchLogs {
+    project: Project,
+    /// Every environment of the project when not set
+    env: Option<String>,
+    context: Option<usize>,
+    batch_size: Option<usize>,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    filter: LogFilter,
+}
+
+#[derive(Serialize, TS)]
+pub struct SearchStarted {
+    search_id: String,
+}
+
+impl IpcCommand for SearchLogs {
+    const NAME: &'static str = "searchLogs";
+    type Response = SearchStarted;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<SearchStarted> {
+        if self.filter.text.is_none() && self.filter.pattern.is_none() {
+            return Err(AppError::Invalid("Log search needs text or a pattern".to_string()).into());
+        }
+
+        let filter = self.filter.compile()?;
+        let workspace = self.project.project.workspace_path.clone();
+        let envs = match self.env {
+            Some(env) => vec![env],
+            None => {
+                let mut envs: Vec<String> = self.project.environments.keys().cloned().collect();
+                envs.sort();
+                envs
+            }
+        };
+
+        let targets = envs
+            .into_iter()
+            .map(|env| {
+                Ok(SearchTarget {
+                    current_session: read_state(&workspace, &env).tilt.session,
+                    redactor: log_redactor(&app, &self.project, &env)?,
+                    env,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let search_id = Local::now().format("%Y%m%d%H%M%S%f").to_string();
+        let context = self.context.unwrap_or(DEFAULT_CONTEXT_LINES);
+        let batch_size = self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+
+        // Results arrive as `log-search-batch` events, the last one has `done` set
+        let id = search_id.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut total = 0;
+            let result = search_logs(
+                &workspace,
+                &targets,
+                &filter,
+                context,
+                batch_size,
+                |matches| {
+                    total += matches.len();
+                    let _ = app.emit(
+                        "log-search-batch",
+                        LogSearchBatch {
+                            search_id: id.clone(),
+                            matches,
+                            total,
+                            done: false,
+                            error: None,
+                        },
+                    );
+                },
+            );
+
+            let _ = app.emit(
+                "log-search-batch",
+                LogSearchBatch {
+                    search_id: id.clone(),
+                    matches: Vec::new(),
+                    total: result.as_ref().copied().unwrap_or(total),
+                    done: true,
+                    error: result.err().map(|e| e.to_string()),
+                },
+            );
+        });
+
+        Ok(SearchStarted { search_id })
+    }
+}
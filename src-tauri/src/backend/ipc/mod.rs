@@ -0,0 +1,242 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use ts_rs::{ExportError, TypeVisitor, TS};
+
+use crate::app_state::{app_data_dir, load_state};
+use crate::backend::errors::{AppError, ErrorCode, IpcError};
+use crate::backend::project::Project;
+use crate::backend::redaction::{build_redactor, Redactor};
+
+mod environments;
+mod jobs;
+mod logs;
+mod projects;
+mod secrets;
+mod tilt;
+
+use environments::*;
+use jobs::*;
+use logs::*;
+use projects::*;
+use secrets::*;
+use tilt::*;
+
+/// A command the frontend calls through `call_backend`, also served on the
+/// control socket. The request type holds the arguments and is named after
+/// the command; both types are exported to TypeScript.
+pub trait IpcCommand: DeserializeOwned + TS + Send + 'static {
+    /// Name the frontend calls it by
+    const NAME: &'static str;
+
+    type Response: Serialize + TS + 'static;
+
+    fn run(self, app: tauri::AppHandle) -> impl Future<Output = Result<Self::Response>> + Send;
+}
+
+/// A registered command with the TypeScript names of its types
+#[derive(Serialize, Debug, Clone, TS)]
+pub struct CommandInfo {
+    pub name: String,
+    pub request: String,
+    pub response: String,
+}
+
+impl CommandInfo {
+    fn of<C: IpcCommand>() -> Self {
+        CommandInfo {
+            name: C::NAME.to_string(),
+            request: C::name(),
+            response: C::Response::name(),
+        }
+    }
+}
+
+async fn dispatch<C: IpcCommand>(
+    app: tauri::AppHandle,
+    args: serde_json::Value,
+) -> Result<serde_json::Value, IpcError> {
+    let request: C = serde_json::from_value(args).map_err(|e| {
+        IpcError::new(
+            ErrorCode::InvalidInput,
+            format!("Invalid arguments for {}: {}", C::NAME, e),
+        )
+    })?;
+    let response = request.run(app).await?;
+    serde_json::to_value(response).map_err(|e| IpcError::from(AppError::Json(e)))
+}
+
+/// Registers the commands: one list drives dispatch, `listCommands` and the bindings
+macro_rules! commands {
+    ($($command:ty),* $(,)?) => {
+        /// Central IPC command handler
+        pub async fn handle_ipc(
+            app: tauri::AppHandle,
+            command: &str,
+            args: serde_json::Value,
+        ) -> Result<serde_json::Value, IpcError> {
+            $(
+                if command == <$command as IpcCommand>::NAME {
+                    return dispatch::<$command>(app, args).await;
+                }
+            )*
+            Err(IpcError::new(
+                ErrorCode::UnknownCommand,
+                format!("Unknown IPC command: {}", command),
+            ))
+        }
+
+        /// Every registered command, in registration order
+        pub fn list_commands() -> Vec<CommandInfo> {
+            vec![$(CommandInfo::of::<$command>()),*]
+        }
+
+        /// Write the TypeScript types of every command into `dir`, plus a
+        /// `commands.ts` mapping command names to their request and response,
+        /// and the `IpcError` every command fails with
+        pub fn export_bindings(dir: &Path) -> Result<(), ExportError> {
+            IpcError::export_all_to(dir)?;
+            let mut bindings = Bindings::new(dir);
+            $(bindings.add::<$command>();)*
+            bindings.finish()
+        }
+    };
+}
+
+commands![
+    ListCommands,
+    CheckTiltInstalled,
+    ValidateExecutablePath,
+    GenerateTiltfiles,
+    ResolveEnvironment,
+    ExplainEnvironment,
+    ValidateInterpolation,
+    StartTilt,
+    StopTilt,
+    RestartTilt,
+    GetTiltState,
+    ReconcileTiltState,
+    GetBuildMetrics,
+    GetTiltLogs,
+    ListLogSessions,
+    GetSessionHistory,
+    SearchLogs,
+    CloneRepo,
+    CreateProject,
+    OpenProject,
+    IsValidProject,
+    InitializeExistingProject,
+    DiscoverServices,
+    UpdateProject,
+    UpdateService,
+    AddService,
+    RemoveService,
+    RenameService,
+    CreateEnvironment,
+    CloneEnvironment,
+    RenameEnvironment,
+    DeleteEnvironment,
+    DiffEnvironments,
+    PromoteEnvironment,
+    SetSecret,
+    RemoveSecret,
+    ListSecrets,
+    OpenInEditor,
+    ReorderServices,
+    ListJobs,
+    GetJob,
+    GetJobLogs,
+    CancelJob,
+];
+
+/// List the registered commands
+#[derive(Deserialize, TS)]
+pub struct ListCommands {}
+
+impl IpcCommand for ListCommands {
+    const NAME: &'static str = "listCommands";
+    type Response = Vec<CommandInfo>;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Vec<CommandInfo>> {
+        Ok(list_commands())
+    }
+}
+
+/// Collects the command types for `export_bindings`
+struct Bindings<'a> {
+    dir: &'a Path,
+    /// Type names `commands.ts` imports, with the file each lives in
+    imports: BTreeMap<String, String>,
+    entries: Vec<String>,
+    result: Result<(), ExportError>,
+}
+
+impl<'a> Bindings<'a> {
+    fn new(dir: &'a Path) -> Self {
+        Bindings {
+            dir,
+            imports: BTreeMap::new(),
+            entries: Vec::new(),
+            result: Ok(()),
+        }
+    }
+
+    fn add<C: IpcCommand>(&mut self) {
+        self.visit::<C>();
+        self.visit::<C::Response>();
+        if C::Response::output_path().is_none() {
+            // Containers such as `Vec<T>` have no file, the types inside do
+            C::Response::visit_generics(self);
+        }
+
+        self.entries.push(format!(
+            "  {}: {{ request: {}; response: {} }}",
+            C::NAME,
+            C::name(),
+            C::Response::name()
+        ));
+    }
+
+    fn finish(self) -> Result<(), ExportError> {
+        self.result?;
+
+        let mut index = String::from("// Generated by `npm run bindings`, do not edit\n\n");
+        for (name, path) in &self.imports {
+            index.push_str(&format!("import type {{ {} }} from \"./{}\"\n", name, path));
+        }
+        index.push_str("\nexport type Commands = {\n");
+        for entry in &self.entries {
+            index.push_str(entry);
+            index.push('\n');
+        }
+        index.push_str("}\n");
+
+        fs::write(self.dir.join("commands.ts"), index)?;
+        Ok(())
+    }
+}
+
+impl TypeVisitor for Bindings<'_> {
+    fn visit<T: TS + 'static + ?Sized>(&mut self) {
+        if self.result.is_err() {
+            return;
+        }
+        // Primitives have no file of their own
+        let Some(path) = T::output_path() else {
+            return;
+        };
+        let path = path.with_extension("").to_string_lossy().replace('\\', "/");
+        self.imports.insert(T::ident(), path);
+        self.result = T::export_all_to(self.dir);
+    }
+}
+
+/// Redactor for an env's Tilt logs, using the user's extra patterns
+fn log_redactor(app: &tauri::AppHandle, project: &Project, env: &str) -> Result<Redactor> {
+    let patterns = load_state(app).preferences.log_redaction_patterns;
+    build_redactor(&app_data_dir(app)?, project, env, &patterns)
+}
@@ -0,0 +1,374 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+
+use super::IpcCommand;
+use crate::app_state::{app_data_dir, load_state};
+use crate::backend::generator::{generate_tiltfiles, reorder_services};
+use crate::backend::git::{clone_repo_in_job, clone_repos_in_job};
+use crate::backend::host::TiltHost;
+use crate::backend::jobs::{spawn_job, Job, JobKind};
+use crate::backend::project::{Project, Service};
+use crate::project::discovery::discover_services;
+use crate::project::{
+    add_service, create_project, initialize_existing_project, is_valid_project, load_project_info,
+    open_in_editor, remove_service, rename_service, repo_checkouts, update_project, update_service,
+    ServiceRemoval,
+};
+
+#[derive(Deserialize, TS)]
+pub struct GenerateTiltfiles {
+    project: Project,
+    env: String,
+}
+
+impl IpcCommand for GenerateTiltfiles {
+    const NAME: &'static str = "generateTiltfiles";
+    type Response = Job;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Job> {
+        let (workspace, env) = (
+            self.project.project.workspace_path.clone(),
+            self.env.clone(),
+        );
+        Ok(spawn_job(
+            JobKind::GenerateTiltfiles,
+            &env,
+            Some(&workspace),
+            Some(&env),
+            move |job| async move {
+                job.step("Generating Tiltfiles");
+                generate_tiltfiles(&self.project, &self.env)
+            },
+        ))
+    }
+}
+
+#[derive(Deserialize, TS)]
+#[ts(optional_fields)]
+pub struct CloneRepo {
+    url: String,
+    path: String,
+    branch: Option<String>,
+}
+
+impl IpcCommand for CloneRepo {
+    const NAME: &'static str = "cloneRepo";
+    type Response = Job;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Job> {
+        let url = self.url.clone();
+        Ok(spawn_job(
+            JobKind::CloneRepo,
+            &url,
+            None,
+            None,
+            move |job| async move {
+                job.step(format!("Cloning into {}", self.path));
+                clone_repo_in_job(
+                    &app.runner(),
+                    &self.url,
+                    Path::new(&self.path),
+                    self.branch.as_deref(),
+                    &job,
+                )
+                .await?;
+                Ok(())
+            },
+        ))
+    }
+}
+
+/// Creates the project in a job, whose result is the new project
+#[derive(Deserialize, TS)]
+pub struct CreateProject {
+    name: String,
+    workspace_path: String,
+    services_path: String,
+}
+
+impl IpcCommand for CreateProject {
+    const NAME: &'static str = "createProject";
+    type Response = Job;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Job> {
+        let name = self.name.clone();
+        Ok(spawn_job(
+            JobKind::CreateProject,
+            &name,
+            None,
+            None,
+            move |job| async move {
+                job.step("Creating workspace");
+                let project =
+                    create_project(&self.name, &self.workspace_path, Some(&self.services_path))
+                        .context("Failed to create project")?;
+
+                clone_repos_in_job(&app.runner(), &repo_checkouts(&project), &job).await?;
+
+                job.step("Generating Tiltfiles");
+                for env in project.environments.keys() {
+                    let _ = generate_tiltfiles(&project, env);
+                }
+                Ok(project)
+            },
+        ))
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct OpenProject {
+    workspace_path: String,
+}
+
+impl IpcCommand for OpenProject {
+    const NAME: &'static str = "openProject";
+    type Response = Project;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Project> {
+        load_project_info(&self.workspace_path).context("Failed to load project")
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct IsValidProject {
+    path: String,
+}
+
+#[derive(Serialize, TS)]
+pub struct ProjectValidity {
+    valid: bool,
+}
+
+impl IpcCommand for IsValidProject {
+    const NAME: &'static str = "isValidProject";
+    type Response = ProjectValidity;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<ProjectValidity> {
+        Ok(ProjectValidity {
+            valid: is_valid_project(&self.path),
+        })
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct InitializeExistingProject {
+    path: String,
+    services_path: String,
+}
+
+impl IpcCommand for InitializeExistingProject {
+    const NAME: &'static str = "initializeExistingProject";
+    type Response = Project;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Project> {
+        initialize_existing_project(&self.path, &self.services_path)
+            .context("Failed to initialize project")
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct DiscoverServices {
+    path: String,
+    services_path: String,
+}
+
+impl IpcCommand for DiscoverServices {
+    const NAME: &'static str = "discoverServices";
+    type Response = Vec<Service>;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Vec<Service>> {
+        discover_services(Path::new(&self.path), &self.services_path)
+            .context("Failed to discover services")
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct UpdateProject {
+    workspace_path: String,
+    project: Project,
+}
+
+impl IpcCommand for UpdateProject {
+    const NAME: &'static str = "updateProject";
+    type Response = Job;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Job> {
+        let (name, workspace) = (
+            self.project.project.name.clone(),
+            self.workspace_path.clone(),
+        );
+        Ok(spawn_job(
+            JobKind::UpdateProject,
+            &name,
+            Some(&workspace),
+            None,
+            move |job| async move {
+                job.step("Saving project");
+                let (project, checkouts) = update_project(&self.workspace_path, &self.project)
+                    .context("Failed to update project")?;
+
+                clone_repos_in_job(&app.runner(), &checkouts, &job).await?;
+                Ok(project)
+            },
+        ))
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct UpdateService {
+    workspace_path: String,
+    env: String,
+    service_name: String,
+    service: Service,
+}
+
+impl IpcCommand for UpdateService {
+    const NAME: &'static str = "updateService";
+    type Response = Project;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Project> {
+        update_service(
+            &self.workspace_path,
+            &self.env,
+            &self.service_name,
+            self.service,
+        )
+        .context("Failed to update service")
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct AddService {
+    workspace_path: String,
+    env: String,
+    service: Service,
+}
+
+impl IpcCommand for AddService {
+    const NAME: &'static str = "addService";
+    type Response = Job;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Job> {
+        let (name, workspace, env) = (
+            self.service.name.clone(),
+            self.workspace_path.clone(),
+            self.env.clone(),
+        );
+        Ok(spawn_job(
+            JobKind::AddService,
+            &name,
+            Some(&workspace),
+            Some(&env),
+            move |job| async move {
+                job.step("Adding service");
+                let (project, checkout) =
+                    add_service(&self.workspace_path, &self.env, self.service)
+                        .context("Failed to add service")?;
+
+                clone_repos_in_job(&app.runner(), checkout.as_slice(), &job).await?;
+                Ok(project)
+            },
+        ))
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct RemoveService {
+    workspace_path: String,
+    env: String,
+    service_name: String,
+    #[serde(default)]
+    #[ts(as = "Option<bool>", optional)]
+    delete_directory: bool,
+}
+
+impl IpcCommand for RemoveService {
+    const NAME: &'static str = "removeService";
+    type Response = ServiceRemoval;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<ServiceRemoval> {
+        remove_service(
+            &self.workspace_path,
+            &self.env,
+            &self.service_name,
+            self.delete_directory,
+        )
+        .context("Failed to remove service")
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct RenameService {
+    workspace_path: String,
+    service_name: String,
+    new_name: String,
+}
+
+impl IpcCommand for RenameService {
+    const NAME: &'static str = "renameService";
+    type Response = Project;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Project> {
+        rename_service(
+            &app_data_dir(&app)?,
+            &self.workspace_path,
+            &self.service_name,
+            &self.new_name,
+        )
+        .context("Failed to rename service")
+    }
+}
+
+#[derive(Deserialize, TS)]
+#[ts(optional_fields)]
+pub struct OpenInEditor {
+    project: Project,
+    repo_name: String,
+    editor: Option<String>,
+}
+
+impl IpcCommand for OpenInEditor {
+    const NAME: &'static str = "openInEditor";
+    type Response = ();
+
+    async fn run(self, app: tauri::AppHandle) -> Result<()> {
+        let state = load_state(&app);
+
+        // Priority: explicit editor param > editor_path from settings > default_editor from settings
+        let editor = self
+            .editor
+            .as_deref()
+            .or(state.preferences.editor_path.as_deref())
+            .or(state.preferences.default_editor.as_deref());
+
+        let services_path = self.project.project.services_path.as_deref();
+        open_in_editor(
+            &app.runner(),
+            Path::new(&self.project.project.workspace_path),
+            &self.repo_name,
+            editor,
+            services_path,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct ReorderServices {
+    project: Project,
+    env: String,
+    new_order: Vec<String>,
+}
+
+impl IpcCommand for ReorderServices {
+    const NAME: &'static str = "reorderServices";
+    type Response = Project;
+
+    async fn run(mut self, _app: tauri::AppHandle) -> Result<Project> {
+        reorder_services(&mut self.project, &self.env, self.new_order)?;
+        Ok(self.project)
+    }
+}
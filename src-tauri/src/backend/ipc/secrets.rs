@@ -0,0 +1,90 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+use ts_rs::TS;
+
+use super::IpcCommand;
+use crate::app_state::app_data_dir;
+use crate::backend::generator::generate_tiltfiles;
+use crate::backend::project::Project;
+use crate::backend::secrets::{list_secrets, remove_secret, set_secret, SecretEntry};
+use crate::project::{load_project_info, write_environment};
+
+#[derive(Deserialize, TS)]
+#[ts(optional_fields)]
+pub struct SetSecret {
+    workspace_path: String,
+    env: String,
+    service: Option<String>,
+    key: String,
+    value: String,
+    file: Option<String>,
+}
+
+impl IpcCommand for SetSecret {
+    const NAME: &'static str = "setSecret";
+    type Response = Project;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Project> {
+        let mut project =
+            load_project_info(&self.workspace_path).context("Failed to load project")?;
+        set_secret(
+            &app_data_dir(&app)?,
+            &mut project,
+            &self.env,
+            self.service.as_deref(),
+            &self.key,
+            &self.value,
+            self.file,
+        )?;
+        write_environment(Path::new(&self.workspace_path), &project, &self.env)
+            .context("Failed to save environment")?;
+        let _ = generate_tiltfiles(&project, &self.env);
+        Ok(project)
+    }
+}
+
+#[derive(Deserialize, TS)]
+#[ts(optional_fields)]
+pub struct RemoveSecret {
+    workspace_path: String,
+    env: String,
+    service: Option<String>,
+    key: String,
+}
+
+impl IpcCommand for RemoveSecret {
+    const NAME: &'static str = "removeSecret";
+    type Response = Project;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Project> {
+        let mut project =
+            load_project_info(&self.workspace_path).context("Failed to load project")?;
+        remove_secret(
+            &app_data_dir(&app)?,
+            &mut project,
+            &self.env,
+            self.service.as_deref(),
+            &self.key,
+        )?;
+        write_environment(Path::new(&self.workspace_path), &project, &self.env)
+            .context("Failed to save environment")?;
+        let _ = generate_tiltfiles(&project, &self.env);
+        Ok(project)
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct ListSecrets {
+    project: Project,
+    env: String,
+}
+
+impl IpcCommand for ListSecrets {
+    const NAME: &'static str = "listSecrets";
+    type Response = Vec<SecretEntry>;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Vec<SecretEntry>> {
+        list_secrets(&app_data_dir(&app)?, &self.project, &self.env)
+    }
+}
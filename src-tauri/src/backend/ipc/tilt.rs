@@ -0,0 +1,210 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::IpcCommand;
+use crate::app_state::load_state;
+use crate::backend::build_metrics::{build_stats, ResourceStats};
+use crate::backend::errors::AppError;
+use crate::backend::jobs::{spawn_job, Job, JobKind};
+use crate::backend::project::Project;
+use crate::backend::session_history::StopInitiator;
+use crate::backend::tilt_manager::{
+    check_tilt_installed, read_state, reconcile_tilt_state, restart_tilt, start_tilt, stop_tilt,
+    validate_executable_path, State, TiltInstallation, TiltLaunch, TitleStatus,
+};
+
+#[derive(Deserialize, TS)]
+pub struct CheckTiltInstalled {}
+
+impl IpcCommand for CheckTiltInstalled {
+    const NAME: &'static str = "checkTiltInstalled";
+    type Response = TiltInstallation;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<TiltInstallation> {
+        Ok(check_tilt_installed(&app).await?)
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct ValidateExecutablePath {
+    path: String,
+}
+
+#[derive(Serialize, TS)]
+pub struct ExecutableVersion {
+    valid: bool,
+    version: String,
+}
+
+impl IpcCommand for ValidateExecutablePath {
+    const NAME: &'static str = "validateExecutablePath";
+    type Response = ExecutableVersion;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<ExecutableVersion> {
+        let flags = ["--version", "version"];
+        let mut last_err = None;
+        for flag in flags {
+            match validate_executable_path(&app, &self.path, Some(flag)).await {
+                Ok(version) => {
+                    return Ok(ExecutableVersion {
+                        valid: true,
+                        version,
+                    });
+                }
+                Err(e) => {
+                    last_err = Some(e);
+                }
+            }
+        }
+        Err(last_err.unwrap().into())
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct StartTilt {
+    project: Project,
+    env: String,
+}
+
+impl IpcCommand for StartTilt {
+    const NAME: &'static str = "startTilt";
+    type Response = Job;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<Job> {
+        let (workspace, env) = (
+            self.project.project.workspace_path.clone(),
+            self.env.clone(),
+        );
+        Ok(spawn_job(
+            JobKind::StartTilt,
+            &env,
+            Some(&workspace),
+            Some(&env),
+            move |job| async move {
+                let state = load_state(&app);
+                let tilt_path = state.preferences.tilt_path.as_deref();
+                job.step("Preparing secrets and logs");
+                let launch = TiltLaunch::prepare(&app, &self.project, &self.env)?;
+                job.check_cancelled()?;
+
+                job.step("Starting Tilt");
+                let workspace = &self.project.project.workspace_path;
+                let run = start_tilt(workspace, &self.env, &app, tilt_path, launch).await?;
+                if run.is_some() && job.is_cancelled() {
+                    // Cancelled while Tilt was starting, it should not stay up
+                    stop_tilt(workspace, &self.env, &app, StopInitiator::User).await?;
+                    return Err(AppError::Cancelled.into());
+                }
+                Ok(())
+            },
+        ))
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct StopTilt {
+    project: Project,
+    env: String,
+    #[serde(default)]
+    #[ts(as = "Option<StopInitiator>", optional)]
+    initiator: StopInitiator,
+}
+
+impl IpcCommand for StopTilt {
+    const NAME: &'static str = "stopTilt";
+    type Response = ();
+
+    async fn run(self, app: tauri::AppHandle) -> Result<()> {
+        stop_tilt(
+            &self.project.project.workspace_path,
+            &self.env,
+            &app,
+            self.initiator,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct RestartTilt {
+    project: Project,
+    env: String,
+    #[serde(default)]
+    #[ts(as = "Option<StopInitiator>", optional)]
+    initiator: StopInitiator,
+}
+
+impl IpcCommand for RestartTilt {
+    const NAME: &'static str = "restartTilt";
+    type Response = ();
+
+    async fn run(self, app: tauri::AppHandle) -> Result<()> {
+        let state = load_state(&app);
+        let tilt_path = state.preferences.tilt_path.as_deref();
+        restart_tilt(
+            &self.project.project.workspace_path,
+            &self.env,
+            &app,
+            tilt_path,
+            TiltLaunch::prepare(&app, &self.project, &self.env)?,
+            self.initiator,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct GetTiltState {
+    project: Project,
+    env: String,
+}
+
+impl IpcCommand for GetTiltState {
+    const NAME: &'static str = "getTiltState";
+    type Response = State;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<State> {
+        Ok(read_state(&self.project.project.workspace_path, &self.env))
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct ReconcileTiltState {
+    project: Project,
+    env: String,
+}
+
+impl IpcCommand for ReconcileTiltState {
+    const NAME: &'static str = "reconcileTiltState";
+    type Response = TitleStatus;
+
+    async fn run(self, app: tauri::AppHandle) -> Result<TitleStatus> {
+        let workspace = &self.project.project.workspace_path;
+        Ok(reconcile_tilt_state(workspace, &self.env, &app).await?)
+    }
+}
+
+#[derive(Deserialize, TS)]
+#[ts(optional_fields)]
+pub struct GetBuildMetrics {
+    project: Project,
+    env: String,
+    /// Only the most recent sessions, all kept sessions when not set
+    sessions: Option<usize>,
+}
+
+impl IpcCommand for GetBuildMetrics {
+    const NAME: &'static str = "getBuildMetrics";
+    type Response = Vec<ResourceStats>;
+
+    async fn run(self, _app: tauri::AppHandle) -> Result<Vec<ResourceStats>> {
+        Ok(build_stats(
+            &self.project.project.workspace_path,
+            &self.env,
+            self.sessions,
+        ))
+    }
+}
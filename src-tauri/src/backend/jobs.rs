@@ -0,0 +1,390 @@
+use chrono::Local;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use tauri::Emitter;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use ts_rs::TS;
+
+use crate::backend::errors::{AppError, ErrorCode, IpcError};
+use crate::backend::events::{publish, subscribe, BackendEvent};
+
+/// Finished jobs kept for the job list, oldest are dropped first
+const MAX_FINISHED_JOBS: usize = 100;
+
+/// Output lines kept per job, oldest are dropped first
+const MAX_JOB_LOG_LINES: usize = 500;
+
+/// What a job does
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    AddService,
+    CloneRepo,
+    CreateProject,
+    GenerateTiltfiles,
+    StartTilt,
+    UpdateProject,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+/// Where one repository of a multi-repo clone is at
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum RepoStatus {
+    Pending,
+    Cloning,
+    Cloned,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Serialize, Debug, Clone, TS)]
+pub struct RepoProgress {
+    pub service: String,
+    pub url: String,
+    pub status: RepoStatus,
+    pub error: Option<String>,
+}
+
+/// An operation running in the background. IPC returns it as soon as it
+/// starts, every change after that is published as a `job` event.
+#[derive(Serialize, Debug, Clone, TS)]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    /// What it works on, such as the repository URL
+    pub title: String,
+    pub workspace_path: Option<String>,
+    pub env: Option<String>,
+    pub status: JobStatus,
+    /// The step it is at
+    pub message: Option<String>,
+    /// Steps done out of `total`, when the job knows how many there are
+    pub done: u32,
+    pub total: Option<u32>,
+    /// One entry per repository when it clones several
+    pub repos: Vec<RepoProgress>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// Why it failed or that it was cancelled
+    pub error: Option<IpcError>,
+    /// What it produced, such as the created project
+    pub result: Option<serde_json::Value>,
+}
+
+/// A line of job output as the webview receives it
+#[derive(Serialize, Debug, Clone)]
+struct JobLogLine {
+    id: String,
+    line: String,
+}
+
+struct Entry {
+    job: Job,
+    logs: VecDeque<String>,
+    cancel: CancellationToken,
+}
+
+/// Jobs of this run, running and recently finished
+#[derive(Default)]
+struct Registry {
+    /// In start order
+    entries: Vec<Entry>,
+    next_id: u64,
+}
+
+impl Registry {
+    fn entry(&mut self, id: &str) -> Result<&mut Entry, AppError> {
+        self.entries
+            .iter_mut()
+            .find(|entry| entry.job.id == id)
+            .ok_or_else(|| AppError::JobNotFound {
+                job: id.to_string(),
+            })
+    }
+
+    /// Drop the oldest finished jobs beyond `MAX_FINISHED_JOBS`
+    fn prune(&mut self) {
+        let finished = self
+            .entries
+            .iter()
+            .filter(|entry| entry.job.status != JobStatus::Running)
+            .count();
+        let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+        self.entries.retain(|entry| {
+            let drop = excess > 0 && entry.job.status != JobStatus::Running;
+            if drop {
+                excess -= 1;
+            }
+            !drop
+        });
+    }
+}
+
+static JOBS: LazyLock<Mutex<Registry>> = LazyLock::new(Default::default);
+
+fn registry() -> MutexGuard<'static, Registry> {
+    JOBS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn now() -> String {
+    Local::now().to_rfc3339()
+}
+
+/// What a running job reports through
+#[derive(Clone)]
+pub struct JobHandle {
+    id: String,
+    cancel: CancellationToken,
+}
+
+impl JobHandle {
+    /// Fires when the job is cancelled. Jobs are not interrupted, they
+    /// stop at their next check of the token.
+    pub fn cancel_token(&self) -> &CancellationToken {
+        &self.cancel
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Fail with `Cancelled` once the job has been cancelled
+    pub fn check_cancelled(&self) -> Result<(), AppError> {
+        if self.is_cancelled() {
+            Err(AppError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Change the job and publish its new state
+    fn update(&self, change: impl FnOnce(&mut Job)) {
+        let job = {
+            let mut jobs = registry();
+            let Ok(entry) = jobs.entry(&self.id) else {
+                return;
+            };
+            change(&mut entry.job);
+            entry.job.clone()
+        };
+        publish(BackendEvent::Job { job: Box::new(job) });
+    }
+
+    /// Move on to the next step
+    pub fn step(&self, message: impl Into<String>) {
+        let message = message.into();
+        self.update(|job| job.message = Some(message));
+    }
+
+    pub fn progress(&self, done: u32, total: u32) {
+        self.update(|job| {
+            job.done = done;
+            job.total = Some(total);
+        });
+    }
+
+    /// Keep a line of output and publish it as a `job_log` event
+    pub fn log(&self, line: impl Into<String>) {
+        let line = line.into();
+        let (workspace_path, env) = {
+            let mut jobs = registry();
+            let Ok(entry) = jobs.entry(&self.id) else {
+                return;
+            };
+            if entry.logs.len() == MAX_JOB_LOG_LINES {
+                entry.logs.pop_front();
+            }
+            entry.logs.push_back(line.clone());
+            (entry.job.workspace_path.clone(), entry.job.env.clone())
+        };
+        publish(BackendEvent::JobLog {
+            id: self.id.clone(),
+            workspace_path,
+            env,
+            line,
+        });
+    }
+
+    /// The repositories this job clones, all pending
+    pub fn set_repos(&self, repos: impl IntoIterator<Item = (String, String)>) {
+        let repos = repos
+            .into_iter()
+            .map(|(service, url)| RepoProgress {
+                service,
+                url,
+                status: RepoStatus::Pending,
+                error: None,
+            })
+            .collect();
+        self.update(|job| job.repos = repos);
+    }
+
+    pub fn repo_status(&self, service: &str, status: RepoStatus, error: Option<String>) {
+        self.update(|job| {
+            if let Some(repo) = job.repos.iter_mut().find(|repo| repo.service == service) {
+                repo.status = status;
+                repo.error = error;
+            }
+        });
+    }
+
+    fn finish(&self, outcome: anyhow::Result<serde_json::Value>) {
+        // Whatever a cancelled job ran into on its way out, it was cancelled
+        let outcome = match outcome {
+            Err(_) if self.is_cancelled() => Err(AppError::Cancelled.into()),
+            outcome => outcome,
+        };
+        self.update(|job| {
+            job.finished_at = Some(now());
+            match outcome {
+                Ok(value) => {
+                    job.status = JobStatus::Succeeded;
+                    job.result = Some(value);
+                }
+                Err(e) => {
+                    let error = IpcError::from(e);
+                    job.status = if error.code == ErrorCode::Cancelled {
+                        JobStatus::Cancelled
+                    } else {
+                        JobStatus::Failed
+                    };
+                    job.error = Some(error);
+                }
+            }
+        });
+        registry().prune();
+    }
+}
+
+/// Start `work` in the background and return its job right away.
+/// What `work` returns ends up in `Job::result`.
+pub fn spawn_job<T, F, Fut>(
+    kind: JobKind,
+    title: impl Into<String>,
+    workspace_path: Option<&str>,
+    env: Option<&str>,
+    work: F,
+) -> Job
+where
+    T: Serialize,
+    F: FnOnce(JobHandle) -> Fut,
+    Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
+{
+    let cancel = CancellationToken::new();
+    let job = {
+        let mut jobs = registry();
+        jobs.next_id += 1;
+        let job = Job {
+            id: format!("job-{}", jobs.next_id),
+            kind,
+            title: title.into(),
+            workspace_path: workspace_path.map(str::to_string),
+            env: env.map(str::to_string),
+            status: JobStatus::Running,
+            message: None,
+            done: 0,
+            total: None,
+            repos: Vec::new(),
+            started_at: now(),
+            finished_at: None,
+            error: None,
+            result: None,
+        };
+        jobs.entries.push(Entry {
+            job: job.clone(),
+            logs: VecDeque::new(),
+            cancel: cancel.clone(),
+        });
+        job
+    };
+    publish(BackendEvent::Job {
+        job: Box::new(job.clone()),
+    });
+
+    let handle = JobHandle {
+        id: job.id.clone(),
+        cancel,
+    };
+    let task = work(handle.clone());
+    tauri::async_runtime::spawn(async move {
+        let outcome = match task.await {
+            Ok(value) => serde_json::to_value(value).map_err(Into::into),
+            Err(e) => Err(e),
+        };
+        handle.finish(outcome);
+    });
+
+    job
+}
+
+/// Jobs of this run, newest first
+pub fn list_jobs() -> Vec<Job> {
+    registry()
+        .entries
+        .iter()
+        .rev()
+        .map(|entry| entry.job.clone())
+        .collect()
+}
+
+pub fn get_job(id: &str) -> Result<Job, AppError> {
+    Ok(registry().entry(id)?.job.clone())
+}
+
+/// Output kept for a job, oldest first
+pub fn job_logs(id: &str) -> Result<Vec<String>, AppError> {
+    Ok(registry().entry(id)?.logs.iter().cloned().collect())
+}
+
+/// Ask a running job to stop. A finished job is returned as it is.
+pub fn cancel_job(id: &str) -> Result<Job, AppError> {
+    let handle = {
+        let mut jobs = registry();
+        let entry = jobs.entry(id)?;
+        if entry.job.status != JobStatus::Running {
+            return Ok(entry.job.clone());
+        }
+        JobHandle {
+            id: id.to_string(),
+            cancel: entry.cancel.clone(),
+        }
+    };
+    handle.cancel.cancel();
+    handle.step("Cancelling");
+    get_job(id)
+}
+
+/// Pass job events on to the webview as `job-updated` and `job-log`
+pub async fn forward_to_webview(app: tauri::AppHandle) {
+    let mut events = subscribe();
+    loop {
+        match events.recv().await {
+            Ok(BackendEvent::Job { job }) => {
+                let _ = app.emit("job-updated", job);
+            }
+            Ok(BackendEvent::JobLog { id, line, .. }) => {
+                let _ = app.emit("job-log", JobLogLine { id, line });
+            }
+            Ok(_) => continue,
+            // The missed update may have been a job's end, send them all again
+            Err(broadcast::error::RecvError::Lagged(_)) => {
+                for job in list_jobs() {
+                    let _ = app.emit("job-updated", job);
+                }
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io;
+use ts_rs::TS;
+
+/// Separator Tilt prints between the resource name and its output
+const RESOURCE_SEPARATOR: &str = " │ ";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+impl LogStream {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LogStream::Stdout => "stdout",
+            LogStream::Stderr => "stderr",
+        }
+    }
+}
+
+/// One captured log line split into its parts
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+pub struct LogRecord {
+    /// Capture time, missing for logs written before records were stamped
+    pub timestamp: Option<String>,
+    pub resource: Option<String>,
+    pub level: Option<LogLevel>,
+    pub stream: Option<LogStream>,
+    /// The line as Tilt printed it
+    pub line: String,
+    /// The line without the resource prefix
+    pub message: String,
+}
+
+/// Prefix written before every captured line: capture time and stream
+pub fn record_header(stream: LogStream) -> String {
+    format!(
+        "{} {} ",
+        Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+        stream.as_str()
+    )
+}
+
+/// Parse a line from the log file. Lines without a header are still accepted.
+pub fn parse_record(raw: &str) -> LogRecord {
+    let (timestamp, stream, line) = split_header(raw).unwrap_or((None, None, raw));
+
+    let (resource, message) = match line.split_once(RESOURCE_SEPARATOR) {
+        Some((name, message)) if is_resource_name(name.trim()) => {
+            (Some(name.trim().to_string()), message)
+        }
+        _ => (None, line),
+    };
+
+    LogRecord {
+        timestamp: timestamp.map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, false)),
+        resource,
+        level: detect_level(message),
+        stream,
+        line: line.to_string(),
+        message: message.to_string(),
+    }
+}
+
+type Header<'a> = (Option<DateTime<FixedOffset>>, Option<LogStream>, &'a str);
+
+fn split_header(raw: &str) -> Option<Header<'_>> {
+    let (timestamp, rest) = raw.split_once(' ')?;
+    let timestamp = DateTime::parse_from_rfc3339(timestamp).ok()?;
+
+    let (stream, line) = rest.split_once(' ').unwrap_or((rest, ""));
+    let stream = match stream {
+        "stdout" => LogStream::Stdout,
+        "stderr" => LogStream::Stderr,
+        _ => return None,
+    };
+
+    Some((Some(timestamp), Some(stream), line))
+}
+
+fn is_resource_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(char::is_whitespace)
+}
+
+fn parse_level(value: &str) -> Option<LogLevel> {
+    match value.to_ascii_lowercase().as_str() {
+        "trace" | "trc" => Some(LogLevel::Trace),
+        "debug" | "dbg" => Some(LogLevel::Debug),
+        "info" | "inf" | "notice" => Some(LogLevel::Info),
+        "warn" | "warning" | "wrn" => Some(LogLevel::Warn),
+        "error" | "err" | "fatal" | "critical" | "crit" | "panic" => Some(LogLevel::Error),
+        _ => None,
+    }
+}
+
+/// Detect the level from JSON logs, logfmt, klog prefixes and leading
+/// `ERROR`, `[warn]` or `info:` style markers
+pub fn detect_level(message: &str) -> Option<LogLevel> {
+    let message = message.trim_start();
+
+    if message.starts_with('{') {
+        if let Ok(serde_json::Value::Object(map)) = serde_json::from_str(message) {
+            return ["level", "severity", "lvl"]
+                .iter()
+                .filter_map(|key| map.get(*key).and_then(|v| v.as_str()))
+                .find_map(parse_level);
+        }
+    }
+
+    for token in message.split_whitespace() {
+        if let Some(value) = token
+            .strip_prefix("level=")
+            .or_else(|| token.strip_prefix("lvl="))
+        {
+            return parse_level(value.trim_matches('"'));
+        }
+    }
+
+    // klog: `E1018 12:00:00.000000 ...`
+    let mut chars = message.chars();
+    if let Some(first) = chars.next() {
+        let date: String = chars.by_ref().take(4).collect();
+        if date.len() == 4 && date.chars().all(|c| c.is_ascii_digit()) && chars.next() == Some(' ')
+        {
+            match first {
+                'I' => return Some(LogLevel::Info),
+                'W' => return Some(LogLevel::Warn),
+                'E' | 'F' => return Some(LogLevel::Error),
+                _ => {}
+            }
+        }
+    }
+
+    message.split_whitespace().take(4).find_map(|token| {
+        let marked = token.starts_with('[') || token.ends_with(':') || token.ends_with(']');
+        let word = token.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+        let upper = word.chars().all(|c| c.is_ascii_uppercase());
+
+        if marked || upper {
+            parse_level(word)
+        } else {
+            None
+        }
+    })
+}
+
+/// Filters for `getTiltLogs`, all optional and combined with AND
+#[derive(Deserialize, Debug, Clone, Default, TS)]
+#[ts(optional_fields)]
+pub struct LogFilter {
+    /// Only lines from these resources
+    pub resources: Option<Vec<String>>,
+    /// Only lines at or above this level, lines without a level are dropped
+    pub level: Option<LogLevel>,
+    /// RFC 3339 bounds on capture time
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Case-insensitive substring of the line
+    pub text: Option<String>,
+    /// Regex matched against the line
+    pub pattern: Option<String>,
+}
+
+/// A `LogFilter` with its bounds and pattern parsed once
+pub struct CompiledFilter {
+    resources: Option<Vec<String>>,
+    level: Option<LogLevel>,
+    since: Option<DateTime<FixedOffset>>,
+    until: Option<DateTime<FixedOffset>>,
+    text: Option<String>,
+    pattern: Option<Regex>,
+}
+
+fn parse_bound(name: &str, value: Option<&String>) -> io::Result<Option<DateTime<FixedOffset>>> {
+    value
+        .map(|v| {
+            DateTime::parse_from_rfc3339(v).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Invalid {} time {}: {}", name, v, e),
+                )
+            })
+        })
+        .transpose()
+}
+
+impl LogFilter {
+    pub fn compile(&self) -> io::Result<CompiledFilter> {
+        let pattern = self
+            .pattern
+            .as_deref()
+            .map(|p| {
+                Regex::new(p).map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("Invalid log pattern {}: {}", p, e),
+                    )
+                })
+            })
+            .transpose()?;
+
+        Ok(CompiledFilter {
+            resources: self.resources.clone().filter(|r| !r.is_empty()),
+            level: self.level,
+            since: parse_bound("since", self.since.as_ref())?,
+            until: parse_bound("until", self.until.as_ref())?,
+            text: self
+                .text
+                .as_deref()
+                .filter(|t| !t.is_empty())
+                .map(str::to_lowercase),
+            pattern,
+        })
+    }
+}
+
+impl CompiledFilter {
+    pub fn matches(&self, record: &LogRecord) -> bool {
+        if let Some(resources) = &self.resources {
+            match &record.resource {
+                Some(resource) if resources.contains(resource) => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(level) = self.level {
+            if record.level.is_none_or(|l| l < level) {
+                return false;
+            }
+        }
+
+        if self.since.is_some() || self.until.is_some() {
+            let Some(timestamp) = record
+                .timestamp
+                .as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            else {
+                return false;
+            };
+
+            if self.since.is_some_and(|since| timestamp < since)
+                || self.until.is_some_and(|until| timestamp > until)
+            {
+                return false;
+            }
+        }
+
+        if let Some(text) = &self.text {
+            if !record.line.to_lowercase().contains(text.as_str()) {
+                return false;
+            }
+        }
+
+        if let Some(pattern) = &self.pattern {
+            if !pattern.is_match(&record.line) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::backend::log_records::{parse_record, CompiledFilter};
+use crate::backend::log_store::{session_logs, session_started_at};
+use crate::backend::redaction::Redactor;
+
+/// Context lines kept around a match when the request does not say
+pub const DEFAULT_CONTEXT_LINES: usize = 2;
+
+/// Matches sent per batch when the request does not say
+pub const DEFAULT_BATCH_SIZE: usize = 50;
+
+/// One environment to search, with the redactor for its values
+pub struct SearchTarget {
+    pub env: String,
+    pub current_session: Option<String>,
+    pub redactor: Redactor,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LogMatch {
+    pub env: String,
+    pub session: String,
+    pub session_started_at: Option<String>,
+    pub file: String,
+    /// One based line number within `file`
+    pub line_number: usize,
+    pub resource: Option<String>,
+    pub line: String,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// A batch of matches sent to the frontend while a search runs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LogSearchBatch {
+    pub search_id: String,
+    pub matches: Vec<LogMatch>,
+    /// Matches sent so far, including this batch
+    pub total: usize,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+/// Search every session of every target, newest session first.
+/// Matches are handed to `on_batch` in groups of `batch_size`, and the
+/// total number of matches is returned once all files are read.
+pub fn search_logs(
+    workspace: &str,
+    targets: &[SearchTarget],
+    filter: &CompiledFilter,
+    context: usize,
+    batch_size: usize,
+    mut on_batch: impl FnMut(Vec<LogMatch>),
+) -> io::Result<usize> {
+    let batch_size = batch_size.max(1);
+    let mut batch = Vec::new();
+    let mut total = 0;
+
+    for target in targets {
+        let sessions = session_logs(workspace, &target.env, target.current_session.as_deref())?;
+
+        for (session, files) in sessions.iter().rev() {
+            for file in files {
+                search_file(file, filter, context, &target.redactor, |found| {
+                    batch.push(LogMatch {
+                        env: target.env.clone(),
+                        session: session.clone(),
+                        session_started_at: session_started_at(session),
+                        file: file.to_string_lossy().to_string(),
+                        ..found
+                    });
+                    total += 1;
+
+                    if batch.len() >= batch_size {
+                        on_batch(std::mem::take(&mut batch));
+                    }
+                })?;
+            }
+        }
+    }
+
+    if !batch.is_empty() {
+        on_batch(batch);
+    }
+
+    Ok(total)
+}
+
+/// Stream one file line by line, keeping only the context window in memory
+fn search_file(
+    path: &Path,
+    filter: &CompiledFilter,
+    context: usize,
+    redactor: &Redactor,
+    mut on_match: impl FnMut(LogMatch),
+) -> io::Result<()> {
+    // A rotated part can be pruned while the search runs
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    let mut previous: VecDeque<String> = VecDeque::with_capacity(context);
+    // Matches still collecting their trailing context
+    let mut pending: VecDeque<LogMatch> = VecDeque::new();
+    let mut buf = Vec::new();
+    let mut reader = BufReader::new(file);
+    let mut line_number = 0;
+
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        let raw = String::from_utf8_lossy(&buf);
+        let record = parse_record(redactor.redact(raw.trim_end_matches(['\n', '\r'])).as_str());
+
+        for waiting in pending.iter_mut() {
+            waiting.after.push(record.line.clone());
+        }
+        while pending.front().is_some_and(|m| m.after.len() >= context) {
+            if let Some(done) = pending.pop_front() {
+                on_match(done);
+            }
+        }
+
+        if filter.matches(&record) {
+            let found = LogMatch {
+                env: String::new(),
+                session: String::new(),
+                session_started_at: None,
+                file: String::new(),
+                line_number,
+                resource: record.resource.clone(),
+                line: record.line.clone(),
+                before: previous.iter().cloned().collect(),
+                after: Vec::new(),
+            };
+
+            if context == 0 {
+                on_match(found);
+            } else {
+                pending.push_back(found);
+            }
+        }
+
+        if context > 0 {
+            if previous.len() == context {
+                previous.pop_front();
+            }
+            previous.push_back(record.line);
+        }
+    }
+
+    for waiting in pending {
+        on_match(waiting);
+    }
+
+    Ok(())
+}
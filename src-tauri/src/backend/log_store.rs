@@ -0,0 +1,466 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use ts_rs::TS;
+
+use crate::app_state::model::LogRetention;
+use crate::backend::log_records::{record_header, LogStream};
+
+/// A Tilt session's logs: the archived parts plus the live file for the current session
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+pub struct LogSession {
+    pub id: String,
+    pub started_at: Option<String>,
+    pub parts: usize,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+    pub current: bool,
+}
+
+pub fn tooling_dir(workspace: &str) -> PathBuf {
+    Path::new(workspace).join(".tooling")
+}
+
+/// The live log of the current session
+pub fn log_path(workspace: &str, env: &str) -> PathBuf {
+    tooling_dir(workspace).join(format!("{}_tilt.log", env))
+}
+
+/// `<env>_tilt.<session>.<part>.log`, kept next to the live log so environment
+/// rename and delete handle archives like any other runtime file
+fn archive_path(workspace: &str, env: &str, session: &str, part: usize) -> PathBuf {
+    tooling_dir(workspace).join(format!("{}_tilt.{}.{:03}.log", env, session, part))
+}
+
+/// Session ids are start timestamps, so they sort chronologically
+pub fn new_session_id() -> String {
+    Local::now().format("%Y%m%d_%H%M%S_%3f").to_string()
+}
+
+pub fn session_started_at(session: &str) -> Option<String> {
+    let stamp = session.get(..15)?;
+    chrono::NaiveDateTime::parse_from_str(stamp, "%Y%m%d_%H%M%S")
+        .ok()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// Archived parts of every session, keyed by session id, parts in order
+fn archived_sessions(workspace: &str, env: &str) -> io::Result<BTreeMap<String, Vec<PathBuf>>> {
+    let mut sessions: BTreeMap<String, Vec<(usize, PathBuf)>> = BTreeMap::new();
+    let dir = tooling_dir(workspace);
+
+    if !dir.is_dir() {
+        return Ok(BTreeMap::new());
+    }
+
+    let prefix = format!("{}_tilt.", env);
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+
+        let Some(rest) = name
+            .strip_prefix(&prefix)
+            .and_then(|r| r.strip_suffix(".log"))
+        else {
+            continue;
+        };
+
+        if let Some((session, part)) = rest.rsplit_once('.') {
+            if let Ok(part) = part.parse::<usize>() {
+                sessions
+                    .entry(session.to_string())
+                    .or_default()
+                    .push((part, path));
+            }
+        }
+    }
+
+    Ok(sessions
+        .into_iter()
+        .map(|(session, mut parts)| {
+            parts.sort_by_key(|(part, _)| *part);
+            (session, parts.into_iter().map(|(_, p)| p).collect())
+        })
+        .collect())
+}
+
+fn next_part(workspace: &str, env: &str, session: &str) -> io::Result<usize> {
+    Ok(archived_sessions(workspace, env)?
+        .get(session)
+        .map(|parts| parts.len() + 1)
+        .unwrap_or(1))
+}
+
+/// Move the live log of a finished session into the archive.
+/// Without a known session id the file's modification time is used.
+pub fn archive_live_log(workspace: &str, env: &str, session: Option<&str>) -> io::Result<()> {
+    let live = log_path(workspace, env);
+    if !live.exists() {
+        return Ok(());
+    }
+
+    if fs::metadata(&live)?.len() == 0 {
+        return fs::remove_file(&live);
+    }
+
+    let session = match session {
+        Some(s) => s.to_string(),
+        None => {
+            let modified: DateTime<Local> = fs::metadata(&live)?.modified()?.into();
+            modified.format("%Y%m%d_%H%M%S_%3f").to_string()
+        }
+    };
+
+    let part = next_part(workspace, env, &session)?;
+    fs::rename(&live, archive_path(workspace, env, &session, part))
+}
+
+/// Delete archived sessions beyond the configured count or age.
+/// The session given as `keep` is never pruned.
+pub fn prune_sessions(
+    workspace: &str,
+    env: &str,
+    retention: &LogRetention,
+    keep: Option<&str>,
+) -> io::Result<()> {
+    let sessions = archived_sessions(workspace, env)?;
+    let max_age = retention
+        .max_age_days
+        .map(|days| Duration::from_secs(days * 24 * 60 * 60));
+    let now = SystemTime::now();
+
+    // Newest first
+    for (index, (session, parts)) in sessions.iter().rev().enumerate() {
+        if Some(session.as_str()) == keep {
+            continue;
+        }
+
+        let newest = parts
+            .iter()
+            .filter_map(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+            .max();
+
+        let too_old = match (max_age, newest) {
+            (Some(max_age), Some(modified)) => now
+                .duration_since(modified)
+                .map(|age| age > max_age)
+                .unwrap_or(false),
+            _ => false,
+        };
+
+        if index >= retention.max_sessions || too_old {
+            for part in parts {
+                fs::remove_file(part)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Files of every session with logs on disk, oldest first.
+/// The live file belongs to the current session, or to an unnamed one after a crash.
+pub fn session_logs(
+    workspace: &str,
+    env: &str,
+    current: Option<&str>,
+) -> io::Result<BTreeMap<String, Vec<PathBuf>>> {
+    let mut sessions = archived_sessions(workspace, env)?;
+    let live = log_path(workspace, env);
+
+    if live.exists() {
+        let session = current.map(str::to_string).unwrap_or_default();
+        sessions.entry(session).or_default().push(live);
+    }
+
+    Ok(sessions)
+}
+
+/// Sessions with logs on disk, oldest first
+pub fn list_sessions(
+    workspace: &str,
+    env: &str,
+    current: Option<&str>,
+) -> io::Result<Vec<LogSession>> {
+    Ok(session_logs(workspace, env, current)?
+        .into_iter()
+        .map(|(id, parts)| LogSession {
+            started_at: session_started_at(&id),
+            parts: parts.len(),
+            size_bytes: parts
+                .iter()
+                .filter_map(|p| fs::metadata(p).ok())
+                .map(|m| m.len())
+                .sum(),
+            current: Some(id.as_str()) == current || id.is_empty(),
+            id,
+        })
+        .collect())
+}
+
+/// Log files of a session in write order. The live file is last for the current session.
+pub fn session_files(
+    workspace: &str,
+    env: &str,
+    session: &str,
+    current: Option<&str>,
+) -> io::Result<Vec<PathBuf>> {
+    let mut files = archived_sessions(workspace, env)?
+        .remove(session)
+        .unwrap_or_default();
+
+    let live = log_path(workspace, env);
+    if Some(session) == current && live.exists() {
+        files.push(live);
+    }
+
+    if files.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Log session {} not found for environment {}", session, env),
+        ));
+    }
+
+    Ok(files)
+}
+
+/// Chunk size used when scanning backwards for line breaks
+const TAIL_CHUNK: u64 = 64 * 1024;
+
+/// The files of one session read as a single stream.
+/// Sizes are captured on open so a read never sees a half-written tail.
+pub struct LogSource {
+    files: Vec<(PathBuf, u64)>,
+}
+
+impl LogSource {
+    pub fn open(files: Vec<PathBuf>) -> io::Result<Self> {
+        let files = files
+            .into_iter()
+            .filter(|f| f.exists())
+            .map(|f| fs::metadata(&f).map(|m| (f, m.len())))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(Self { files })
+    }
+
+    pub fn size(&self) -> u64 {
+        self.files.iter().map(|(_, len)| len).sum()
+    }
+
+    /// A reader over `[start, end)` of the concatenated files
+    fn reader(&self, start: u64, end: u64) -> io::Result<Box<dyn Read>> {
+        let mut reader: Box<dyn Read> = Box::new(io::empty());
+        let mut file_start = 0;
+
+        for (path, len) in &self.files {
+            let file_end = file_start + len;
+
+            if file_end > start && file_start < end {
+                let from = start.saturating_sub(file_start);
+                let to = end.min(file_end) - file_start;
+
+                let mut file = File::open(path)?;
+                file.seek(SeekFrom::Start(from))?;
+                reader = Box::new(reader.chain(file.take(to - from)));
+            }
+
+            file_start = file_end;
+        }
+
+        Ok(reader)
+    }
+
+    /// Up to `limit` complete lines starting at byte `start`, keeping the lines
+    /// `select` maps to `Some`. Returns them and the offset just past the last line read.
+    pub fn read_lines<T>(
+        &self,
+        start: u64,
+        limit: usize,
+        mut select: impl FnMut(String) -> Option<T>,
+    ) -> io::Result<(Vec<T>, u64)> {
+        let size = self.size();
+        let mut reader = BufReader::new(self.reader(start.min(size), size)?);
+        let mut lines = Vec::new();
+        let mut offset = start.min(size);
+        let mut buf = Vec::new();
+
+        while lines.len() < limit {
+            buf.clear();
+            let read = reader.read_until(b'\n', &mut buf)?;
+
+            // Stop at the end or at a line that is still being written
+            if read == 0 || buf.last() != Some(&b'\n') {
+                break;
+            }
+
+            offset += read as u64;
+            lines.extend(select(decode_line(&buf)));
+        }
+
+        Ok((lines, offset))
+    }
+
+    /// The last `count` complete lines before byte `end` that `select` maps to `Some`,
+    /// read backwards in chunks. Returns the offset of the earliest line read,
+    /// the lines in order and the offset just past the last line.
+    pub fn tail<T>(
+        &self,
+        end: u64,
+        count: usize,
+        mut select: impl FnMut(String) -> Option<T>,
+    ) -> io::Result<(u64, Vec<T>, u64)> {
+        let size = self.size();
+        let mut end = end.min(size);
+        let mut buf: Vec<u8> = Vec::new();
+        let mut pos = end;
+
+        // Drop a trailing line that is still being written
+        if end == size {
+            loop {
+                if let Some(i) = buf.iter().rposition(|b| *b == b'\n') {
+                    end -= (buf.len() - i - 1) as u64;
+                    buf.truncate(i + 1);
+                    break;
+                }
+
+                if pos == 0 {
+                    end = 0;
+                    buf.clear();
+                    break;
+                }
+
+                pos = self.read_back(pos, &mut buf)?;
+            }
+        }
+
+        let mut lines = Vec::new();
+        let mut start = end;
+
+        while lines.len() < count && start > 0 {
+            // Everything before the last byte, which is the line's own break
+            let body = &buf[..buf.len().saturating_sub(1)];
+
+            match body.iter().rposition(|b| *b == b'\n') {
+                Some(i) => {
+                    let line = buf.split_off(i + 1);
+                    start -= line.len() as u64;
+                    lines.extend(select(decode_line(&line)));
+                }
+                None if pos == 0 => {
+                    start = 0;
+                    lines.extend(select(decode_line(&buf)));
+                    buf.clear();
+                }
+                None => pos = self.read_back(pos, &mut buf)?,
+            }
+        }
+
+        lines.reverse();
+        Ok((start, lines, end))
+    }
+
+    /// Prepend the chunk before `pos` to `buf`, returning the new position
+    fn read_back(&self, pos: u64, buf: &mut Vec<u8>) -> io::Result<u64> {
+        let from = pos.saturating_sub(TAIL_CHUNK);
+        let mut chunk = Vec::with_capacity((pos - from) as usize);
+        self.reader(from, pos)?.read_to_end(&mut chunk)?;
+        chunk.extend_from_slice(buf);
+        *buf = chunk;
+        Ok(from)
+    }
+
+    /// Byte offset where line `line` (zero based) starts, or the end if the log is shorter
+    pub fn line_offset(&self, line: usize) -> io::Result<u64> {
+        let len = self.size();
+        let mut reader = BufReader::new(self.reader(0, len)?);
+        let mut offset = 0;
+        let mut buf = Vec::new();
+
+        for _ in 0..line {
+            buf.clear();
+            let read = reader.read_until(b'\n', &mut buf)?;
+            if read == 0 || buf.last() != Some(&b'\n') {
+                break;
+            }
+            offset += read as u64;
+        }
+
+        Ok(offset)
+    }
+}
+
+fn decode_line(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// Appends log lines to the live file, rotating it into the archive once it
+/// grows past the configured size
+pub struct LogWriter {
+    workspace: String,
+    env: String,
+    session: String,
+    max_bytes: u64,
+    file: File,
+    written: u64,
+}
+
+impl LogWriter {
+    pub fn create(
+        workspace: &str,
+        env: &str,
+        session: &str,
+        retention: &LogRetention,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(tooling_dir(workspace))?;
+        let file = File::create(log_path(workspace, env))?;
+
+        Ok(Self {
+            workspace: workspace.to_string(),
+            env: env.to_string(),
+            session: session.to_string(),
+            max_bytes: retention.max_file_bytes,
+            file,
+            written: 0,
+        })
+    }
+
+    /// Write a line stamped with its capture time and stream
+    pub fn write_line(&mut self, stream: LogStream, line: &str) -> io::Result<()> {
+        let header = record_header(stream);
+        writeln!(self.file, "{}{}", header, line)?;
+        self.written += (header.len() + line.len()) as u64 + 1;
+
+        if self.max_bytes > 0 && self.written >= self.max_bytes {
+            self.rotate()?;
+        }
+
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        let part = next_part(&self.workspace, &self.env, &self.session)?;
+        fs::rename(
+            log_path(&self.workspace, &self.env),
+            archive_path(&self.workspace, &self.env, &self.session, part),
+        )?;
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_path(&self.workspace, &self.env))?;
+        self.written = 0;
+
+        Ok(())
+    }
+}
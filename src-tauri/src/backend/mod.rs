@@ -1,9 +1,27 @@
+pub mod build_metrics;
+#[cfg(unix)]
+pub mod control;
 pub mod generator;
 pub mod git;
+pub mod inheritance;
+pub mod interpolation;
 pub mod project;
 pub mod tilt_manager;
+pub mod tilt_resources;
 // pub mod project_manager;
 pub mod dependency_graph;
 pub mod errors;
+pub mod events;
+pub mod host;
 pub mod ipc;
+pub mod jobs;
+pub mod log_records;
+pub mod log_search;
+pub mod log_store;
+pub mod notifier;
 pub mod ports;
+pub mod process;
+pub mod promotion;
+pub mod redaction;
+pub mod secrets;
+pub mod session_history;
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::app_state::load_state;
+use crate::app_state::model::NotificationPreferences;
+use crate::backend::tilt_resources::UiResource;
+
+/// Resource name used for notifications about Tilt itself
+const TILT_RESOURCE: &str = "(tilt)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationKind {
+    BuildFailed,
+    RuntimeError,
+    Recovered,
+    TiltExited,
+}
+
+impl NotificationKind {
+    fn title(&self) -> &'static str {
+        match self {
+            NotificationKind::BuildFailed => "Build failed",
+            NotificationKind::RuntimeError => "Runtime error",
+            NotificationKind::Recovered => "Resource recovered",
+            NotificationKind::TiltExited => "Tilt exited",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ResourceHealth {
+    pub update_status: String,
+    pub runtime_status: String,
+}
+
+impl ResourceHealth {
+    fn failing(&self) -> bool {
+        self.update_status == "error" || self.runtime_status == "error"
+    }
+}
+
+/// Last seen status per resource and last notification per key, across polls
+#[derive(Default)]
+struct Tracker {
+    health: HashMap<String, ResourceHealth>,
+    sent: HashMap<(String, NotificationKind), Instant>,
+}
+
+static TRACKER: LazyLock<Mutex<Tracker>> = LazyLock::new(|| Mutex::new(Tracker::default()));
+
+fn resource_key(workspace: &str, env: &str, resource: &str) -> String {
+    format!("{}::{}::{}", workspace, env, resource)
+}
+
+fn project_label(workspace: &str) -> String {
+    Path::new(workspace)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| workspace.to_string())
+}
+
+pub fn is_muted(prefs: &NotificationPreferences, workspace: &str, resource: &str) -> bool {
+    !prefs.enabled
+        || prefs.muted_projects.iter().any(|p| p == workspace)
+        || prefs
+            .muted_services
+            .get(workspace)
+            .is_some_and(|services| services.iter().any(|s| s == resource))
+}
+
+/// Transitions worth a notification between two observations of a resource
+pub fn transitions(previous: &ResourceHealth, current: &ResourceHealth) -> Vec<NotificationKind> {
+    let mut kinds = Vec::new();
+
+    if current.update_status == "error" && previous.update_status != "error" {
+        kinds.push(NotificationKind::BuildFailed);
+    }
+    if current.runtime_status == "error" && previous.runtime_status != "error" {
+        kinds.push(NotificationKind::RuntimeError);
+    }
+    if previous.failing() && !current.failing() && current.runtime_status == "ok" {
+        kinds.push(NotificationKind::Recovered);
+    }
+
+    kinds
+}
+
+/// Compare a poll of Tilt's resources with the previous one and notify on changes
+pub fn notify_resource_changes(
+    app: &tauri::AppHandle,
+    workspace: &str,
+    env: &str,
+    resources: &[UiResource],
+) {
+    let prefs = load_state(app).preferences.notifications;
+    let mut pending = Vec::new();
+
+    {
+        let mut tracker = TRACKER.lock().unwrap_or_else(|e| e.into_inner());
+
+        for resource in resources {
+            let current = ResourceHealth {
+                update_status: resource.status.update_status.clone(),
+                runtime_status: resource.status.runtime_status.clone(),
+            };
+
+            let key = resource_key(workspace, env, resource.name());
+            let previous = tracker
+                .health
+                .insert(key, current.clone())
+                .unwrap_or_default();
+
+            for kind in transitions(&previous, &current) {
+                pending.push((resource.name().to_string(), kind));
+            }
+        }
+    }
+
+    for (resource, kind) in pending {
+        send(app, &prefs, workspace, env, &resource, kind, None);
+    }
+}
+
+/// Drop the statuses seen for an environment so the next session starts clean
+pub fn forget_environment(workspace: &str, env: &str) {
+    let prefix = resource_key(workspace, env, "");
+    let mut tracker = TRACKER.lock().unwrap_or_else(|e| e.into_inner());
+    tracker.health.retain(|key, _| !key.starts_with(&prefix));
+    tracker.sent.retain(|(key, _), _| !key.starts_with(&prefix));
+}
+
+/// Whether the same notification for the same resource went out less than
+/// `debounce` ago. Records the notification as sent when it did not.
+pub fn debounced(
+    workspace: &str,
+    env: &str,
+    resource: &str,
+    kind: NotificationKind,
+    debounce: Duration,
+) -> bool {
+    let key = (resource_key(workspace, env, resource), kind);
+    let mut tracker = TRACKER.lock().unwrap_or_else(|e| e.into_inner());
+
+    if tracker
+        .sent
+        .get(&key)
+        .is_some_and(|sent| sent.elapsed() < debounce)
+    {
+        return true;
+    }
+    tracker.sent.insert(key, Instant::now());
+    false
+}
+
+/// Notify that Tilt stopped without being asked to and forget its resources
+pub fn notify_tilt_exited(
+    app: &tauri::AppHandle,
+    workspace: &str,
+    env: &str,
+    exit_code: Option<i32>,
+) {
+    forget_environment(workspace, env);
+
+    let prefs = load_state(app).preferences.notifications;
+    let detail = exit_code.map(|code| format!("exit code {}", code));
+    send(
+        app,
+        &prefs,
+        workspace,
+        env,
+        TILT_RESOURCE,
+        NotificationKind::TiltExited,
+        detail,
+    );
+}
+
+fn send(
+    app: &tauri::AppHandle,
+    prefs: &NotificationPreferences,
+    workspace: &str,
+    env: &str,
+    resource: &str,
+    kind: NotificationKind,
+    detail: Option<String>,
+) {
+    if is_muted(prefs, workspace, resource) {
+        return;
+    }
+
+    let debounce = Duration::from_secs(prefs.debounce_secs);
+    if debounced(workspace, env, resource, kind, debounce) {
+        return;
+    }
+
+    let body = match (kind, detail) {
+        (NotificationKind::TiltExited, Some(detail)) => format!("Tilt stopped ({})", detail),
+        (NotificationKind::TiltExited, None) => "Tilt stopped unexpectedly".to_string(),
+        (_, _) => format!("{}: {}", resource, kind.title().to_lowercase()),
+    };
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(format!(
+            "{} · {} ({})",
+            kind.title(),
+            project_label(workspace),
+            env
+        ))
+        .body(body)
+        .show()
+    {
+        eprintln!("Failed to show notification: {}", e);
+    }
+}
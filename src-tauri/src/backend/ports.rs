@@ -1,10 +1,17 @@
-// use std::net::{SocketAddr, IpAddr, Ipv4Addr};
-// use std::time::Duration;
+use std::io;
+use std::net::{Ipv4Addr, TcpListener};
 
-// pub async fn is_port_open(port: u16) -> bool {
-//     let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port);
-//     tokio::time::timeout(Duration::from_secs(1), tokio::net::TcpStream::connect(addr))
-//         .await
-//         .map(|res| res.is_ok())
-//         .unwrap_or(false)
-// }
+/// Where Tilt serves its API and web UI unless told otherwise
+pub const TILT_DEFAULT_PORT: u16 = 10350;
+
+/// Ports after the default tried before giving up
+const TILT_PORT_RANGE: u16 = 100;
+
+/// First port from Tilt's default that nothing listens on and that is not
+/// in `taken`, the ports handed to Tilts that may not have bound them yet
+pub fn free_tilt_port(taken: &[u16]) -> io::Result<u16> {
+    (TILT_DEFAULT_PORT..TILT_DEFAULT_PORT + TILT_PORT_RANGE)
+        .filter(|port| !taken.contains(port))
+        .find(|port| TcpListener::bind((Ipv4Addr::LOCALHOST, *port)).is_ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::AddrInUse, "No free port for Tilt"))
+}
@@ -0,0 +1,482 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use tauri_plugin_shell::{process::CommandEvent, ShellExt};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+/// Result of a program run to completion
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOutput {
+    pub success: bool,
+    /// `None` when the program was killed by a signal
+    pub code: Option<i32>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl ProcessOutput {
+    /// A successful run printing `stdout`
+    pub fn ok(stdout: &str) -> Self {
+        ProcessOutput {
+            success: true,
+            code: Some(0),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: Vec::new(),
+        }
+    }
+
+    /// A failed run exiting with `code` and printing `stderr`
+    pub fn failed(code: i32, stderr: &str) -> Self {
+        ProcessOutput {
+            success: false,
+            code: Some(code),
+            stdout: Vec::new(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+}
+
+/// Output of a long-running program, one line per event without the line ending
+#[derive(Debug, Clone)]
+pub enum ProcessEvent {
+    Stdout(String),
+    Stderr(String),
+    Terminated(Option<i32>),
+}
+
+pub struct SpawnedProcess {
+    pub pid: u32,
+    /// Ends after `Terminated`
+    pub events: mpsc::UnboundedReceiver<ProcessEvent>,
+}
+
+/// A line of output without its line ending
+pub fn decode_line(line: &[u8]) -> String {
+    String::from_utf8_lossy(line)
+        .trim_end_matches(['\r', '\n'])
+        .to_string()
+}
+
+/// Starts programs and watches them. Everything that runs an external
+/// program (Tilt, git, the editor) goes through one, so it can be swapped
+/// for `ScriptedRunner` when nothing real should run.
+pub trait ProcessRunner: Clone + Send + Sync + 'static {
+    /// Run a program to completion and capture its output
+    fn output(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> impl Future<Output = io::Result<ProcessOutput>> + Send;
+
+    /// Run a program to completion and return its exit code
+    fn status(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> impl Future<Output = io::Result<Option<i32>>> + Send;
+
+    /// Start a long-running program and stream its output
+    fn spawn(&self, program: &str, args: &[&str], cwd: Option<&Path>)
+        -> io::Result<SpawnedProcess>;
+
+    /// Kill a process started by `spawn`. Fails when the kill command does.
+    fn kill(&self, pid: u32) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            let id = pid.to_string();
+            #[cfg(unix)]
+            let output = self.output("kill", &[&id], None).await?;
+            #[cfg(not(unix))]
+            let output = self.output("taskkill", &["/F", "/PID", &id], None).await?;
+
+            if !output.success {
+                return Err(io::Error::other(format!(
+                    "Failed to kill process {}: {}",
+                    pid,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(())
+        }
+    }
+
+    /// Whether a process is still running
+    fn is_alive(&self, pid: u32) -> impl Future<Output = bool> + Send {
+        async move {
+            #[cfg(unix)]
+            {
+                self.output("kill", &["-0", &pid.to_string()], None)
+                    .await
+                    .map(|output| output.success)
+                    .unwrap_or(false)
+            }
+            #[cfg(not(unix))]
+            {
+                self.output("tasklist", &["/FI", &format!("PID eq {}", pid)], None)
+                    .await
+                    .map(|output| {
+                        String::from_utf8_lossy(&output.stdout).contains(&pid.to_string())
+                    })
+                    .unwrap_or(false)
+            }
+        }
+    }
+}
+
+/// Runs programs through the Tauri shell plugin
+#[derive(Clone)]
+pub struct ShellRunner {
+    app: tauri::AppHandle,
+}
+
+impl ShellRunner {
+    pub fn new(app: tauri::AppHandle) -> Self {
+        ShellRunner { app }
+    }
+
+    fn command(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> tauri_plugin_shell::process::Command {
+        let mut command = self.app.shell().command(program).args(args);
+        if let Some(cwd) = cwd {
+            command = command.current_dir(cwd);
+        }
+        command
+    }
+}
+
+impl ProcessRunner for ShellRunner {
+    async fn output(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> io::Result<ProcessOutput> {
+        let output = self
+            .command(program, args, cwd)
+            .output()
+            .await
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        Ok(ProcessOutput {
+            success: output.status.success(),
+            code: output.status.code(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+
+    async fn status(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> io::Result<Option<i32>> {
+        // The shell plugin has no inherited stdio, so the output is dropped
+        Ok(self.output(program, args, cwd).await?.code)
+    }
+
+    fn spawn(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> io::Result<SpawnedProcess> {
+        let (mut rx, child) = self
+            .command(program, args, cwd)
+            .spawn()
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        let pid = child.pid();
+
+        let (tx, events) = mpsc::unbounded_channel();
+        tauri::async_runtime::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                let event = match event {
+                    CommandEvent::Stdout(line) => ProcessEvent::Stdout(decode_line(&line)),
+                    CommandEvent::Stderr(line) => ProcessEvent::Stderr(decode_line(&line)),
+                    CommandEvent::Terminated(payload) => ProcessEvent::Terminated(payload.code),
+                    _ => continue,
+                };
+                let terminated = matches!(event, ProcessEvent::Terminated(_));
+                if tx.send(event).is_err() || terminated {
+                    break;
+                }
+            }
+        });
+
+        Ok(SpawnedProcess { pid, events })
+    }
+}
+
+/// Runs programs with tokio, for use outside the desktop app
+#[derive(Clone, Default)]
+pub struct TokioRunner;
+
+fn tokio_command(program: &str, args: &[&str], cwd: Option<&Path>) -> Command {
+    let mut command = Command::new(program);
+    command.args(args).stdin(Stdio::null());
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    command
+}
+
+/// Forward the lines of a pipe until it closes
+async fn forward_lines(
+    pipe: impl AsyncRead + Unpin,
+    tx: mpsc::UnboundedSender<ProcessEvent>,
+    event: fn(String) -> ProcessEvent,
+) {
+    let mut reader = BufReader::new(pipe);
+    let mut line = Vec::new();
+
+    while let Ok(read) = reader.read_until(b'\n', &mut line).await {
+        if read == 0 || tx.send(event(decode_line(&line))).is_err() {
+            break;
+        }
+        line.clear();
+    }
+}
+
+impl ProcessRunner for TokioRunner {
+    async fn output(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> io::Result<ProcessOutput> {
+        let output = tokio_command(program, args, cwd).output().await?;
+
+        Ok(ProcessOutput {
+            success: output.status.success(),
+            code: output.status.code(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+
+    async fn status(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> io::Result<Option<i32>> {
+        Ok(tokio_command(program, args, cwd).status().await?.code())
+    }
+
+    fn spawn(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> io::Result<SpawnedProcess> {
+        let mut child = tokio_command(program, args, cwd)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let pid = child.id().unwrap_or_default();
+        let (tx, events) = mpsc::unbounded_channel();
+
+        let stdout = child
+            .stdout
+            .take()
+            .map(|pipe| tokio::spawn(forward_lines(pipe, tx.clone(), ProcessEvent::Stdout)));
+        let stderr = child
+            .stderr
+            .take()
+            .map(|pipe| tokio::spawn(forward_lines(pipe, tx.clone(), ProcessEvent::Stderr)));
+
+        tokio::spawn(async move {
+            // All output is forwarded before the exit is reported
+            for reader in [stdout, stderr].into_iter().flatten() {
+                let _ = reader.await;
+            }
+            let code = child.wait().await.ok().and_then(|status| status.code());
+            let _ = tx.send(ProcessEvent::Terminated(code));
+        });
+
+        Ok(SpawnedProcess { pid, events })
+    }
+}
+
+/// One step of a scripted long-running program
+#[derive(Debug, Clone)]
+pub enum ScriptStep {
+    Stdout(String),
+    Stderr(String),
+    /// The program exits; without it the program runs until killed
+    Exit(Option<i32>),
+}
+
+/// A program run recorded by `ScriptedRunner`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+}
+
+#[derive(Default)]
+struct Script {
+    outputs: HashMap<String, VecDeque<ProcessOutput>>,
+    spawns: HashMap<String, Vec<ScriptStep>>,
+    running: HashMap<u32, mpsc::UnboundedSender<ProcessEvent>>,
+    invocations: Vec<Invocation>,
+    next_pid: u32,
+}
+
+/// Fake runner answering from a script instead of starting anything.
+/// Programs without a scripted answer are reported as not found.
+#[derive(Clone, Default)]
+pub struct ScriptedRunner {
+    script: Arc<Mutex<Script>>,
+}
+
+impl ScriptedRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue the result of the next run of `program`; the last one repeats
+    pub fn on_output(&self, program: &str, output: ProcessOutput) -> &Self {
+        self.lock()
+            .outputs
+            .entry(program.to_string())
+            .or_default()
+            .push_back(output);
+        self
+    }
+
+    /// What `program` prints and does when spawned
+    pub fn on_spawn(&self, program: &str, steps: Vec<ScriptStep>) -> &Self {
+        self.lock().spawns.insert(program.to_string(), steps);
+        self
+    }
+
+    /// Every run so far, in order
+    pub fn invocations(&self) -> Vec<Invocation> {
+        self.lock().invocations.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Script> {
+        self.script.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(&self, program: &str, args: &[&str], cwd: Option<&Path>) {
+        self.lock().invocations.push(Invocation {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            cwd: cwd.map(Path::to_path_buf),
+        });
+    }
+
+    fn next_output(&self, program: &str) -> io::Result<ProcessOutput> {
+        let mut script = self.lock();
+        let queue = script
+            .outputs
+            .get_mut(program)
+            .filter(|queue| !queue.is_empty())
+            .ok_or_else(|| not_scripted(program))?;
+
+        if queue.len() > 1 {
+            Ok(queue.pop_front().unwrap_or_default())
+        } else {
+            Ok(queue.front().cloned().unwrap_or_default())
+        }
+    }
+}
+
+fn not_scripted(program: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("No scripted run for {}", program),
+    )
+}
+
+impl ProcessRunner for ScriptedRunner {
+    async fn output(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> io::Result<ProcessOutput> {
+        self.record(program, args, cwd);
+        self.next_output(program)
+    }
+
+    async fn status(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> io::Result<Option<i32>> {
+        self.record(program, args, cwd);
+        Ok(self.next_output(program)?.code)
+    }
+
+    fn spawn(
+        &self,
+        program: &str,
+        args: &[&str],
+        cwd: Option<&Path>,
+    ) -> io::Result<SpawnedProcess> {
+        self.record(program, args, cwd);
+
+        let mut script = self.lock();
+        let steps = script
+            .spawns
+            .get(program)
+            .cloned()
+            .ok_or_else(|| not_scripted(program))?;
+        script.next_pid += 1;
+        let pid = script.next_pid;
+
+        let (tx, events) = mpsc::unbounded_channel();
+        for step in steps {
+            let event = match step {
+                ScriptStep::Stdout(line) => ProcessEvent::Stdout(line),
+                ScriptStep::Stderr(line) => ProcessEvent::Stderr(line),
+                ScriptStep::Exit(code) => {
+                    let _ = tx.send(ProcessEvent::Terminated(code));
+                    return Ok(SpawnedProcess { pid, events });
+                }
+            };
+            let _ = tx.send(event);
+        }
+        script.running.insert(pid, tx);
+
+        Ok(SpawnedProcess { pid, events })
+    }
+
+    /// A failing scripted `kill` output leaves the process running
+    async fn kill(&self, pid: u32) -> io::Result<()> {
+        self.record("kill", &[&pid.to_string()], None);
+        if let Ok(output) = self.next_output("kill") {
+            if !output.success {
+                return Err(io::Error::other(format!(
+                    "Failed to kill process {}: {}",
+                    pid,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+        }
+        if let Some(tx) = self.lock().running.remove(&pid) {
+            let _ = tx.send(ProcessEvent::Terminated(None));
+        }
+        Ok(())
+    }
+
+    async fn is_alive(&self, pid: u32) -> bool {
+        self.lock().running.contains_key(&pid)
+    }
+}
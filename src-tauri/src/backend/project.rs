@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use ts_rs::TS;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(optional_fields = nullable)]
 pub struct Service {
     pub name: String,
     pub path: Option<String>,
@@ -14,65 +16,105 @@ pub struct Service {
     pub depends_on: Option<Vec<String>>,
     pub helm: Option<Helm>,
     pub kustomize: Option<Kustomize>,
+    pub replicas: Option<u32>,
+    /// Env entries whose values are kept out of the project files
+    pub secrets: Option<Vec<SecretRef>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An env entry marked secret. The value lives in the local encrypted store,
+/// or in a gitignored dotenv file when `file` is set.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(optional_fields = nullable)]
+pub struct SecretRef {
+    pub key: String,
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(optional_fields = nullable)]
 pub struct Repo {
     pub url: String,
     pub branch: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct Docker {
     pub context: String,
     pub dockerfile: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct K8s {
     pub manifests: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(optional_fields = nullable)]
 pub struct Helm {
+    /// `.` or `./<dir>` for a chart in the service's directory, anything else
+    /// is passed to Tilt as is
     pub chart: String,
     pub values: Option<HashMap<String, serde_json::Value>>,
     pub namespace: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct Kustomize {
     pub path: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct Project {
     pub project: ProjectInfo,
     pub environments: HashMap<String, Environment>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(optional_fields = nullable)]
 pub struct ProjectInfo {
+    /// Stable across renames and moves, keys the project's stored secrets.
+    /// Projects created before it existed get one when a secret is set.
+    #[serde(default)]
+    pub id: Option<String>,
     pub name: String,
     pub workspace_path: String,
     pub tilt: Tilt,
     pub services_path: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct Tilt {
     pub mode: TiltMode,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub enum TiltMode {
     Root,
     PerRepo,
     Hybrid,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(optional_fields = nullable)]
 pub struct Environment {
     pub shared_env: HashMap<String, String>,
     pub services: Vec<Service>,
+    /// Name of the environment this one inherits services and shared env from
+    pub extends: Option<String>,
+    /// Per-service patches applied on top of the inherited services
+    pub overrides: Option<HashMap<String, ServiceOverride>>,
+    /// Shared env entries whose values are kept out of the project files
+    pub secrets: Option<Vec<SecretRef>>,
+}
+
+/// Partial service definition layered over an inherited service
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(optional_fields = nullable)]
+pub struct ServiceOverride {
+    pub port: Option<u16>,
+    pub enabled: Option<bool>,
+    pub env: Option<HashMap<String, String>>,
+    pub branch: Option<String>,
+    pub helm_values: Option<HashMap<String, serde_json::Value>>,
+    pub replicas: Option<u32>,
 }
@@ -0,0 +1,318 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use ts_rs::TS;
+
+use crate::backend::errors::AppError;
+use crate::backend::inheritance::{inheritance_chain, resolve_environment};
+use crate::backend::project::{Environment, Project, Service};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A single env var that differs between two environments.
+/// `added` means present in the source only, `removed` in the target only.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct EnvVarChange {
+    pub key: String,
+    pub change: ChangeKind,
+    pub source_value: Option<String>,
+    pub target_value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ServiceChange {
+    pub name: String,
+    pub change: ChangeKind,
+    /// Top-level service fields that differ, excluding `env`
+    pub fields: Vec<String>,
+    pub env: Vec<EnvVarChange>,
+    pub source: Option<Service>,
+    pub target: Option<Service>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct EnvDiff {
+    pub source: String,
+    pub target: String,
+    pub shared_env: Vec<EnvVarChange>,
+    pub services: Vec<ServiceChange>,
+}
+
+/// The parts of a diff to apply to the target environment
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct PromoteSelection {
+    /// Shared env keys to copy (or remove) from the source
+    #[serde(default)]
+    #[ts(as = "Option<Vec<String>>", optional)]
+    pub shared_env: Vec<String>,
+    /// Services to copy (or remove) as a whole
+    #[serde(default)]
+    #[ts(as = "Option<Vec<String>>", optional)]
+    pub services: Vec<String>,
+    /// Individual service env keys to copy (or remove), keyed by service name
+    #[serde(default)]
+    #[ts(as = "Option<HashMap<String, Vec<String>>>", optional)]
+    pub service_env: HashMap<String, Vec<String>>,
+}
+
+/// Compare the effective configuration of two environments
+pub fn diff_environments(project: &Project, source: &str, target: &str) -> Result<EnvDiff> {
+    let source_env = resolve_environment(project, source)?;
+    let target_env = resolve_environment(project, target)?;
+
+    let shared_env = diff_env_vars(&source_env.shared_env, &target_env.shared_env);
+
+    let mut names: Vec<&str> = source_env
+        .services
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    for svc in &target_env.services {
+        if !names.contains(&svc.name.as_str()) {
+            names.push(&svc.name);
+        }
+    }
+
+    let mut services = Vec::new();
+    for name in names {
+        let source_svc = source_env.services.iter().find(|s| s.name == name);
+        let target_svc = target_env.services.iter().find(|s| s.name == name);
+
+        let change = match (source_svc, target_svc) {
+            (Some(_), None) => ChangeKind::Added,
+            (None, Some(_)) => ChangeKind::Removed,
+            _ => ChangeKind::Changed,
+        };
+
+        let empty = HashMap::new();
+        let env = diff_env_vars(
+            source_svc.and_then(|s| s.env.as_ref()).unwrap_or(&empty),
+            target_svc.and_then(|s| s.env.as_ref()).unwrap_or(&empty),
+        );
+        let fields = diff_fields(source_svc, target_svc)?;
+
+        if change == ChangeKind::Changed && fields.is_empty() && env.is_empty() {
+            continue;
+        }
+
+        services.push(ServiceChange {
+            name: name.to_string(),
+            change,
+            fields,
+            env,
+            source: source_svc.cloned(),
+            target: target_svc.cloned(),
+        });
+    }
+
+    Ok(EnvDiff {
+        source: source.to_string(),
+        target: target.to_string(),
+        shared_env,
+        services,
+    })
+}
+
+/// Apply the selected parts of the source → target diff to `project`.
+///
+/// Services that the target only inherits are copied into its own `services`
+/// before being edited, so the promotion never leaks into the parent.
+/// Removing a shared env key or a service the target inherits is rejected,
+/// an environment has no way to drop what its parent defines.
+pub fn apply_promotion(
+    project: &mut Project,
+    source: &str,
+    target: &str,
+    selection: &PromoteSelection,
+) -> Result<()> {
+    let source_env = resolve_environment(project, source)?;
+    let target_effective = resolve_environment(project, target)?;
+
+    for key in &selection.shared_env {
+        if source_env.shared_env.contains_key(key) {
+            continue;
+        }
+        if let Some(parent) =
+            inherited_from(project, target, |env| env.shared_env.contains_key(key))?
+        {
+            return Err(AppError::Invalid(format!(
+                "Cannot remove {} from {}, it is inherited from {}",
+                key, target, parent
+            ))
+            .into());
+        }
+    }
+    for name in &selection.services {
+        if source_env.services.iter().any(|s| &s.name == name) {
+            continue;
+        }
+        if let Some(parent) = inherited_from(project, target, |env| {
+            env.services.iter().any(|s| &s.name == name)
+        })? {
+            return Err(AppError::Invalid(format!(
+                "Cannot remove service {} from {}, it is inherited from {}",
+                name, target, parent
+            ))
+            .into());
+        }
+    }
+
+    let target_env = project
+        .environments
+        .get_mut(target)
+        .ok_or_else(|| AppError::env_not_found(target))?;
+
+    for key in &selection.shared_env {
+        match source_env.shared_env.get(key) {
+            Some(value) => {
+                target_env.shared_env.insert(key.clone(), value.clone());
+            }
+            None => {
+                target_env.shared_env.remove(key);
+            }
+        }
+    }
+
+    for name in &selection.services {
+        let source_svc = source_env.services.iter().find(|s| &s.name == name);
+
+        if let Some(overrides) = target_env.overrides.as_mut() {
+            overrides.remove(name);
+        }
+
+        match source_svc {
+            Some(svc) => match target_env.services.iter_mut().find(|s| &s.name == name) {
+                Some(existing) => *existing = svc.clone(),
+                None => target_env.services.push(svc.clone()),
+            },
+            None => target_env.services.retain(|s| &s.name != name),
+        }
+    }
+
+    for (name, keys) in &selection.service_env {
+        let source_svc = source_env
+            .services
+            .iter()
+            .find(|s| &s.name == name)
+            .ok_or_else(|| AppError::service_not_found(name, Some(source)))?;
+
+        // The override env is applied last and would undo the promotion
+        let override_env = target_env
+            .overrides
+            .as_mut()
+            .and_then(|overrides| overrides.get_mut(name))
+            .and_then(|patch| patch.env.as_mut());
+        if let Some(override_env) = override_env {
+            for key in keys {
+                override_env.remove(key);
+            }
+        }
+
+        let target_svc = materialize_service(target_env, &target_effective, name)
+            .ok_or_else(|| AppError::service_not_found(name, Some(target)))?;
+
+        let target_vars = target_svc.env.get_or_insert_with(HashMap::new);
+        for key in keys {
+            match source_svc.env.as_ref().and_then(|e| e.get(key)) {
+                Some(value) => {
+                    target_vars.insert(key.clone(), value.clone());
+                }
+                None => {
+                    target_vars.remove(key);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The closest environment `target` inherits from whose own definition matches
+fn inherited_from(
+    project: &Project,
+    target: &str,
+    defines: impl Fn(&Environment) -> bool,
+) -> Result<Option<String>> {
+    let chain = inheritance_chain(project, target)?;
+    Ok(chain
+        .iter()
+        .rev()
+        .skip(1)
+        .find(|name| defines(&project.environments[*name]))
+        .cloned())
+}
+
+/// Return the target's own definition of a service, copying the effective
+/// (inherited) definition into it first when needed
+fn materialize_service<'a>(
+    target_env: &'a mut Environment,
+    effective: &Environment,
+    name: &str,
+) -> Option<&'a mut Service> {
+    if !target_env.services.iter().any(|s| s.name == name) {
+        let svc = effective.services.iter().find(|s| s.name == name)?;
+        target_env.services.push(svc.clone());
+
+        // The effective definition already includes the override
+        if let Some(overrides) = target_env.overrides.as_mut() {
+            overrides.remove(name);
+        }
+    }
+
+    target_env.services.iter_mut().find(|s| s.name == name)
+}
+
+fn diff_env_vars(
+    source: &HashMap<String, String>,
+    target: &HashMap<String, String>,
+) -> Vec<EnvVarChange> {
+    let keys: BTreeSet<&String> = source.keys().chain(target.keys()).collect();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let source_value = source.get(key);
+            let target_value = target.get(key);
+
+            let change = match (source_value, target_value) {
+                (Some(_), None) => ChangeKind::Added,
+                (None, Some(_)) => ChangeKind::Removed,
+                (Some(a), Some(b)) if a != b => ChangeKind::Changed,
+                _ => return None,
+            };
+
+            Some(EnvVarChange {
+                key: key.clone(),
+                change,
+                source_value: source_value.cloned(),
+                target_value: target_value.cloned(),
+            })
+        })
+        .collect()
+}
+
+fn diff_fields(source: Option<&Service>, target: Option<&Service>) -> Result<Vec<String>> {
+    let (Some(source), Some(target)) = (source, target) else {
+        return Ok(Vec::new());
+    };
+
+    let source = serde_json::to_value(source)?;
+    let target = serde_json::to_value(target)?;
+
+    let (Some(source), Some(target)) = (source.as_object(), target.as_object()) else {
+        return Ok(Vec::new());
+    };
+
+    let keys: BTreeSet<&String> = source.keys().chain(target.keys()).collect();
+
+    Ok(keys
+        .into_iter()
+        .filter(|k| k.as_str() != "env" && source.get(*k) != target.get(*k))
+        .cloned()
+        .collect())
+}
@@ -0,0 +1,125 @@
+use anyhow::Result;
+use regex::Regex;
+use std::path::Path;
+
+use crate::backend::errors::AppError;
+use crate::backend::inheritance::resolve_environment;
+use crate::backend::interpolation::{secret_keys, service_env, InterpolationContext};
+use crate::backend::project::Project;
+use crate::backend::secrets::{secret_values, MASK};
+
+/// Key suffixes whose values are treated as sensitive even when not marked secret
+const SENSITIVE_SUFFIXES: [&str; 3] = ["_TOKEN", "_PASSWORD", "_KEY"];
+
+/// Values shorter than this are not redacted, they would mask half the log
+const MIN_VALUE_LEN: usize = 4;
+
+/// Masks known sensitive values and user patterns in log lines
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    values: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl Redactor {
+    /// Build a redactor from literal values and regex patterns.
+    /// An invalid pattern is an error rather than a secret left unmasked.
+    pub fn new(
+        values: impl IntoIterator<Item = String>,
+        patterns: &[String],
+    ) -> Result<Self, AppError> {
+        let mut values: Vec<String> = values
+            .into_iter()
+            .filter(|v| v.len() >= MIN_VALUE_LEN)
+            .collect();
+
+        values.sort();
+        values.dedup();
+        // Replace longer values first so a value containing another is fully masked
+        values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+
+        let patterns = compile_patterns(patterns)?;
+
+        Ok(Self { values, patterns })
+    }
+
+    pub fn redact(&self, line: &str) -> String {
+        let mut line = line.to_string();
+
+        for value in &self.values {
+            if line.contains(value.as_str()) {
+                line = line.replace(value.as_str(), MASK);
+            }
+        }
+
+        for pattern in &self.patterns {
+            line = pattern.replace_all(&line, MASK).into_owned();
+        }
+
+        line
+    }
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>, AppError> {
+    patterns
+        .iter()
+        .map(|p| {
+            Regex::new(p).map_err(|e| {
+                AppError::Invalid(format!("Invalid log redaction pattern {}: {}", p, e))
+            })
+        })
+        .collect()
+}
+
+/// Check the patterns before they are saved to the preferences
+pub fn validate_patterns(patterns: &[String]) -> Result<(), AppError> {
+    compile_patterns(patterns).map(|_| ())
+}
+
+pub fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_ascii_uppercase();
+    SENSITIVE_SUFFIXES
+        .iter()
+        .any(|suffix| key.ends_with(suffix))
+}
+
+/// Collect every value of `env` that must not reach the log file: secret values
+/// plus any shared or service env entry with a sensitive-looking key. Values are
+/// taken after `${...}` interpolation, as the generator writes them.
+pub fn build_redactor(
+    data_dir: &Path,
+    project: &Project,
+    env: &str,
+    patterns: &[String],
+) -> Result<Redactor> {
+    let environment = resolve_environment(project, env)?;
+    let mut values = Vec::new();
+
+    for svc in &environment.services {
+        let secret_keys = secret_keys(&environment, svc);
+        let ctx = InterpolationContext {
+            env_name: env,
+            service: svc,
+            services: &environment.services,
+            secret_keys: &secret_keys,
+        };
+        // A broken reference stops generation, mask the raw values meanwhile
+        let svc_env = service_env(&environment.shared_env, &ctx).unwrap_or_else(|_| {
+            let mut raw = environment.shared_env.clone();
+            raw.extend(svc.env.clone().unwrap_or_default());
+            raw
+        });
+
+        for (key, value) in svc_env {
+            if is_sensitive_key(&key) {
+                values.push(value);
+            }
+        }
+    }
+
+    for service_values in secret_values(data_dir, project, env)?.into_values() {
+        values.extend(service_values.into_values());
+    }
+
+    Ok(Redactor::new(values, patterns)?)
+}
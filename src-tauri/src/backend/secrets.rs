@@ -0,0 +1,609 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use ts_rs::TS;
+
+use crate::backend::errors::AppError;
+use crate::backend::inheritance::{inheritance_chain, resolve_environment};
+use crate::backend::project::{Project, SecretRef};
+
+/// Shown instead of a secret value in every IPC response
+pub const MASK: &str = "********";
+
+/// A secret as reported to the frontend, never carrying the value itself
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SecretEntry {
+    pub service: Option<String>,
+    pub key: String,
+    pub file: Option<String>,
+    pub has_value: bool,
+    pub value: String,
+}
+
+/// A secret ref together with its value, if one is stored
+struct ResolvedSecret {
+    service: Option<String>,
+    secret: SecretRef,
+    value: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedBlob {
+    nonce: String,
+    data: String,
+}
+
+/// Encrypted key/value store under the app data dir.
+///
+/// Values are sealed with ChaCha20-Poly1305 using a random key kept next to
+/// the store with owner-only permissions.
+pub struct SecretStore {
+    dir: PathBuf,
+}
+
+impl SecretStore {
+    pub fn open(data_dir: &Path) -> Self {
+        Self {
+            dir: data_dir.join("secrets"),
+        }
+    }
+
+    fn key_path(&self) -> PathBuf {
+        self.dir.join("store.key")
+    }
+
+    fn store_path(&self) -> PathBuf {
+        self.dir.join("store.json")
+    }
+
+    fn cipher(&self) -> Result<ChaCha20Poly1305> {
+        let key_path = self.key_path();
+
+        let key = if key_path.exists() {
+            fs::read(&key_path).context("Failed to read secret store key")?
+        } else {
+            fs::create_dir_all(&self.dir)?;
+            let key = ChaCha20Poly1305::generate_key(&mut OsRng).to_vec();
+            write_private(&key_path, &key).context("Failed to write secret store key")?;
+            key
+        };
+
+        if key.len() != 32 {
+            return Err(anyhow::anyhow!("Secret store key is corrupted"));
+        }
+
+        Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>> {
+        let store_path = self.store_path();
+        if !store_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let blob: EncryptedBlob = serde_json::from_str(&fs::read_to_string(&store_path)?)?;
+        let nonce = STANDARD.decode(blob.nonce)?;
+        let data = STANDARD.decode(blob.data)?;
+
+        if nonce.len() != 12 {
+            return Err(anyhow::anyhow!("Secret store is corrupted"));
+        }
+
+        let plain = self
+            .cipher()?
+            .decrypt(Nonce::from_slice(&nonce), data.as_ref())
+            .map_err(|_| anyhow::anyhow!("Failed to decrypt secret store"))?;
+
+        Ok(serde_json::from_slice(&plain)?)
+    }
+
+    fn save(&self, values: &HashMap<String, String>) -> Result<()> {
+        let cipher = self.cipher()?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let plain = serde_json::to_vec(values)?;
+        let data = cipher
+            .encrypt(&nonce, plain.as_ref())
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt secret store"))?;
+
+        let blob = EncryptedBlob {
+            nonce: STANDARD.encode(nonce),
+            data: STANDARD.encode(data),
+        };
+
+        fs::create_dir_all(&self.dir)?;
+        write_private(
+            &self.store_path(),
+            serde_json::to_string_pretty(&blob)?.as_bytes(),
+        )?;
+        Ok(())
+    }
+}
+
+/// A new project id, for `ProjectInfo::id`
+pub fn new_project_id() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// What a project's values are stored under: its id, or its folder for
+/// projects created before ids existed
+fn project_key(project: &Project) -> &str {
+    project
+        .project
+        .id
+        .as_deref()
+        .unwrap_or(&project.project.workspace_path)
+}
+
+fn slot(project: &str, env: &str, service: Option<&str>, key: &str) -> String {
+    format!("{}::{}::{}::{}", project, env, service.unwrap_or("*"), key)
+}
+
+/// Move the values stored for `from` to slots of `to`. `map` gets the env and
+/// service of each slot and returns the env and service it goes to, if any.
+/// With `keep` the original values stay, copying instead of moving.
+fn rekey_slots(
+    data_dir: &Path,
+    from: &str,
+    to: &str,
+    keep: bool,
+    map: impl Fn(&str, Option<&str>) -> Option<(String, Option<String>)>,
+) -> Result<()> {
+    let store = SecretStore::open(data_dir);
+    let mut values = store.load()?;
+    let prefix = format!("{}::", from);
+
+    let mut moved = Vec::new();
+    for (old, value) in &values {
+        let Some(rest) = old.strip_prefix(&prefix) else {
+            continue;
+        };
+        let mut parts = rest.splitn(3, "::");
+        let (Some(env), Some(service), Some(key)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let service = (service != "*").then_some(service);
+
+        if let Some((env, service)) = map(env, service) {
+            let new = slot(to, &env, service.as_deref(), key);
+            moved.push((old.clone(), new, value.clone()));
+        }
+    }
+
+    if moved.is_empty() {
+        return Ok(());
+    }
+    if !keep {
+        for (old, _, _) in &moved {
+            values.remove(old);
+        }
+    }
+    for (_, new, value) in moved {
+        values.insert(new, value);
+    }
+    store.save(&values)
+}
+
+/// Give a project created before ids existed its id, taking along the values
+/// stored under its folder
+fn assign_project_id(data_dir: &Path, project: &mut Project) -> Result<()> {
+    let id = new_project_id();
+    rekey_slots(
+        data_dir,
+        &project.project.workspace_path,
+        &id,
+        false,
+        |env, service| Some((env.to_string(), service.map(str::to_string))),
+    )?;
+    project.project.id = Some(id);
+    Ok(())
+}
+
+/// Move the values stored for an environment to its new name
+pub fn rename_env_secrets(
+    data_dir: &Path,
+    project: &Project,
+    env: &str,
+    new_name: &str,
+) -> Result<()> {
+    let key = project_key(project);
+    rekey_slots(data_dir, key, key, false, |e, service| {
+        (e == env).then(|| (new_name.to_string(), service.map(str::to_string)))
+    })
+}
+
+/// Copy the values stored for an environment to a clone of it
+pub fn copy_env_secrets(
+    data_dir: &Path,
+    project: &Project,
+    source: &str,
+    target: &str,
+) -> Result<()> {
+    let key = project_key(project);
+    rekey_slots(data_dir, key, key, true, |e, service| {
+        (e == source).then(|| (target.to_string(), service.map(str::to_string)))
+    })
+}
+
+/// Drop the values stored for a deleted environment
+pub fn delete_env_secrets(data_dir: &Path, project: &Project, env: &str) -> Result<()> {
+    let store = SecretStore::open(data_dir);
+    let mut values = store.load()?;
+    let prefix = format!("{}::{}::", project_key(project), env);
+
+    let before = values.len();
+    values.retain(|slot, _| !slot.starts_with(&prefix));
+    if values.len() == before {
+        return Ok(());
+    }
+    store.save(&values)
+}
+
+/// Move the values stored for a service, in every environment, to its new name
+pub fn rename_service_secrets(
+    data_dir: &Path,
+    project: &Project,
+    service: &str,
+    new_name: &str,
+) -> Result<()> {
+    let key = project_key(project);
+    rekey_slots(data_dir, key, key, false, |env, s| {
+        (s == Some(service)).then(|| (env.to_string(), Some(new_name.to_string())))
+    })
+}
+
+/// Mark an env entry as secret and store its value.
+///
+/// Any plain-text value for the same key is removed from the environment so it
+/// no longer ends up in `environments/*.json`. With `file` set, the value is
+/// written to that gitignored dotenv file instead of the encrypted store; it
+/// must be a relative path inside the workspace.
+pub fn set_secret(
+    data_dir: &Path,
+    project: &mut Project,
+    env: &str,
+    service: Option<&str>,
+    key: &str,
+    value: &str,
+    file: Option<String>,
+) -> Result<()> {
+    let workspace = project.project.workspace_path.clone();
+    let file_path = file
+        .as_deref()
+        .map(|file| workspace_file(Path::new(&workspace), file))
+        .transpose()?;
+
+    if project.project.id.is_none() {
+        assign_project_id(data_dir, project)?;
+    }
+
+    let project_key = project_key(project).to_string();
+    let environment = project
+        .environments
+        .get_mut(env)
+        .ok_or_else(|| AppError::env_not_found(env))?;
+
+    let secret = SecretRef {
+        key: key.to_string(),
+        file: file.clone(),
+    };
+
+    let refs = match service {
+        Some(name) => {
+            let svc = environment
+                .services
+                .iter_mut()
+                .find(|s| s.name == name)
+                .ok_or_else(|| AppError::service_not_found(name, Some(env)))?;
+            if let Some(vars) = svc.env.as_mut() {
+                vars.remove(key);
+            }
+            svc.secrets.get_or_insert_with(Vec::new)
+        }
+        None => {
+            environment.shared_env.remove(key);
+            environment.secrets.get_or_insert_with(Vec::new)
+        }
+    };
+
+    refs.retain(|s| s.key != key);
+    refs.push(secret);
+
+    match (file, file_path) {
+        (Some(file), Some(path)) => {
+            write_dotenv_value(&path, key, value)?;
+            ensure_gitignored(Path::new(&workspace), &file)?;
+        }
+        _ => {
+            let store = SecretStore::open(data_dir);
+            let mut values = store.load()?;
+            values.insert(slot(&project_key, env, service, key), value.to_string());
+            store.save(&values)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Drop a secret and its stored value
+pub fn remove_secret(
+    data_dir: &Path,
+    project: &mut Project,
+    env: &str,
+    service: Option<&str>,
+    key: &str,
+) -> Result<()> {
+    let project_key = project_key(project).to_string();
+    let environment = project
+        .environments
+        .get_mut(env)
+        .ok_or_else(|| AppError::env_not_found(env))?;
+
+    let refs = match service {
+        Some(name) => environment
+            .services
+            .iter_mut()
+            .find(|s| s.name == name)
+            .and_then(|s| s.secrets.as_mut()),
+        None => environment.secrets.as_mut(),
+    };
+
+    if let Some(refs) = refs {
+        refs.retain(|s| s.key != key);
+    }
+
+    let store = SecretStore::open(data_dir);
+    let mut values = store.load()?;
+    if values
+        .remove(&slot(&project_key, env, service, key))
+        .is_some()
+    {
+        store.save(&values)?;
+    }
+
+    Ok(())
+}
+
+/// List the effective secrets of an environment with their values masked
+pub fn list_secrets(data_dir: &Path, project: &Project, env: &str) -> Result<Vec<SecretEntry>> {
+    let resolved = resolved_secrets(data_dir, project, env)?;
+
+    let mut entries: Vec<SecretEntry> = resolved
+        .into_iter()
+        .map(|r| SecretEntry {
+            service: r.service,
+            key: r.secret.key,
+            file: r.secret.file,
+            has_value: r.value.is_some(),
+            value: MASK.to_string(),
+        })
+        .collect();
+
+    entries.sort_by(|a, b| (&a.service, &a.key).cmp(&(&b.service, &b.key)));
+    Ok(entries)
+}
+
+/// Secret values per service for an environment. Shared secrets are included
+/// for every service, with service secrets taking precedence.
+pub fn secret_values(
+    data_dir: &Path,
+    project: &Project,
+    env: &str,
+) -> Result<HashMap<String, HashMap<String, String>>> {
+    let environment = resolve_environment(project, env)?;
+    let resolved = resolved_secrets(data_dir, project, env)?;
+
+    let shared: HashMap<String, String> = resolved
+        .iter()
+        .filter(|r| r.service.is_none())
+        .filter_map(|r| r.value.clone().map(|v| (r.secret.key.clone(), v)))
+        .collect();
+
+    let mut values = HashMap::new();
+    for svc in &environment.services {
+        let mut svc_values = shared.clone();
+        for r in &resolved {
+            if r.service.as_deref() == Some(svc.name.as_str()) {
+                if let Some(value) = &r.value {
+                    svc_values.insert(r.secret.key.clone(), value.clone());
+                }
+            }
+        }
+        values.insert(svc.name.clone(), svc_values);
+    }
+
+    Ok(values)
+}
+
+fn materialized_dir(workspace: &str, env: &str) -> PathBuf {
+    Path::new(workspace)
+        .join(".tooling")
+        .join("secrets")
+        .join(env)
+}
+
+/// Write each service's secrets from `secret_values` to
+/// `.tooling/secrets/<env>/<service>.env` so the generated Tiltfile can turn
+/// them into a Kubernetes `Secret` at `tilt up`. Files of an earlier run are
+/// replaced, the values only stay on disk while Tilt runs.
+pub fn materialize_secrets(
+    workspace: &str,
+    env: &str,
+    values: &HashMap<String, HashMap<String, String>>,
+) -> io::Result<()> {
+    let env_dir = materialized_dir(workspace, env);
+    clear_materialized_secrets(workspace, env)?;
+    fs::create_dir_all(&env_dir)?;
+
+    // Keep materialized values out of version control whatever the repo ignores
+    if let Some(secrets_dir) = env_dir.parent() {
+        fs::write(secrets_dir.join(".gitignore"), "*\n")?;
+    }
+
+    for (service, values) in values {
+        // Written even when empty, the generated Tiltfile reads it for every
+        // service that declares secrets, whether or not values are stored
+        let mut lines: Vec<String> = values.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        lines.sort();
+
+        write_private(
+            &env_dir.join(format!("{}.env", service)),
+            lines.join("\n").as_bytes(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Remove the files `materialize_secrets` wrote for an environment
+pub fn clear_materialized_secrets(workspace: &str, env: &str) -> io::Result<()> {
+    match fs::remove_dir_all(materialized_dir(workspace, env)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Every effective secret ref of an environment. Store lookups fall back through the `extends` chain.
+fn resolved_secrets(data_dir: &Path, project: &Project, env: &str) -> Result<Vec<ResolvedSecret>> {
+    let environment = resolve_environment(project, env)?;
+    let mut chain = inheritance_chain(project, env)?;
+    chain.reverse();
+
+    let workspace = Path::new(&project.project.workspace_path);
+    let store = SecretStore::open(data_dir).load()?;
+
+    let mut refs: Vec<(Option<String>, SecretRef)> = environment
+        .secrets
+        .iter()
+        .flatten()
+        .map(|s| (None, s.clone()))
+        .collect();
+    for svc in &environment.services {
+        for secret in svc.secrets.iter().flatten() {
+            refs.push((Some(svc.name.clone()), secret.clone()));
+        }
+    }
+
+    let mut resolved = Vec::new();
+    for (service, secret) in refs {
+        let value = match &secret.file {
+            Some(file) => read_dotenv(&workspace_file(workspace, file)?)?.remove(&secret.key),
+            None => chain.iter().find_map(|e| {
+                store
+                    .get(&slot(
+                        project_key(project),
+                        e,
+                        service.as_deref(),
+                        &secret.key,
+                    ))
+                    .cloned()
+            }),
+        };
+
+        resolved.push(ResolvedSecret {
+            service,
+            secret,
+            value,
+        });
+    }
+
+    Ok(resolved)
+}
+
+fn read_dotenv(path: &Path) -> Result<HashMap<String, String>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| l.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect())
+}
+
+fn write_dotenv_value(path: &Path, key: &str, value: &str) -> Result<()> {
+    let mut values = read_dotenv(path)?;
+    values.insert(key.to_string(), value.to_string());
+
+    let mut lines: Vec<String> = values.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    lines.sort();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_private(path, (lines.join("\n") + "\n").as_bytes())?;
+    Ok(())
+}
+
+/// Resolve a secret file of the workspace, refusing paths that leave it
+fn workspace_file(workspace: &Path, file: &str) -> Result<PathBuf> {
+    let path = Path::new(file);
+    let inside = path.components().next().is_some()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+
+    if !inside {
+        return Err(AppError::Invalid(format!(
+            "Secret file {} must be a relative path inside the workspace",
+            file
+        ))
+        .into());
+    }
+    Ok(workspace.join(path))
+}
+
+/// Add `file` to the workspace `.gitignore` unless it is already listed
+fn ensure_gitignored(workspace: &Path, file: &str) -> Result<()> {
+    let gitignore = workspace.join(".gitignore");
+    let content = fs::read_to_string(&gitignore).unwrap_or_default();
+    let entry = format!("/{}", file.trim_start_matches("./"));
+
+    if content
+        .lines()
+        .any(|l| l.trim() == entry || l.trim() == file)
+    {
+        return Ok(());
+    }
+
+    let mut content = content;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&entry);
+    content.push('\n');
+
+    fs::write(gitignore, content)?;
+    Ok(())
+}
+
+/// Write a file only its owner can read. It is created that way, so the
+/// contents are never readable by others, not even briefly.
+fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // An existing file keeps its mode when opened
+        if path.exists() {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+
+    options.open(path)?.write_all(contents)
+}
@@ -0,0 +1,183 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+/// Entries kept per environment, oldest are dropped first
+const MAX_HISTORY_ENTRIES: usize = 200;
+
+/// Who ended a Tilt session
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum StopInitiator {
+    #[default]
+    User,
+    Tray,
+    /// A state check found Tilt gone without seeing it exit
+    Watchdog,
+    /// Tilt exited without being asked to stop
+    Crash,
+}
+
+/// One Tilt run of an environment
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+pub struct SessionRecord {
+    /// Same id as the session's logs
+    pub id: String,
+    pub started_at: String,
+    pub stopped_at: Option<String>,
+    pub tilt_version: Option<String>,
+    /// Hash of every generated file under `tilt/<env>` at start
+    pub tiltfile_hash: Option<String>,
+    pub enabled_services: Vec<String>,
+    pub exit_code: Option<i32>,
+    /// Restarts in a row that led to this session, 0 for a fresh start
+    pub restart_count: u32,
+    pub stopped_by: Option<StopInitiator>,
+}
+
+fn history_path(workspace: &str, env: &str) -> PathBuf {
+    Path::new(workspace)
+        .join(".tooling")
+        .join(format!("{}_history.json", env))
+}
+
+fn now() -> String {
+    Local::now().to_rfc3339()
+}
+
+/// Session history of an environment, oldest first
+pub fn read_history(workspace: &str, env: &str) -> Vec<SessionRecord> {
+    fs::read_to_string(history_path(workspace, env))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn write_history(workspace: &str, env: &str, history: &[SessionRecord]) -> io::Result<()> {
+    let path = history_path(workspace, env);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(history)?)
+}
+
+/// Read-modify-write of a history file under a lock on its `.lock` file,
+/// which IPC calls, capture tasks and other app instances all take
+fn modify_history(
+    workspace: &str,
+    env: &str,
+    modify: impl FnOnce(&mut Vec<SessionRecord>),
+) -> io::Result<()> {
+    let path = history_path(workspace, env);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let lock = File::create(path.with_extension("lock"))?;
+    lock.lock()?;
+
+    let mut history = read_history(workspace, env);
+    modify(&mut history);
+    write_history(workspace, env, &history)
+}
+
+/// Hash the generated Tilt files of an environment, in path order
+pub fn tiltfile_hash(workspace: &str, env: &str) -> io::Result<String> {
+    fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                collect(&path, files)?;
+            } else {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let dir = Path::new(workspace).join("tilt").join(env);
+    let mut files = Vec::new();
+    collect(&dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        let relative = file.strip_prefix(&dir).unwrap_or(&file);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update(fs::read(&file)?);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Record a new session. `restarted` carries on the restart count of the previous one.
+pub fn record_start(
+    workspace: &str,
+    env: &str,
+    id: &str,
+    tilt_version: Option<String>,
+    enabled_services: Vec<String>,
+    restarted: bool,
+) -> io::Result<()> {
+    let tiltfile_hash = tiltfile_hash(workspace, env).ok();
+
+    modify_history(workspace, env, |history| {
+        let restart_count = match history.last() {
+            Some(previous) if restarted => previous.restart_count + 1,
+            _ => 0,
+        };
+
+        history.push(SessionRecord {
+            id: id.to_string(),
+            started_at: now(),
+            stopped_at: None,
+            tilt_version,
+            tiltfile_hash,
+            enabled_services,
+            exit_code: None,
+            restart_count,
+            stopped_by: None,
+        });
+
+        let excess = history.len().saturating_sub(MAX_HISTORY_ENTRIES);
+        history.drain(..excess);
+    })
+}
+
+/// Mark a session as stopped. The first initiator recorded wins, so a
+/// requested stop is not reported as a crash when Tilt exits afterwards.
+/// Returns the initiator that ended up recorded.
+pub fn record_stop(
+    workspace: &str,
+    env: &str,
+    id: &str,
+    initiator: StopInitiator,
+    exit_code: Option<i32>,
+) -> io::Result<Option<StopInitiator>> {
+    let mut recorded = None;
+
+    modify_history(workspace, env, |history| {
+        if let Some(record) = history.iter_mut().rev().find(|r| r.id == id) {
+            record.stopped_at.get_or_insert_with(now);
+            recorded = Some(*record.stopped_by.get_or_insert(initiator));
+            if exit_code.is_some() {
+                record.exit_code = exit_code;
+            }
+        }
+    })?;
+
+    Ok(recorded)
+}
+
+/// Undo a stop recorded for a session that turned out to keep running
+pub fn unrecord_stop(workspace: &str, env: &str, id: &str) -> io::Result<()> {
+    modify_history(workspace, env, |history| {
+        if let Some(record) = history.iter_mut().rev().find(|r| r.id == id) {
+            record.stopped_at = None;
+            record.stopped_by = None;
+        }
+    })
+}
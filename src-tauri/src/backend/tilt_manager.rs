@@ -1,35 +1,180 @@
 use serde::{Deserialize, Serialize};
-use std::fs::{self, File};
-use std::io::{self, Write};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
-use tauri_plugin_shell::{process::CommandEvent, ShellExt};
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use ts_rs::TS;
+
+use crate::app_state::model::LogRetention;
+use crate::backend::build_metrics::record_snapshot;
+use crate::backend::errors::AppError;
+use crate::backend::events::{publish, BackendEvent};
+use crate::backend::host::{TiltHost, TrayHealth};
+use crate::backend::inheritance::resolve_environment;
+use crate::backend::log_records::{parse_record, LogFilter, LogRecord, LogStream};
+use crate::backend::log_store::{self, log_path, LogSource, LogWriter};
+use crate::backend::notifier::forget_environment;
+use crate::backend::ports::free_tilt_port;
+use crate::backend::process::{ProcessEvent, ProcessRunner};
+use crate::backend::project::Project;
+use crate::backend::redaction::{build_redactor, Redactor};
+use crate::backend::secrets::{clear_materialized_secrets, materialize_secrets, secret_values};
+use crate::backend::session_history::{record_start, record_stop, unrecord_stop, StopInitiator};
+use crate::backend::tilt_resources::{fetch_ui_resources, UiResource};
+
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
 pub struct TiltInstallation {
     pub installed: bool,
     pub path: Option<String>,
     pub version: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
 pub struct TiltState {
     pub status: String,
     pub pid: Option<u32>,
+    /// Log session of the current or most recent run
+    #[serde(default)]
+    pub session: Option<String>,
+    /// Port of the Tilt API and web UI, every environment gets its own
+    #[serde(default)]
+    pub port: Option<u16>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
 pub struct State {
     pub tilt: TiltState,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// What a new Tilt session needs besides the executable
+pub struct TiltLaunch {
+    /// Masks secrets in captured output
+    pub redactor: Redactor,
+    /// Secret values per service, on disk only while Tilt runs
+    pub secrets: HashMap<String, HashMap<String, String>>,
+    pub retention: LogRetention,
+    /// Recorded in the session history
+    pub enabled_services: Vec<String>,
+}
+
+impl TiltLaunch {
+    /// Read the environment's secrets and collect the launch settings from
+    /// the preferences, as every start of an environment does
+    pub fn prepare<H: TiltHost>(host: &H, project: &Project, env: &str) -> anyhow::Result<Self> {
+        let preferences = host.preferences();
+        let data_dir = host.data_dir()?;
+
+        let enabled_services = resolve_environment(project, env)?
+            .services
+            .into_iter()
+            .filter(|s| s.enabled)
+            .map(|s| s.name)
+            .collect();
+
+        Ok(TiltLaunch {
+            redactor: build_redactor(&data_dir, project, env, &preferences.log_redaction_patterns)?,
+            secrets: secret_values(&data_dir, project, env)?,
+            retention: preferences.log_retention,
+            enabled_services,
+        })
+    }
+}
+
+/// A Tilt process started by `start_tilt`
+pub struct TiltRun {
+    pub pid: u32,
+    pub session: String,
+    exited: JoinHandle<Option<i32>>,
+}
+
+impl TiltRun {
+    /// Wait until Tilt exits and its output is written, returning its exit code
+    pub async fn wait(self) -> Option<i32> {
+        self.exited.await.ok().flatten()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
 pub struct TitleStatus {
     pub status: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// A page of log lines, one entry per line
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
 pub struct TiltLog {
     pub logs: Vec<String>,
+    /// The same lines parsed into resource, level, stream and capture time
+    pub records: Vec<LogRecord>,
+    /// Byte offset of the first returned line, pass as `before` to page backwards
+    #[ts(type = "number")]
+    pub cursor: u64,
+    /// Byte offset after the last returned line, pass as `cursor` to page forwards
+    #[ts(type = "number")]
+    pub next_cursor: u64,
+    /// Size of the log in bytes when it was read
+    #[ts(type = "number")]
+    pub size: u64,
+}
+
+/// Which lines `get_tilt_logs` returns. Without `cursor` or `from_line` the
+/// last `lines` lines before `before` (or the end of the log) are returned.
+#[derive(Deserialize, Debug, Clone, Default, TS)]
+#[ts(optional_fields)]
+pub struct LogQuery {
+    pub lines: Option<usize>,
+    /// Read forwards from this byte offset
+    #[ts(type = "number", optional)]
+    pub cursor: Option<u64>,
+    /// Read forwards from this line number, zero based
+    pub from_line: Option<usize>,
+    /// Read the lines ending at this byte offset
+    #[ts(type = "number", optional)]
+    pub before: Option<u64>,
+    /// A past session from `list_log_sessions`, defaults to the current one
+    pub session: Option<String>,
+    /// Only lines matching these filters are returned and counted against `lines`
+    #[serde(flatten)]
+    pub filter: LogFilter,
+}
+
+/// Lines returned when the query does not set `lines`
+const DEFAULT_LOG_LINES: usize = 1000;
+
+/// How often a watcher reads the resources of its running Tilt
+const WATCH_INTERVAL: Duration = Duration::from_secs(3);
+
+/// The watcher of a running environment and the port its Tilt serves on
+struct Watcher {
+    cancel: CancellationToken,
+    port: u16,
+}
+
+/// Watchers of the environments this process started, by workspace and env
+static WATCHERS: LazyLock<Mutex<HashMap<(String, String), Watcher>>> =
+    LazyLock::new(Default::default);
+
+fn watchers() -> MutexGuard<'static, HashMap<(String, String), Watcher>> {
+    WATCHERS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Held while an environment's Tilt is being started, so a second start waits
+/// and then finds it up
+static STARTS: LazyLock<Mutex<HashMap<(String, String), StartLock>>> =
+    LazyLock::new(Default::default);
+
+type StartLock = Arc<tokio::sync::Mutex<()>>;
+
+fn start_lock(workspace: &str, env: &str) -> StartLock {
+    STARTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry((workspace.to_string(), env.to_string()))
+        .or_default()
+        .clone()
 }
 
 fn runtime_path(workspace: &str, env: &str) -> PathBuf {
@@ -38,28 +183,44 @@ fn runtime_path(workspace: &str, env: &str) -> PathBuf {
         .join(format!("{}_runtime.json", env))
 }
 
-fn log_path(workspace: &str, env: &str) -> PathBuf {
-    Path::new(workspace)
-        .join(".tooling")
-        .join(format!("{}_tilt.log", env))
+/// Ports recorded for the workspace's other environments that are up, which
+/// may belong to a Tilt started by another instance of the app
+fn recorded_ports(workspace: &str, env: &str) -> Vec<u16> {
+    let own = runtime_path(workspace, env);
+    let Ok(entries) = fs::read_dir(Path::new(workspace).join(".tooling")) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            *path != own
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.ends_with("_runtime.json"))
+        })
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|data| serde_json::from_str::<State>(&data).ok())
+        .filter(|state| matches!(state.tilt.status.as_str(), "running" | "starting"))
+        .filter_map(|state| state.tilt.port)
+        .collect()
 }
 
 /// Validate an executable path by checking its version
-pub async fn validate_executable_path(
-    app_handle: &tauri::AppHandle,
+pub async fn validate_executable_path<H: TiltHost>(
+    host: &H,
     path: &str,
     args: Option<&str>,
 ) -> io::Result<String> {
-    let version_result = app_handle
-        .shell()
-        .command(path)
-        .args([args.unwrap_or("--version")])
-        .output()
+    let version_result = host
+        .runner()
+        .output(path, &[args.unwrap_or("--version")], None)
         .await;
 
     match version_result {
         Ok(output) => {
-            if output.status.success() {
+            if output.success {
                 // Try stdout first, then stderr (some tools output version to stderr)
                 let stdout_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
                 let version = if !stdout_str.is_empty() {
@@ -85,22 +246,17 @@ pub async fn validate_executable_path(
 }
 
 /// Check if Tilt is installed and return its path
-pub async fn check_tilt_installed(app_handle: &tauri::AppHandle) -> io::Result<TiltInstallation> {
+pub async fn check_tilt_installed<H: TiltHost>(host: &H) -> io::Result<TiltInstallation> {
     #[cfg(unix)]
     let which_cmd = "which";
     #[cfg(not(unix))]
     let which_cmd = "where";
 
-    let result = app_handle
-        .shell()
-        .command(which_cmd)
-        .args(["tilt"])
-        .output()
-        .await;
+    let result = host.runner().output(which_cmd, &["tilt"], None).await;
 
     match result {
         Ok(output) => {
-            if output.status.success() {
+            if output.success {
                 let path_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
                 let tilt_path = path_str.lines().next().unwrap_or("").to_string();
 
@@ -113,15 +269,10 @@ pub async fn check_tilt_installed(app_handle: &tauri::AppHandle) -> io::Result<T
                 }
 
                 // Try to get version
-                let version_result = app_handle
-                    .shell()
-                    .command(&tilt_path)
-                    .args(["--version"])
-                    .output()
-                    .await;
+                let version_result = host.runner().output(&tilt_path, &["--version"], None).await;
 
                 let version = if let Ok(ver_output) = version_result {
-                    if ver_output.status.success() {
+                    if ver_output.success {
                         // Try stdout first, then stderr
                         let stdout_str = String::from_utf8_lossy(&ver_output.stdout)
                             .trim()
@@ -174,6 +325,12 @@ fn write_state(workspace: &str, env: &str, state: &State) -> io::Result<()> {
     let path = runtime_path(workspace, env);
     let json = serde_json::to_string_pretty(state)?;
     fs::write(path, json)?;
+
+    publish(BackendEvent::Status {
+        workspace_path: workspace.to_string(),
+        env: env.to_string(),
+        status: state.tilt.status.clone(),
+    });
     Ok(())
 }
 
@@ -186,62 +343,55 @@ pub fn read_state(workspace: &str, env: &str) -> State {
             tilt: TiltState {
                 status: "stopped".to_string(),
                 pid: None,
+                session: None,
+                port: None,
             },
         }),
         Err(_) => State {
             tilt: TiltState {
                 status: "stopped".to_string(),
                 pid: None,
+                session: None,
+                port: None,
             },
         },
     }
 }
 
 /// Check if a Tilt process is still alive
-async fn check_tilt_process_running(pid: Option<u32>, app_handle: &tauri::AppHandle) -> bool {
-    if let Some(pid_val) = pid {
-        #[cfg(unix)]
-        {
-            let result = app_handle
-                .shell()
-                .command("kill")
-                .args(["-0", &pid_val.to_string()])
-                .status()
-                .await;
-            result.map(|status| status.success()).unwrap_or(false)
-        }
-        #[cfg(not(unix))]
-        {
-            println!("{}", pid_val);
-            // On Windows, check if process exists
-            let result = app_handle
-                .shell()
-                .command("tasklist")
-                .args(["/FI", &format!("PID eq {}", pid_val)])
-                .output()
-                .await;
-            result
-                .map(|output| {
-                    String::from_utf8_lossy(&output.stdout).contains(&pid_val.to_string())
-                })
-                .unwrap_or(false)
-        }
-    } else {
-        false
+async fn check_tilt_process_running<H: TiltHost>(pid: Option<u32>, host: &H) -> bool {
+    match pid {
+        Some(pid) => host.runner().is_alive(pid).await,
+        None => false,
     }
 }
 
 /// Reconcile Tilt state periodically
-pub async fn reconcile_tilt_state(
+pub async fn reconcile_tilt_state<H: TiltHost>(
     workspace: &str,
     env: &str,
-    app_handle: &tauri::AppHandle,
+    host: &H,
 ) -> io::Result<TitleStatus> {
     let mut state = read_state(workspace, env);
-    let tilt_running = check_tilt_process_running(state.tilt.pid, app_handle).await;
+    let tilt_running = check_tilt_process_running(state.tilt.pid, host).await;
     let status = if tilt_running { "running" } else { "stopped" }.to_string();
 
     if state.tilt.status != status {
+        // Tilt went away without a stop request. The exit of a Tilt this
+        // process started is recorded by its capture task instead.
+        let was_up = state.tilt.status == "running" || state.tilt.status == "starting";
+        let captured = watchers().contains_key(&(workspace.to_string(), env.to_string()));
+        if was_up && !tilt_running && !captured {
+            if let Some(session) = &state.tilt.session {
+                let initiator =
+                    record_stop(workspace, env, session, StopInitiator::Watchdog, None)?;
+                if initiator == Some(StopInitiator::Watchdog) {
+                    host.tilt_exited(workspace, env, None).await;
+                }
+            }
+            clear_materialized_secrets(workspace, env)?;
+        }
+
         state.tilt.status = status.clone();
         write_state(workspace, env, &state)?;
     }
@@ -249,81 +399,178 @@ pub async fn reconcile_tilt_state(
     Ok(TitleStatus { status })
 }
 
-/// Start Tilt for the given workspace/env
-pub async fn start_tilt(
+/// Read the resources of a running Tilt, fold their builds into the session metrics
+/// and report its health
+async fn poll_tilt_resources<H: TiltHost>(
+    workspace: &str,
+    env: &str,
+    host: &H,
+    tilt_path: Option<&str>,
+    port: u16,
+) -> io::Result<Vec<UiResource>> {
+    let state = read_state(workspace, env);
+    let Some(session) = state.tilt.session else {
+        return Ok(Vec::new());
+    };
+
+    let resources = fetch_ui_resources(host, tilt_path, Some(port)).await?;
+    record_snapshot(workspace, env, &session, &resources)?;
+    host.health_changed(workspace, env, TrayHealth::from_resources(&resources))
+        .await;
+
+    Ok(resources)
+}
+
+/// Follow a running Tilt from the backend until it stops or `cancel` fires,
+/// so resource changes are noticed without the webview polling
+async fn watch_tilt<H: TiltHost>(
+    workspace: String,
+    env: String,
+    host: H,
+    tilt_path: String,
+    port: u16,
+    cancel: CancellationToken,
+) {
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return,
+            _ = tokio::time::sleep(WATCH_INTERVAL) => {}
+        }
+
+        // Publishes the move from starting to running
+        match reconcile_tilt_state(&workspace, &env, &host).await {
+            Ok(status) if status.status == "stopped" => return,
+            _ => {}
+        }
+
+        // Tilt's API is not up yet for the first polls after a start
+        let Ok(resources) =
+            poll_tilt_resources(&workspace, &env, &host, Some(&tilt_path), port).await
+        else {
+            continue;
+        };
+        if cancel.is_cancelled() {
+            return;
+        }
+        host.resources_changed(&workspace, &env, &resources).await;
+    }
+}
+
+/// Stop the watcher of an environment, if this process runs one
+fn stop_watching(workspace: &str, env: &str) {
+    if let Some(watcher) = watchers().remove(&(workspace.to_string(), env.to_string())) {
+        watcher.cancel.cancel();
+    }
+}
+
+/// Start Tilt for the given workspace/env.
+/// Returns `None` when it is already running or being started.
+pub async fn start_tilt<H: TiltHost>(
     workspace: &str,
     env: &str,
-    app_handle: &tauri::AppHandle,
+    host: &H,
     tilt_path_override: Option<&str>,
-) -> io::Result<()> {
+    launch: TiltLaunch,
+) -> io::Result<Option<TiltRun>> {
+    launch_tilt(workspace, env, host, tilt_path_override, launch, false).await
+}
+
+async fn launch_tilt<H: TiltHost>(
+    workspace: &str,
+    env: &str,
+    host: &H,
+    tilt_path_override: Option<&str>,
+    launch: TiltLaunch,
+    restarted: bool,
+) -> io::Result<Option<TiltRun>> {
+    let TiltLaunch {
+        redactor,
+        secrets,
+        retention,
+        enabled_services,
+    } = launch;
+    let start = start_lock(workspace, env);
+    let _starting = start.lock().await;
     let state = read_state(workspace, env);
-    if state.tilt.status == "running" {
-        return Ok(());
+    let up = state.tilt.status == "running" || state.tilt.status == "starting";
+    if up && check_tilt_process_running(state.tilt.pid, host).await {
+        return Ok(None);
     }
 
     // Use provided tilt path or check if tilt is installed
-    let tilt_path = if let Some(path) = tilt_path_override {
+    let (tilt_path, tilt_version) = if let Some(path) = tilt_path_override {
         // Validate the provided path
-        validate_executable_path(app_handle, path, Some("version")).await?;
-        path.to_string()
+        let version = validate_executable_path(host, path, Some("version")).await?;
+        (path.to_string(), Some(version))
     } else {
-        let tilt_installation = check_tilt_installed(app_handle).await?;
+        let tilt_installation = check_tilt_installed(host).await?;
         if !tilt_installation.installed {
             return Err(io::Error::new(
                 io::ErrorKind::NotFound,
-                "Tilt is not installed. Please configure Tilt path in Settings or install Tilt from https://docs.tilt.dev/install.html",
+                AppError::TiltNotInstalled,
             ));
         }
-        tilt_installation.path.unwrap_or_else(|| "tilt".to_string())
+        (
+            tilt_installation.path.unwrap_or_else(|| "tilt".to_string()),
+            tilt_installation.version,
+        )
     };
     let tiltfile = Path::new(workspace).join("tilt").join(env).join("Tiltfile");
-    let log_file_path = log_path(workspace, env);
-
-    // Create log file
-    let _log_file = File::create(&log_file_path)?;
-
-    let (mut rx, child) = app_handle
-        .shell()
-        .command(&tilt_path)
-        .args([
-            "up",
-            "-f",
-            tiltfile.to_str().ok_or_else(|| {
-                io::Error::new(io::ErrorKind::InvalidInput, "Invalid Tiltfile path")
-            })?,
-        ])
-        .current_dir(workspace)
-        .spawn()
-        .map_err(|e| io::Error::other(e.to_string()))?;
-
-    let pid = child.pid();
 
-    // Spawn a task to capture output and write to log file
-    let log_file_path_clone = log_file_path.clone();
-    tauri::async_runtime::spawn(async move {
-        while let Some(event) = rx.recv().await {
-            let mut file = match std::fs::OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&log_file_path_clone)
-            {
-                Ok(f) => f,
-                Err(_) => continue,
-            };
-
-            match event {
-                CommandEvent::Stdout(line) => {
-                    let _ = writeln!(file, "{}", String::from_utf8_lossy(&line));
-                }
-                CommandEvent::Stderr(line) => {
-                    let _ = writeln!(file, "{}", String::from_utf8_lossy(&line));
-                }
-                CommandEvent::Terminated(_) => break,
-                _ => {}
-            }
+    // Keep the previous session's output instead of truncating it
+    log_store::archive_live_log(workspace, env, state.tilt.session.as_deref())?;
+    let session = log_store::new_session_id();
+    log_store::prune_sessions(workspace, env, &retention, Some(&session))?;
+    let mut writer = LogWriter::create(workspace, env, &session, &retention)?;
+
+    let tiltfile = tiltfile
+        .to_str()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid Tiltfile path"))?;
+
+    // Registered before Tilt binds its port so a concurrent start skips it
+    let watch = CancellationToken::new();
+    let recorded = recorded_ports(workspace, env);
+    let port = {
+        let mut watchers = watchers();
+        let key = (workspace.to_string(), env.to_string());
+        if let Some(previous) = watchers.remove(&key) {
+            previous.cancel.cancel();
         }
+        let taken: Vec<u16> = watchers.values().map(|w| w.port).chain(recorded).collect();
+        let port = free_tilt_port(&taken)?;
+        let cancel = watch.clone();
+        watchers.insert(key, Watcher { cancel, port });
+        port
+    };
+
+    // The Tiltfile reads them when Tilt loads it
+    let spawned = materialize_secrets(workspace, env, &secrets).and_then(|_| {
+        host.runner().spawn(
+            &tilt_path,
+            &["up", "-f", tiltfile, "--port", &port.to_string()],
+            Some(Path::new(workspace)),
+        )
     });
+    let process = match spawned {
+        Ok(process) => process,
+        Err(e) => {
+            stop_watching(workspace, env);
+            let _ = clear_materialized_secrets(workspace, env);
+            return Err(e);
+        }
+    };
+    let (pid, mut rx) = (process.pid, process.events);
+
+    record_start(
+        workspace,
+        env,
+        &session,
+        tilt_version,
+        enabled_services,
+        restarted,
+    )?;
 
+    // Written before the output is read, so an early exit can mark it stopped
     write_state(
         workspace,
         env,
@@ -331,43 +578,118 @@ pub async fn start_tilt(
             tilt: TiltState {
                 status: "starting".to_string(),
                 pid: Some(pid),
+                session: Some(session.clone()),
+                port: Some(port),
             },
         },
     )?;
+    host.health_changed(workspace, env, TrayHealth::Starting)
+        .await;
 
-    Ok(())
+    // Spawn a task to capture output and write to log file
+    let (task_workspace, task_env, task_session) =
+        (workspace.to_string(), env.to_string(), session.clone());
+    let task_host = host.clone();
+    let task_watch = watch.clone();
+    let exited = tauri::async_runtime::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            // Mask secrets before anything reaches the disk
+            let (stream, line) = match event {
+                ProcessEvent::Stdout(line) => (LogStream::Stdout, redactor.redact(&line)),
+                ProcessEvent::Stderr(line) => (LogStream::Stderr, redactor.redact(&line)),
+                ProcessEvent::Terminated(code) => {
+                    task_watch.cancel();
+                    let initiator = record_stop(
+                        &task_workspace,
+                        &task_env,
+                        &task_session,
+                        StopInitiator::Crash,
+                        code,
+                    );
+                    match initiator {
+                        Ok(Some(StopInitiator::Crash)) => {
+                            task_host
+                                .tilt_exited(&task_workspace, &task_env, code)
+                                .await
+                        }
+                        // A requested stop writes the state itself
+                        Ok(Some(_)) => return code,
+                        _ => {}
+                    }
+
+                    // Unless a newer session already took over the state
+                    let mut state = read_state(&task_workspace, &task_env);
+                    if state.tilt.session.as_deref() == Some(task_session.as_str())
+                        && state.tilt.status != "stopped"
+                    {
+                        state.tilt.status = "stopped".to_string();
+                        let _ = write_state(&task_workspace, &task_env, &state);
+                        let _ = clear_materialized_secrets(&task_workspace, &task_env);
+                        task_host
+                            .health_changed(&task_workspace, &task_env, TrayHealth::Idle)
+                            .await;
+                    }
+                    return code;
+                }
+            };
+            let _ = writer.write_line(stream, &line);
+            publish(BackendEvent::Log {
+                workspace_path: task_workspace.clone(),
+                env: task_env.clone(),
+                stream,
+                line,
+            });
+        }
+        None
+    });
+
+    tauri::async_runtime::spawn(watch_tilt(
+        workspace.to_string(),
+        env.to_string(),
+        host.clone(),
+        tilt_path,
+        port,
+        watch,
+    ));
+
+    Ok(Some(TiltRun {
+        pid,
+        session,
+        exited,
+    }))
 }
 
 /// Stop Tilt for the given workspace/env
-pub async fn stop_tilt(
+pub async fn stop_tilt<H: TiltHost>(
     workspace: &str,
     env: &str,
-    app_handle: &tauri::AppHandle,
+    host: &H,
+    initiator: StopInitiator,
 ) -> io::Result<()> {
     let state = read_state(workspace, env);
 
     if let Some(pid) = state.tilt.pid {
-        // Kill the process
-        #[cfg(unix)]
-        {
-            app_handle
-                .shell()
-                .command("kill")
-                .args([pid.to_string()])
-                .status()
-                .await
-                .map_err(|e| io::Error::other(e.to_string()))?;
-        }
-        #[cfg(not(unix))]
-        {
-            app_handle
-                .shell()
-                .command("taskkill")
-                .args(["/F", "/PID", &pid.to_string()])
-                .status()
-                .await
-                .map_err(|e| io::Error::other(e.to_string()))?;
+        // Recorded before the kill so the exit is not taken for a crash
+        let recorded = match &state.tilt.session {
+            Some(session) => record_stop(workspace, env, session, initiator, None)?,
+            None => None,
+        };
+
+        // A failed kill leaves Tilt running, so its state is kept and the
+        // stop is taken back. One that failed because Tilt already exited is fine.
+        if let Err(e) = host.runner().kill(pid).await {
+            if host.runner().is_alive(pid).await {
+                if let (Some(session), Some(recorded)) = (&state.tilt.session, recorded) {
+                    if recorded == initiator {
+                        unrecord_stop(workspace, env, session)?;
+                    }
+                }
+                return Err(e);
+            }
         }
+        stop_watching(workspace, env);
+        forget_environment(workspace, env);
+        clear_materialized_secrets(workspace, env)?;
 
         write_state(
             workspace,
@@ -376,49 +698,86 @@ pub async fn stop_tilt(
                 tilt: TiltState {
                     status: "stopped".to_string(),
                     pid: None,
+                    session: state.tilt.session.clone(),
+                    port: None,
                 },
             },
         )?;
+        host.health_changed(workspace, env, TrayHealth::Idle).await;
     }
 
     Ok(())
 }
 
 /// Restart Tilt for the given workspace/env
-pub async fn restart_tilt(
+pub async fn restart_tilt<H: TiltHost>(
     workspace: &str,
     env: &str,
-    app_handle: &tauri::AppHandle,
+    host: &H,
     tilt_path_override: Option<&str>,
-) -> io::Result<()> {
-    stop_tilt(workspace, env, app_handle).await?;
+    launch: TiltLaunch,
+    initiator: StopInitiator,
+) -> io::Result<Option<TiltRun>> {
+    stop_tilt(workspace, env, host, initiator).await?;
 
-    start_tilt(workspace, env, app_handle, tilt_path_override).await?;
+    launch_tilt(workspace, env, host, tilt_path_override, launch, true).await
+}
 
-    Ok(())
+/// List the log sessions kept for the given workspace/env
+pub fn list_log_sessions(workspace: &str, env: &str) -> io::Result<Vec<log_store::LogSession>> {
+    let state = read_state(workspace, env);
+    log_store::list_sessions(workspace, env, state.tilt.session.as_deref())
 }
 
-/// Get Tilt logs for the given workspace/env
-pub fn get_tilt_logs(workspace: &str, env: &str, lines: Option<usize>) -> io::Result<TiltLog> {
-    let log_file_path = log_path(workspace, env);
+/// Get Tilt logs for the given workspace/env, from the current session or a past one.
+/// Only the requested range is read, the log is never loaded whole.
+/// Filters apply while scanning, so `lines` counts matching lines.
+/// Lines are redacted again on read, covering logs written before a value was marked secret.
+pub fn get_tilt_logs(
+    workspace: &str,
+    env: &str,
+    query: &LogQuery,
+    redactor: &Redactor,
+) -> io::Result<TiltLog> {
+    let state = read_state(workspace, env);
+    let current = state.tilt.session.as_deref();
+
+    // Offsets span every part of the session so cursors survive rotation
+    let files = match (query.session.as_deref(), current) {
+        (Some(session), _) => log_store::session_files(workspace, env, session, current)?,
+        (None, Some(session)) => log_store::session_files(workspace, env, session, current)
+            .unwrap_or_else(|_| vec![log_path(workspace, env)]),
+        (None, None) => vec![log_path(workspace, env)],
+    };
 
-    if !log_file_path.exists() {
-        return Ok(TiltLog { logs: Vec::new() });
-    }
+    let source = LogSource::open(files)?;
+    let limit = query.lines.unwrap_or(DEFAULT_LOG_LINES);
 
-    let content = fs::read_to_string(log_file_path)?;
+    let start = match (query.cursor, query.from_line) {
+        (Some(cursor), _) => Some(cursor),
+        (None, Some(line)) => Some(source.line_offset(line)?),
+        (None, None) => None,
+    };
 
-    if let Some(n) = lines {
-        // Return last n lines
-        // let all_lines: Vec<String> = content.lines().collect();
-        let all_lines: Vec<String> = content.lines().map(String::from).collect();
-        let start = all_lines.len().saturating_sub(n);
-        Ok(TiltLog {
-            logs: all_lines[start..].to_vec(),
-        })
-    } else {
-        Ok(TiltLog {
-            logs: vec![content],
-        })
-    }
+    let filter = query.filter.compile()?;
+    let select = |line: String| {
+        let record = parse_record(&redactor.redact(&line));
+        filter.matches(&record).then_some(record)
+    };
+
+    let (cursor, records, next_cursor) = match start {
+        Some(start) => {
+            let (records, next) = source.read_lines(start, limit, select)?;
+            (start.min(source.size()), records, next)
+        }
+        None => source.tail(query.before.unwrap_or(source.size()), limit, select)?,
+    };
+
+    Ok(TiltLog {
+        logs: records.iter().map(|r| r.line.clone()).collect(),
+        records,
+        cursor,
+        next_cursor,
+        size: source.size(),
+    })
 }
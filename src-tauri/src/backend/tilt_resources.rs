@@ -0,0 +1,143 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+use crate::backend::host::TiltHost;
+use crate::backend::process::ProcessRunner;
+
+/// A finished or running build from a resource's `buildHistory`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UiBuild {
+    pub start_time: Option<String>,
+    pub finish_time: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UiCondition {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub status: String,
+    pub last_transition_time: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UiResourceStatus {
+    /// Most recent first, Tilt keeps only a few entries
+    pub build_history: Vec<UiBuild>,
+    pub update_status: String,
+    pub runtime_status: String,
+    pub conditions: Vec<UiCondition>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct UiMetadata {
+    pub name: String,
+}
+
+/// The subset of Tilt's `UIResource` the orchestrator reads
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct UiResource {
+    pub metadata: UiMetadata,
+    pub status: UiResourceStatus,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct UiResourceList {
+    items: Vec<UiResource>,
+}
+
+impl UiResource {
+    pub fn name(&self) -> &str {
+        &self.metadata.name
+    }
+
+    /// When the resource last became ready, if it is ready now
+    pub fn ready_since(&self) -> Option<&str> {
+        self.status
+            .conditions
+            .iter()
+            .find(|c| c.kind == "Ready" && c.status == "True")
+            .and_then(|c| c.last_transition_time.as_deref())
+    }
+}
+
+/// Parse a Tilt timestamp, treating Go's zero time as unset
+pub fn parse_tilt_time(value: Option<&str>) -> Option<DateTime<FixedOffset>> {
+    value
+        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+        .filter(|t| t.timestamp() > 0)
+}
+
+/// `args` addressed to the Tilt serving on `port`, or the default one
+fn with_port<'a>(args: &[&'a str], port: &'a Option<String>) -> Vec<&'a str> {
+    let mut args = args.to_vec();
+    if let Some(port) = port {
+        args.extend(["--port", port.as_str()]);
+    }
+    args
+}
+
+/// Ask the running Tilt for its resources with `tilt get uiresources`
+pub async fn fetch_ui_resources<H: TiltHost>(
+    host: &H,
+    tilt_path: Option<&str>,
+    port: Option<u16>,
+) -> io::Result<Vec<UiResource>> {
+    let port = port.map(|p| p.to_string());
+    let output = host
+        .runner()
+        .output(
+            tilt_path.unwrap_or("tilt"),
+            &with_port(&["get", "uiresources", "-o", "json"], &port),
+            None,
+        )
+        .await?;
+
+    if !output.success {
+        return Err(io::Error::other(format!(
+            "Failed to read Tilt resources: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let list: UiResourceList = serde_json::from_slice(&output.stdout)?;
+    Ok(list.items)
+}
+
+/// Enable or disable a resource in the running Tilt with `tilt enable`/`tilt disable`
+pub async fn set_resource_enabled<H: TiltHost>(
+    host: &H,
+    tilt_path: Option<&str>,
+    port: Option<u16>,
+    name: &str,
+    enabled: bool,
+) -> io::Result<()> {
+    let action = if enabled { "enable" } else { "disable" };
+    let port = port.map(|p| p.to_string());
+    let output = host
+        .runner()
+        .output(
+            tilt_path.unwrap_or("tilt"),
+            &with_port(&[action, name], &port),
+            None,
+        )
+        .await?;
+
+    if !output.success {
+        return Err(io::Error::other(format!(
+            "Failed to {} {} in Tilt: {}",
+            action,
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(())
+}
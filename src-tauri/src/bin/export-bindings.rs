@@ -0,0 +1,21 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Writes the IPC command types for the frontend, `src/api/bindings` by default
+fn main() -> ExitCode {
+    let dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("../src/api/bindings"));
+
+    match tilt_orchestrator_lib::export_bindings(&dir) {
+        Ok(()) => {
+            println!("Wrote bindings to {}", dir.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
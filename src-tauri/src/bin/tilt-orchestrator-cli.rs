@@ -0,0 +1,3 @@
+fn main() -> std::process::ExitCode {
+    tilt_orchestrator_lib::run_cli()
+}
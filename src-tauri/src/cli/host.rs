@@ -0,0 +1,48 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::backend::host::{TiltHost, TrayHealth};
+use crate::backend::process::TokioRunner;
+use crate::backend::tilt_resources::UiResource;
+
+/// Same as `identifier` in tauri.conf.json, so the CLI shares the app's data
+const APP_IDENTIFIER: &str = "com.binarygeotech.tilt-orchestrator";
+
+/// Runs processes with tokio and reports to the terminal
+#[derive(Clone)]
+pub struct TerminalHost {
+    data_dir: PathBuf,
+}
+
+impl TerminalHost {
+    pub fn new() -> io::Result<Self> {
+        let data_dir = dirs::data_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No data directory"))?
+            .join(APP_IDENTIFIER);
+
+        Ok(TerminalHost { data_dir })
+    }
+}
+
+impl TiltHost for TerminalHost {
+    type Runner = TokioRunner;
+
+    fn data_dir(&self) -> io::Result<PathBuf> {
+        Ok(self.data_dir.clone())
+    }
+
+    fn runner(&self) -> TokioRunner {
+        TokioRunner
+    }
+
+    async fn tilt_exited(&self, _workspace: &str, env: &str, exit_code: Option<i32>) {
+        match exit_code {
+            Some(code) => eprintln!("Tilt exited for {} (exit code {})", env, code),
+            None => eprintln!("Tilt exited for {}", env),
+        }
+    }
+
+    async fn health_changed(&self, _workspace: &str, _env: &str, _health: TrayHealth) {}
+
+    async fn resources_changed(&self, _workspace: &str, _env: &str, _resources: &[UiResource]) {}
+}
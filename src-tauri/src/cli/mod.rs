@@ -0,0 +1,516 @@
+mod host;
+
+use anyhow::{anyhow, bail, Result};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use crate::backend::generator::generate_tiltfiles;
+use crate::backend::git::clone_repo;
+use crate::backend::host::TiltHost;
+use crate::backend::inheritance::resolve_environment;
+use crate::backend::log_records::LogRecord;
+use crate::backend::process::TokioRunner;
+use crate::backend::project::{Project, Repo, Service};
+use crate::backend::redaction::build_redactor;
+use crate::backend::session_history::StopInitiator;
+use crate::backend::tilt_manager::{
+    get_tilt_logs, read_state, reconcile_tilt_state, start_tilt, stop_tilt, LogQuery, TiltLaunch,
+};
+use crate::backend::tilt_resources::set_resource_enabled;
+use crate::project::{
+    add_service, initialize_existing_project, is_valid_project, load_project_info,
+    set_service_enabled,
+};
+use host::TerminalHost;
+
+/// How often `logs --follow` looks for new lines
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Parser)]
+#[command(
+    name = "tilt-orchestrator-cli",
+    version,
+    about = "Manage Tilt Orchestrator projects without the desktop app"
+)]
+struct Cli {
+    /// Project directory, the one holding project.json
+    #[arg(long, global = true, default_value = ".")]
+    project: PathBuf,
+
+    /// Print JSON, one document per line, instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Set up the project directory as a Tilt Orchestrator project
+    Init {
+        /// Directory of the service repositories, relative to the project
+        #[arg(long, default_value = "repos")]
+        services_path: String,
+    },
+    /// Regenerate the Tiltfiles of an environment
+    Generate {
+        #[arg(long)]
+        env: String,
+    },
+    /// Start Tilt for an environment and stay attached until it exits
+    Up {
+        #[arg(long)]
+        env: String,
+    },
+    /// Stop Tilt for an environment
+    Down {
+        #[arg(long)]
+        env: String,
+    },
+    /// Show whether Tilt is running, for one or every environment
+    Status {
+        #[arg(long)]
+        env: Option<String>,
+    },
+    /// Print the captured Tilt logs of an environment
+    Logs {
+        #[arg(long)]
+        env: String,
+        /// Lines printed before following
+        #[arg(long, short = 'n', default_value_t = 100)]
+        lines: usize,
+        /// Keep printing new lines until interrupted
+        #[arg(long, short)]
+        follow: bool,
+    },
+    /// Add, enable or disable services
+    #[command(subcommand)]
+    Service(ServiceCommand),
+    /// Inspect environments
+    #[command(subcommand)]
+    Env(EnvCommand),
+}
+
+#[derive(Subcommand)]
+enum ServiceCommand {
+    /// Add a service to an environment, cloning its repository when given
+    Add {
+        name: String,
+        #[arg(long)]
+        env: String,
+        #[arg(long)]
+        port: u16,
+        #[arg(long)]
+        repo: Option<String>,
+        #[arg(long)]
+        branch: Option<String>,
+        /// Local directory of the service instead of a repository
+        #[arg(long)]
+        path: Option<String>,
+    },
+    /// Enable a service and regenerate the environment's Tiltfiles
+    Enable {
+        name: String,
+        #[arg(long)]
+        env: String,
+    },
+    /// Disable a service and regenerate the environment's Tiltfiles
+    Disable {
+        name: String,
+        #[arg(long)]
+        env: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum EnvCommand {
+    /// List the environments with their services and Tilt status
+    List,
+}
+
+#[derive(Serialize)]
+struct EnvStatus {
+    env: String,
+    status: String,
+    pid: Option<u32>,
+    session: Option<String>,
+}
+
+#[derive(Serialize)]
+struct EnvSummary {
+    name: String,
+    extends: Option<String>,
+    services: usize,
+    enabled_services: usize,
+    status: String,
+}
+
+/// Entry point of the `tilt-orchestrator-cli` binary
+pub fn run() -> ExitCode {
+    let cli = Cli::parse();
+    let json = cli.json;
+
+    match tauri::async_runtime::block_on(execute(cli)) {
+        Ok(code) => code,
+        Err(e) => {
+            if json {
+                println!("{}", serde_json::json!({ "error": e.to_string() }));
+            } else {
+                eprintln!("error: {}", e);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Print a value as JSON, or run the text form
+fn report<T: Serialize>(json: bool, value: &T, text: impl FnOnce()) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(value)?);
+    } else {
+        text();
+    }
+    Ok(())
+}
+
+fn print_records(json: bool, records: &[LogRecord]) -> Result<()> {
+    for record in records {
+        report(json, record, || println!("{}", record.line))?;
+    }
+    Ok(())
+}
+
+fn project_dir(path: &Path) -> Result<String> {
+    let dir =
+        fs::canonicalize(path).map_err(|e| anyhow!("Cannot open {}: {}", path.display(), e))?;
+    Ok(dir.to_string_lossy().to_string())
+}
+
+fn load_project(path: &Path) -> Result<Project> {
+    load_project_info(&project_dir(path)?).map_err(|e| anyhow!("Failed to load project: {}", e))
+}
+
+fn ensure_env(project: &Project, env: &str) -> Result<()> {
+    if !project.environments.contains_key(env) {
+        bail!("Environment {} not found", env);
+    }
+    Ok(())
+}
+
+fn env_names(project: &Project) -> Vec<String> {
+    let mut names: Vec<String> = project.environments.keys().cloned().collect();
+    names.sort();
+    names
+}
+
+async fn execute(cli: Cli) -> Result<ExitCode> {
+    let json = cli.json;
+
+    match cli.command {
+        Command::Init { services_path } => {
+            let dir = project_dir(&cli.project)?;
+            if is_valid_project(&dir) {
+                bail!("{} is already a Tilt Orchestrator project", dir);
+            }
+
+            let project = initialize_existing_project(&dir, &services_path)?;
+            report(json, &project, || {
+                println!(
+                    "Initialized {} with environments {}",
+                    project.project.name,
+                    env_names(&project).join(", ")
+                )
+            })?;
+        }
+
+        Command::Generate { env } => {
+            let project = load_project(&cli.project)?;
+            ensure_env(&project, &env)?;
+            generate_tiltfiles(&project, &env)?;
+
+            let path = PathBuf::from(&project.project.workspace_path)
+                .join("tilt")
+                .join(&env);
+            report(
+                json,
+                &serde_json::json!({ "env": env, "path": path }),
+                || println!("Generated Tiltfiles for {} in {}", env, path.display()),
+            )?;
+        }
+
+        Command::Up { env } => return up(&cli.project, &env, json).await,
+
+        Command::Down { env } => {
+            let project = load_project(&cli.project)?;
+            ensure_env(&project, &env)?;
+            let workspace = &project.project.workspace_path;
+            let host = TerminalHost::new()?;
+
+            let running = read_state(workspace, &env).tilt.pid.is_some();
+            stop_tilt(workspace, &env, &host, StopInitiator::User).await?;
+
+            report(
+                json,
+                &serde_json::json!({ "env": env, "status": "stopped", "was_running": running }),
+                || {
+                    if running {
+                        println!("Stopped Tilt for {}", env);
+                    } else {
+                        println!("Tilt is not running for {}", env);
+                    }
+                },
+            )?;
+        }
+
+        Command::Status { env } => {
+            let project = load_project(&cli.project)?;
+            let workspace = &project.project.workspace_path;
+            let host = TerminalHost::new()?;
+
+            let envs = match env {
+                Some(env) => {
+                    ensure_env(&project, &env)?;
+                    vec![env]
+                }
+                None => env_names(&project),
+            };
+
+            let mut statuses = Vec::new();
+            for env in envs {
+                let status = reconcile_tilt_state(workspace, &env, &host).await?.status;
+                let state = read_state(workspace, &env).tilt;
+                statuses.push(EnvStatus {
+                    env,
+                    status,
+                    pid: state.pid,
+                    session: state.session,
+                });
+            }
+
+            report(json, &statuses, || {
+                for status in &statuses {
+                    let pid = status.pid.map(|p| p.to_string()).unwrap_or_default();
+                    println!("{:<16} {:<10} {}", status.env, status.status, pid);
+                }
+            })?;
+        }
+
+        Command::Logs { env, lines, follow } => {
+            let project = load_project(&cli.project)?;
+            ensure_env(&project, &env)?;
+            let workspace = &project.project.workspace_path;
+            let host = TerminalHost::new()?;
+            let redactor = build_redactor(
+                &host.data_dir()?,
+                &project,
+                &env,
+                &host.preferences().log_redaction_patterns,
+            )?;
+
+            let query = LogQuery {
+                lines: Some(lines),
+                ..Default::default()
+            };
+            let log = get_tilt_logs(workspace, &env, &query, &redactor)?;
+            print_records(json, &log.records)?;
+
+            let mut cursor = log.next_cursor;
+            if follow {
+                loop {
+                    tokio::select! {
+                        _ = tokio::time::sleep(FOLLOW_INTERVAL) => {}
+                        _ = tokio::signal::ctrl_c() => break,
+                    }
+
+                    let query = LogQuery {
+                        cursor: Some(cursor),
+                        ..Default::default()
+                    };
+                    let mut log = get_tilt_logs(workspace, &env, &query, &redactor)?;
+
+                    // A shorter log means a new session started, read it from the top
+                    if log.size < cursor {
+                        let query = LogQuery {
+                            cursor: Some(0),
+                            ..Default::default()
+                        };
+                        log = get_tilt_logs(workspace, &env, &query, &redactor)?;
+                    }
+
+                    print_records(json, &log.records)?;
+                    cursor = log.next_cursor;
+                }
+            }
+        }
+
+        Command::Service(command) => service(&cli.project, command, json).await?,
+
+        Command::Env(EnvCommand::List) => {
+            let project = load_project(&cli.project)?;
+            let workspace = &project.project.workspace_path;
+
+            let mut summaries = Vec::new();
+            for name in env_names(&project) {
+                let services = resolve_environment(&project, &name)?.services;
+                summaries.push(EnvSummary {
+                    extends: project.environments[&name].extends.clone(),
+                    services: services.len(),
+                    enabled_services: services.iter().filter(|s| s.enabled).count(),
+                    status: read_state(workspace, &name).tilt.status,
+                    name,
+                });
+            }
+
+            report(json, &summaries, || {
+                for env in &summaries {
+                    let extends = env
+                        .extends
+                        .as_ref()
+                        .map(|e| format!("extends {}", e))
+                        .unwrap_or_default();
+                    println!(
+                        "{:<16} {:<10} {}/{} services enabled  {}",
+                        env.name, env.status, env.enabled_services, env.services, extends
+                    );
+                }
+            })?;
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Start Tilt and stay attached. Ctrl-C stops it.
+async fn up(path: &Path, env: &str, json: bool) -> Result<ExitCode> {
+    let project = load_project(path)?;
+    ensure_env(&project, env)?;
+    let workspace = &project.project.workspace_path;
+    let host = TerminalHost::new()?;
+
+    let launch = TiltLaunch::prepare(&host, &project, env)?;
+    if launch.enabled_services.is_empty() {
+        bail!("Add or enable at least one service to start Tilt");
+    }
+
+    let tilt_path = host.preferences().tilt_path;
+    let Some(run) = start_tilt(workspace, env, &host, tilt_path.as_deref(), launch).await? else {
+        bail!("Tilt is already running for {}", env);
+    };
+
+    report(
+        json,
+        &serde_json::json!({ "env": env, "status": "starting", "pid": run.pid, "session": run.session }),
+        || {
+            println!(
+                "Started Tilt for {} (pid {}), press Ctrl-C to stop",
+                env, run.pid
+            )
+        },
+    )?;
+
+    let exited = run.wait();
+    tokio::pin!(exited);
+
+    let (exit_code, stopped) = tokio::select! {
+        code = &mut exited => {
+            reconcile_tilt_state(workspace, env, &host).await?;
+            (code, false)
+        }
+        _ = tokio::signal::ctrl_c() => {
+            stop_tilt(workspace, env, &host, StopInitiator::User).await?;
+            (exited.await, true)
+        }
+    };
+
+    report(
+        json,
+        &serde_json::json!({ "env": env, "status": "stopped", "exit_code": exit_code }),
+        || println!("Tilt stopped for {}", env),
+    )?;
+
+    Ok(if stopped || exit_code == Some(0) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+async fn service(path: &Path, command: ServiceCommand, json: bool) -> Result<()> {
+    let project = load_project(path)?;
+    let workspace = &project.project.workspace_path;
+    let enabled = matches!(command, ServiceCommand::Enable { .. });
+
+    match command {
+        ServiceCommand::Add {
+            name,
+            env,
+            port,
+            repo,
+            branch,
+            path,
+        } => {
+            let service = Service {
+                name: name.clone(),
+                path,
+                port,
+                enabled: true,
+                repo: repo.map(|url| Repo { url, branch }),
+                docker: None,
+                k8s: None,
+                env: None,
+                depends_on: None,
+                helm: None,
+                kustomize: None,
+                replicas: None,
+                secrets: None,
+            };
+
+            let (project, checkout) = add_service(workspace, &env, service)?;
+            if let Some(checkout) = checkout {
+                clone_repo(
+                    &TokioRunner,
+                    &checkout.url,
+                    &checkout.dir.to_string_lossy(),
+                    checkout.branch.as_deref(),
+                )
+                .await?;
+            }
+            report(json, &project, || {
+                println!("Added service {} to {}", name, env)
+            })?;
+        }
+
+        ServiceCommand::Enable { name, env } | ServiceCommand::Disable { name, env } => {
+            ensure_env(&project, &env)?;
+
+            // A running Tilt picks up the regenerated Tiltfile, a disabled
+            // service is torn down first and an enabled one enabled in Tilt
+            // afterwards, as the tray does
+            let tilt = read_state(workspace, &env).tilt;
+            let running = tilt.status == "running";
+            let host = TerminalHost::new()?;
+            let tilt_path = host.preferences().tilt_path;
+            if running && !enabled {
+                let _ = set_resource_enabled(&host, tilt_path.as_deref(), tilt.port, &name, false)
+                    .await;
+            }
+
+            let project = set_service_enabled(workspace, &env, &name, enabled)?;
+            if running && enabled {
+                let _ =
+                    set_resource_enabled(&host, tilt_path.as_deref(), tilt.port, &name, true).await;
+            }
+            report(json, &project, || {
+                let state = if enabled { "Enabled" } else { "Disabled" };
+                println!("{} service {} in {}", state, name, env)
+            })?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,6 +1,8 @@
 use crate::app_state::model::{Preferences, RecentProject};
 use crate::app_state::{add_recent_project, load_state, remove_recent_project, save_state};
+use crate::backend::errors::IpcError;
 use crate::backend::ipc::handle_ipc;
+use crate::backend::redaction::validate_patterns;
 use tauri::command;
 
 #[command]
@@ -22,10 +24,11 @@ pub fn get_preferences(app: tauri::AppHandle) -> Preferences {
 
 /// Update user preferences
 #[command]
-pub fn update_preferences(app: tauri::AppHandle, preferences: Preferences) -> Result<(), String> {
+pub fn update_preferences(app: tauri::AppHandle, preferences: Preferences) -> Result<(), IpcError> {
+    validate_patterns(&preferences.log_redaction_patterns)?;
     let mut state = load_state(&app);
     state.preferences = preferences;
-    save_state(&app, &state).map_err(|e| e.to_string())
+    save_state(&app, &state).map_err(IpcError::from)
 }
 
 /// Add / update a recent project entry
@@ -34,24 +37,22 @@ pub fn add_recent_project_cmd(
     app: tauri::AppHandle,
     name: String,
     path: String,
-) -> Result<(), String> {
-    add_recent_project(&app, name, path).map_err(|e| e.to_string())
+) -> Result<(), IpcError> {
+    add_recent_project(&app, name, path).map_err(IpcError::from)
 }
 
 /// Remove a project from recent projects
 #[command]
-pub fn remove_recent_project_cmd(app: tauri::AppHandle, path: String) -> Result<(), String> {
-    remove_recent_project(&app, path).map_err(|e| e.to_string())
+pub fn remove_recent_project_cmd(app: tauri::AppHandle, path: String) -> Result<(), IpcError> {
+    remove_recent_project(&app, path).map_err(IpcError::from)
 }
 
+/// Run a registered IPC command, see `listCommands` for the available ones
 #[tauri::command]
 pub async fn call_backend(
     app: tauri::AppHandle,
     command: &str,
     args: serde_json::Value,
-) -> Result<String, String> {
-    handle_ipc(app, command, args)
-        .await
-        .map(|val| val.to_string())
-        .map_err(|e| e.to_string())
+) -> Result<serde_json::Value, IpcError> {
+    handle_ipc(app, command, args).await
 }
@@ -5,11 +5,22 @@
 
 mod app_state;
 mod backend;
+mod cli;
 mod commands;
-mod project;
+pub mod project;
 mod tray_icon;
 
+/// Public so integration tests can drive Tilt and jobs with `ScriptedRunner`
+/// and work on projects, their environments, secrets and logs
+pub use app_state::model::{LogRetention, NotificationPreferences};
+pub use backend::{
+    generator, git, host, inheritance, interpolation, jobs, log_records, log_search, log_store,
+    notifier, process, project as model, promotion, redaction, secrets, session_history,
+    tilt_manager, tilt_resources,
+};
+
 use app_state::{load_state, save_state};
+use backend::errors::{ErrorCode, IpcError};
 use tauri::{AppHandle, Manager, WindowEvent};
 use tokio::sync::RwLock;
 
@@ -20,17 +31,19 @@ fn greet(name: &str) -> String {
 }
 
 #[tauri::command]
-async fn close_splashscreen(window: tauri::Window) -> Result<(), String> {
+async fn close_splashscreen(window: tauri::Window) -> Result<(), IpcError> {
+    let internal = |e: tauri::Error| IpcError::new(ErrorCode::Internal, e.to_string());
+
     // Close splashscreen
     if let Some(splashscreen) = window.get_webview_window("splashscreen") {
-        splashscreen.close().map_err(|e| e.to_string())?;
+        splashscreen.close().map_err(internal)?;
     }
     // Show main window
     window
         .get_webview_window("main")
-        .ok_or("main window not found")?
+        .ok_or_else(|| IpcError::new(ErrorCode::NotFound, "main window not found"))?
         .show()
-        .map_err(|e| e.to_string())?;
+        .map_err(internal)?;
     Ok(())
 }
 
@@ -44,10 +57,21 @@ async fn __close_splashscreen(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Run the headless command line interface
+pub fn run_cli() -> std::process::ExitCode {
+    cli::run()
+}
+
+/// Write the TypeScript types of the IPC commands into `dir`
+pub fn export_bindings(dir: &std::path::Path) -> std::io::Result<()> {
+    backend::ipc::export_bindings(dir).map_err(|e| std::io::Error::other(e.to_string()))
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
             #[cfg(desktop)]
             {
@@ -67,6 +91,18 @@ pub fn run() {
 
             app.manage(RwLock::new(tray_icon::tray_manager::TrayState::default()));
 
+            tauri::async_runtime::spawn(backend::jobs::forward_to_webview(app.handle().clone()));
+
+            #[cfg(unix)]
+            {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = backend::control::serve(handle).await {
+                        eprintln!("Control socket unavailable: {}", e);
+                    }
+                });
+            }
+
             let _ = window.set_size(tauri::Size::Logical(tauri::LogicalSize {
                 width: state.window.width,
                 height: state.window.height,
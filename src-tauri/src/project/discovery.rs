@@ -0,0 +1,191 @@
+use std::fs;
+use std::path::Path;
+
+use crate::backend::errors::AppError;
+use crate::backend::project::{Docker, Helm, K8s, Kustomize, Repo, Service};
+
+/// Default port for Node services when the Dockerfile doesn't EXPOSE one
+const NODE_DEFAULT_PORT: u16 = 3000;
+/// Default port for everything else
+const DEFAULT_PORT: u16 = 8080;
+
+/// Directories that commonly hold a Helm chart inside a repository
+const CHART_DIRS: [&str; 4] = [".", "chart", "charts", "helm"];
+
+/// Scan `<workspace>/<services_path>/*` and propose a `Service` for each folder.
+///
+/// Detection is purely file based: a Dockerfile becomes a `docker` block, a
+/// `Chart.yaml` a `helm` block, a `kustomization.yaml` a `kustomize` block and a
+/// `k8s/` directory a `k8s` block. The port comes from the first `EXPOSE` of the
+/// Dockerfile's final stage, falling back to a language default.
+pub fn discover_services(workspace: &Path, services_path: &str) -> Result<Vec<Service>, AppError> {
+    let services_dir = workspace.join(services_path);
+
+    if !services_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut services = Vec::new();
+
+    for entry in fs::read_dir(&services_dir)? {
+        let entry = entry?;
+        let dir = entry.path();
+
+        if !dir.is_dir() {
+            continue;
+        }
+
+        let name = match dir.file_name().and_then(|n| n.to_str()) {
+            Some(n) if !n.starts_with('.') => n.to_string(),
+            _ => continue,
+        };
+
+        if let Some(service) = detect_service(&dir, &name) {
+            services.push(service);
+        }
+    }
+
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(services)
+}
+
+/// Build a service definition from the contents of a single repo folder.
+/// Returns `None` when nothing deployable or buildable is found.
+fn detect_service(dir: &Path, name: &str) -> Option<Service> {
+    let dockerfile = dir.join("Dockerfile");
+    let has_dockerfile = dockerfile.is_file();
+    let has_k8s_dir = dir.join("k8s").is_dir();
+    let has_package_json = dir.join("package.json").is_file();
+    let has_cargo_toml = dir.join("Cargo.toml").is_file();
+
+    let chart_dir = CHART_DIRS
+        .iter()
+        .find(|d| dir.join(d).join("Chart.yaml").is_file());
+
+    let kustomize_dir = [".", "k8s"]
+        .iter()
+        .find(|d| dir.join(d).join("kustomization.yaml").is_file());
+
+    if !has_dockerfile
+        && !has_k8s_dir
+        && !has_package_json
+        && !has_cargo_toml
+        && chart_dir.is_none()
+        && kustomize_dir.is_none()
+    {
+        return None;
+    }
+
+    let exposed_ports = if has_dockerfile {
+        fs::read_to_string(&dockerfile)
+            .map(|content| parse_exposed_ports(&content))
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    let port = exposed_ports
+        .first()
+        .copied()
+        .unwrap_or(if has_package_json {
+            NODE_DEFAULT_PORT
+        } else {
+            DEFAULT_PORT
+        });
+
+    let docker = has_dockerfile.then(|| Docker {
+        context: ".".to_string(),
+        dockerfile: "Dockerfile".to_string(),
+    });
+
+    // Relative to the service directory, the generator resolves it like the
+    // docker and k8s paths so it follows renames
+    let helm = chart_dir.map(|d| Helm {
+        chart: if *d == "." {
+            ".".to_string()
+        } else {
+            format!("./{}", d)
+        },
+        values: None,
+        namespace: None,
+    });
+
+    let kustomize = kustomize_dir.map(|d| Kustomize {
+        path: d.to_string(),
+    });
+
+    // A kustomization inside k8s/ already covers the manifests there
+    let k8s = (has_k8s_dir && kustomize.is_none()).then(|| K8s {
+        manifests: "k8s".to_string(),
+    });
+
+    Some(Service {
+        name: name.to_string(),
+        path: None,
+        port,
+        enabled: true,
+        repo: read_git_remote(dir),
+        docker,
+        k8s,
+        env: None,
+        depends_on: None,
+        helm,
+        kustomize,
+        replicas: None,
+        secrets: None,
+    })
+}
+
+/// Collect the ports from the `EXPOSE` instructions of a Dockerfile's final
+/// stage, the one that ends up in the image. Variable references such as
+/// `${PORT}` are skipped.
+fn parse_exposed_ports(dockerfile: &str) -> Vec<u16> {
+    let mut ports = Vec::new();
+
+    for line in dockerfile.lines() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some(instr) if instr.eq_ignore_ascii_case("FROM") => ports.clear(),
+            Some(instr) if instr.eq_ignore_ascii_case("EXPOSE") => ports
+                .extend(tokens.filter_map(|token| token.split('/').next()?.parse::<u16>().ok())),
+            _ => {}
+        }
+    }
+
+    ports
+}
+
+/// Read the `origin` remote from a cloned repository's `.git/config`
+fn read_git_remote(dir: &Path) -> Option<Repo> {
+    let config = fs::read_to_string(dir.join(".git").join("config")).ok()?;
+
+    let mut in_origin = false;
+    let mut url = None;
+
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_origin = line == r#"[remote "origin"]"#;
+            continue;
+        }
+
+        if in_origin {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "url" {
+                    url = Some(value.trim().to_string());
+                    break;
+                }
+            }
+        }
+    }
+
+    let branch = fs::read_to_string(dir.join(".git").join("HEAD"))
+        .ok()
+        .and_then(|head| {
+            head.trim()
+                .strip_prefix("ref: refs/heads/")
+                .map(str::to_string)
+        });
+
+    url.map(|url| Repo { url, branch })
+}
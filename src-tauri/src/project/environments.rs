@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::backend::errors::AppError;
+use crate::backend::generator::generate_tiltfiles;
+use crate::backend::project::{Environment, Project};
+use crate::backend::promotion::{apply_promotion, PromoteSelection};
+use crate::backend::secrets::{
+    clear_materialized_secrets, copy_env_secrets, delete_env_secrets, rename_env_secrets,
+};
+use crate::backend::tilt_manager::read_state;
+use crate::project::paths::*;
+use crate::project::store::{read_project, write_json};
+
+/// Create a new, empty environment
+pub fn create_environment(workspace_path: &str, env: &str) -> Result<Project, AppError> {
+    let root = Path::new(workspace_path);
+    let mut project = read_project(root)?;
+
+    validate_env_name(env)?;
+    ensure_env_absent(&project, env)?;
+
+    let environment = Environment {
+        shared_env: HashMap::new(),
+        services: Vec::new(),
+        extends: None,
+        overrides: None,
+        secrets: None,
+    };
+
+    write_json(&env_file(root, env), &environment)?;
+    project.environments.insert(env.to_string(), environment);
+    write_json(&project_file(root), &project)?;
+
+    generate_tiltfiles(&project, env)?;
+
+    Ok(project)
+}
+
+/// Clone an environment, including its services, shared env and stored
+/// secrets, under a new name
+pub fn clone_environment(
+    data_dir: &Path,
+    workspace_path: &str,
+    source: &str,
+    target: &str,
+) -> Result<Project, AppError> {
+    let root = Path::new(workspace_path);
+    let mut project = read_project(root)?;
+
+    validate_env_name(target)?;
+    ensure_env_absent(&project, target)?;
+
+    let environment = project
+        .environments
+        .get(source)
+        .cloned()
+        .ok_or_else(|| AppError::env_not_found(source))?;
+
+    write_json(&env_file(root, target), &environment)?;
+    project.environments.insert(target.to_string(), environment);
+    write_json(&project_file(root), &project)?;
+    copy_env_secrets(data_dir, &project, source, target)?;
+
+    generate_tiltfiles(&project, target)?;
+
+    Ok(project)
+}
+
+/// Rename an environment and move every file and stored secret that is keyed by its name
+pub fn rename_environment(
+    data_dir: &Path,
+    workspace_path: &str,
+    env: &str,
+    new_name: &str,
+) -> Result<Project, AppError> {
+    let root = Path::new(workspace_path);
+    let mut project = read_project(root)?;
+
+    validate_env_name(new_name)?;
+    ensure_env_absent(&project, new_name)?;
+    ensure_env_stopped(workspace_path, env)?;
+
+    let environment = project
+        .environments
+        .remove(env)
+        .ok_or_else(|| AppError::env_not_found(env))?;
+
+    // Generated .env files live inside each service directory
+    for path in service_env_files(root, &project, &environment, env) {
+        if path.exists() {
+            let renamed = path.with_file_name(format!(".env.{}", new_name));
+            fs::rename(&path, renamed)?;
+        }
+    }
+
+    // Materialized secrets are written again at the next start
+    clear_materialized_secrets(workspace_path, env)?;
+
+    // Runtime state and logs
+    let tooling = root.join(".tooling");
+    for path in runtime_files(&tooling, env, &project)? {
+        fs::rename(&path, renamed_runtime_file(&tooling, &path, env, new_name))?;
+    }
+
+    let tilt_dir = root.join("tilt").join(env);
+    if tilt_dir.exists() {
+        fs::rename(&tilt_dir, root.join("tilt").join(new_name))?;
+    }
+
+    let old_env_file = env_file(root, env);
+    if old_env_file.exists() {
+        fs::remove_file(old_env_file)?;
+    }
+    write_json(&env_file(root, new_name), &environment)?;
+
+    project
+        .environments
+        .insert(new_name.to_string(), environment);
+
+    // Point environments that extended the old name at the new one
+    for child in child_environments(&project, env) {
+        if let Some(child_env) = project.environments.get_mut(&child) {
+            child_env.extends = Some(new_name.to_string());
+            write_json(&env_file(root, &child), child_env)?;
+        }
+    }
+
+    write_json(&project_file(root), &project)?;
+    rename_env_secrets(data_dir, &project, env, new_name)?;
+
+    // The root Tiltfile carries the environment name, so regenerate it
+    generate_tiltfiles(&project, new_name)?;
+
+    Ok(project)
+}
+
+/// Delete an environment, every file that is keyed by its name and its stored secrets
+pub fn delete_environment(
+    data_dir: &Path,
+    workspace_path: &str,
+    env: &str,
+) -> Result<Project, AppError> {
+    let root = Path::new(workspace_path);
+    let mut project = read_project(root)?;
+
+    ensure_env_stopped(workspace_path, env)?;
+
+    let children = child_environments(&project, env);
+    if !children.is_empty() {
+        return Err(AppError::Invalid(format!(
+            "Environment {} is extended by {}",
+            env,
+            children.join(", ")
+        )));
+    }
+
+    let environment = project
+        .environments
+        .remove(env)
+        .ok_or_else(|| AppError::env_not_found(env))?;
+
+    for path in service_env_files(root, &project, &environment, env) {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+
+    for path in runtime_files(&root.join(".tooling"), env, &project)? {
+        if path.is_dir() {
+            fs::remove_dir_all(path)?;
+        } else {
+            fs::remove_file(path)?;
+        }
+    }
+    clear_materialized_secrets(workspace_path, env)?;
+
+    let tilt_dir = root.join("tilt").join(env);
+    if tilt_dir.exists() {
+        fs::remove_dir_all(tilt_dir)?;
+    }
+
+    let env_path = env_file(root, env);
+    if env_path.exists() {
+        fs::remove_file(env_path)?;
+    }
+
+    write_json(&project_file(root), &project)?;
+    delete_env_secrets(data_dir, &project, env)?;
+
+    Ok(project)
+}
+
+/// Apply a selected subset of the source → target diff and persist the target
+pub fn promote_environment(
+    workspace_path: &str,
+    source: &str,
+    target: &str,
+    selection: &PromoteSelection,
+) -> Result<Project, AppError> {
+    let root = Path::new(workspace_path);
+    let mut project = read_project(root)?;
+
+    apply_promotion(&mut project, source, target, selection)?;
+
+    if let Some(environment) = project.environments.get(target) {
+        write_json(&env_file(root, target), environment)?;
+    }
+    write_json(&project_file(root), &project)?;
+
+    generate_tiltfiles(&project, target)?;
+
+    Ok(project)
+}
+
+/// Environment names end up in file and directory names, so keep them simple
+pub fn validate_env_name(env: &str) -> Result<(), AppError> {
+    let valid = !env.is_empty()
+        && env
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Invalid(format!(
+            "Invalid environment name '{}': use letters, digits, '-' or '_'",
+            env
+        )))
+    }
+}
+
+/// Refuse to touch an environment whose Tilt process is running
+pub fn ensure_env_stopped(workspace_path: &str, env: &str) -> Result<(), AppError> {
+    let state = read_state(workspace_path, env);
+
+    if state.tilt.status == "running" || state.tilt.status == "starting" {
+        return Err(AppError::EnvRunning {
+            env: env.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+fn ensure_env_absent(project: &Project, env: &str) -> Result<(), AppError> {
+    if project.environments.contains_key(env) {
+        return Err(AppError::EnvExists {
+            env: env.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Names of the environments that directly extend `env`
+fn child_environments(project: &Project, env: &str) -> Vec<String> {
+    let mut children: Vec<String> = project
+        .environments
+        .iter()
+        .filter(|(_, e)| e.extends.as_deref() == Some(env))
+        .map(|(k, _)| k.clone())
+        .collect();
+    children.sort();
+    children
+}
+
+/// Paths of the generated `.env.<env>` file for every service in the environment
+fn service_env_files(
+    root: &Path,
+    project: &Project,
+    environment: &Environment,
+    env: &str,
+) -> Vec<PathBuf> {
+    let services_path = project.project.services_path.as_deref().unwrap_or("repos");
+
+    environment
+        .services
+        .iter()
+        .map(|s| {
+            root.join(services_path)
+                .join(&s.name)
+                .join(format!(".env.{}", env))
+        })
+        .collect()
+}
+
+/// Files in `.tooling` that belong to `env` (`<env>_runtime.json`, `<env>_tilt.log`, ...)
+/// and the directories named after it one level down (`secrets/<env>`, ...).
+///
+/// An environment named `dev` must not claim the files of `dev_eu`, so names that
+/// match a longer, still existing environment are skipped.
+fn runtime_files(tooling: &Path, env: &str, project: &Project) -> Result<Vec<PathBuf>, AppError> {
+    if !tooling.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let prefix = format!("{}_", env);
+    let longer_envs: Vec<String> = project
+        .environments
+        .keys()
+        .filter(|k| k.as_str() != env && k.starts_with(&prefix))
+        .map(|k| format!("{}_", k))
+        .collect();
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(tooling)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+
+        if path.is_dir() {
+            let env_dir = path.join(env);
+            if env_dir.is_dir() {
+                files.push(env_dir);
+            }
+        } else if name.starts_with(&prefix)
+            && !longer_envs.iter().any(|other| name.starts_with(other))
+        {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Where a path from [`runtime_files`] goes when `env` is renamed to `new_name`
+fn renamed_runtime_file(tooling: &Path, path: &Path, env: &str, new_name: &str) -> PathBuf {
+    if path.parent() != Some(tooling) {
+        return path.with_file_name(new_name);
+    }
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    tooling.join(format!("{}{}", new_name, &name[env.len()..]))
+}
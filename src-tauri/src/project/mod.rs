@@ -1,20 +1,30 @@
 use chrono::Local;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::io;
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
 
 // mod backend;
+pub mod discovery;
+pub mod environments;
 pub mod paths;
 pub mod store;
 
 use crate::backend::errors::AppError;
-use crate::backend::generator::generate_tiltfiles;
-use crate::backend::git::clone_repo;
+use crate::backend::generator::{generate_tiltfiles, rename_k8s_deployment};
+use crate::backend::git::RepoCheckout;
+use crate::backend::inheritance::resolve_environment;
+use crate::backend::process::ProcessRunner;
 use crate::backend::project::{Environment, Project, ProjectInfo, Service, Tilt, TiltMode};
+use crate::backend::secrets::{new_project_id, rename_service_secrets};
+use crate::project::discovery::discover_services;
+use crate::project::environments::ensure_env_stopped;
 use crate::project::paths::*;
-use crate::project::store::{assert_service_path, read_json, rename_project, write_json};
+use crate::project::store::{
+    assert_service_path, read_json, read_project, rename_project, write_json,
+};
 
 pub fn __create_project(info: ProjectInfo) -> Result<(), AppError> {
     let root = project_root(&info.workspace_path, &info.name);
@@ -30,13 +40,17 @@ pub fn __create_project(info: ProjectInfo) -> Result<(), AppError> {
     let dev = Environment {
         shared_env: HashMap::new(),
         services: vec![],
+        extends: None,
+        overrides: None,
+        secrets: None,
     };
 
     write_json(&env_file(&root, "dev"), &dev)?;
     Ok(())
 }
 
-/// Create a new project workspace
+/// Create a new project workspace. Cloning the services' repositories
+/// (see `repo_checkouts`) and generating the Tiltfiles is left to the caller.
 pub fn create_project(
     name: &str,
     workspace_path: &str,
@@ -57,6 +71,9 @@ pub fn create_project(
         Environment {
             shared_env: HashMap::new(),
             services: Vec::new(),
+            extends: None,
+            overrides: None,
+            secrets: None,
         },
     );
     environments.insert(
@@ -64,6 +81,9 @@ pub fn create_project(
         Environment {
             shared_env: HashMap::new(),
             services: Vec::new(),
+            extends: None,
+            overrides: None,
+            secrets: None,
         },
     );
     environments.insert(
@@ -71,11 +91,15 @@ pub fn create_project(
         Environment {
             shared_env: HashMap::new(),
             services: Vec::new(),
+            extends: None,
+            overrides: None,
+            secrets: None,
         },
     );
 
     let project = Project {
         project: ProjectInfo {
+            id: Some(new_project_id()),
             name: name.to_string(),
             workspace_path: project_path.to_string_lossy().to_string(),
             tilt: Tilt {
@@ -91,39 +115,50 @@ pub fn create_project(
     for (key, environment) in project.environments.iter() {
         write_json(&env_file(&project_path, key), &environment)?;
 
-        // Clone repositories and create service directories for each service
         for service in environment.services.iter() {
-            let service_dir = project_path.join(services_path).join(&service.name);
-
-            // Create service directory
-            fs::create_dir_all(&service_dir)?;
-
-            // Clone repository if configured
-            if let Some(repo) = &service.repo {
-                let rt = tokio::runtime::Runtime::new()
-                    .map_err(|e| AppError::Io(io::Error::other(e.to_string())))?;
-
-                rt.block_on(async {
-                    clone_repo(
-                        &repo.url,
-                        service_dir.to_str().unwrap(),
-                        repo.branch.as_deref(),
-                    )
-                    .await
-                })
-                .map_err(AppError::Io)?;
-            }
+            fs::create_dir_all(project_path.join(services_path).join(&service.name))?;
         }
-
-        // Generate tiltfiles after setting up services
-        let _ = generate_tiltfiles(&project, key);
     }
 
     Ok(project)
 }
 
-pub fn update_project(workspace_path: &str, project: &Project) -> Result<Project, AppError> {
-    let p: Project = read_json(&project_file(workspace_path.as_ref()))?;
+/// Repositories of the project's services, one per service directory.
+/// Environments sharing a service share its checkout.
+pub fn repo_checkouts(project: &Project) -> Vec<RepoCheckout> {
+    let root = Path::new(&project.project.workspace_path);
+    let services_path = project.project.services_path.as_deref().unwrap_or("repos");
+
+    let mut envs: Vec<&String> = project.environments.keys().collect();
+    envs.sort();
+
+    let mut checkouts: Vec<RepoCheckout> = Vec::new();
+    for env in envs {
+        for service in &project.environments[env].services {
+            let Some(repo) = &service.repo else {
+                continue;
+            };
+            if checkouts.iter().any(|c| c.service == service.name) {
+                continue;
+            }
+            checkouts.push(RepoCheckout {
+                service: service.name.clone(),
+                url: repo.url.clone(),
+                branch: repo.branch.clone(),
+                dir: root.join(services_path).join(&service.name),
+            });
+        }
+    }
+    checkouts
+}
+
+/// Save an edited project. Cloning the repositories of the services it adds
+/// is left to the caller, which gets their checkouts back.
+pub fn update_project(
+    workspace_path: &str,
+    project: &Project,
+) -> Result<(Project, Vec<RepoCheckout>), AppError> {
+    let p = read_project(workspace_path.as_ref())?;
 
     let project_name_old: String = p.project.name.to_string();
     let is_name_changed: bool = project_name_old != project.project.name;
@@ -141,9 +176,17 @@ pub fn update_project(workspace_path: &str, project: &Project) -> Result<Project
         }
     }
 
-    write_json(&project_file(Path::new(&project_path)), &project)?;
+    // The webview may hold the project from before it got its id
+    let mut project = project.clone();
+    if project.project.id.is_none() {
+        project.project.id = p.project.id;
+    }
+    let project = &project;
+
+    write_json(&project_file(Path::new(&project_path)), project)?;
 
     let services_path = project.project.services_path.as_deref().unwrap_or("repos");
+    let mut checkouts: Vec<RepoCheckout> = Vec::new();
 
     for (key, environment) in project.environments.iter() {
         // Get old environment to compare services
@@ -151,6 +194,9 @@ pub fn update_project(workspace_path: &str, project: &Project) -> Result<Project
             read_json(&env_file(Path::new(&project_path), key)).unwrap_or(Environment {
                 shared_env: HashMap::new(),
                 services: vec![],
+                extends: None,
+                overrides: None,
+                secrets: None,
             });
 
         // Find new services (not in old environment)
@@ -182,28 +228,10 @@ pub fn update_project(workspace_path: &str, project: &Project) -> Result<Project
             }
 
             // Clone repository for new services if configured
-            if new_services.iter().any(|s| s.name == service.name) {
-                if let Some(repo) = &service.repo {
-                    // Only clone if directory is empty
-                    if service_dir
-                        .read_dir()
-                        .map(|mut i| i.next().is_none())
-                        .unwrap_or(true)
-                    {
-                        let rt = tokio::runtime::Runtime::new()
-                            .map_err(|e| AppError::Io(io::Error::other(e.to_string())))?;
-
-                        rt.block_on(async {
-                            clone_repo(
-                                &repo.url,
-                                service_dir.to_str().unwrap(),
-                                repo.branch.as_deref(),
-                            )
-                            .await
-                        })
-                        .map_err(AppError::Io)?;
-                    }
-                }
+            if new_services.iter().any(|s| s.name == service.name)
+                && !checkouts.iter().any(|c| c.service == service.name)
+            {
+                checkouts.extend(pending_checkout(&service_dir, service));
             }
 
             // Ensure custom path exists if specified
@@ -213,11 +241,12 @@ pub fn update_project(workspace_path: &str, project: &Project) -> Result<Project
         }
     }
 
-    Ok(project.clone())
+    Ok((project.clone(), checkouts))
 }
 
 /// Open a repository in the user’s editor (default VSCode)
-pub fn open_in_editor(
+pub async fn open_in_editor<R: ProcessRunner>(
+    runner: &R,
     workspace: &Path,
     repo_name: &str,
     editor: Option<&str>,
@@ -234,12 +263,13 @@ pub fn open_in_editor(
         ));
     }
 
-    let status = Command::new(editor).arg(repo_path).status();
+    let repo_path = repo_path.to_string_lossy();
+    let status = runner.status(editor, &[&repo_path], None).await;
 
     match status {
-        Ok(s) if s.success() => Ok(()),
-        Ok(s) => {
-            eprintln!("Failed to open editor: exited with status {}", s);
+        Ok(Some(0)) => Ok(()),
+        Ok(code) => {
+            eprintln!("Failed to open editor: exited with status {:?}", code);
             Ok(())
         }
         Err(e) => {
@@ -255,7 +285,7 @@ pub fn open_in_editor(
 // }
 
 pub fn load_project_info(root: &str) -> Result<Project, AppError> {
-    let p: Project = read_json(&project_file(root.as_ref()))?;
+    let p = read_project(root.as_ref())?;
     Ok(p)
 }
 
@@ -309,6 +339,9 @@ pub fn initialize_existing_project(path: &str, services_path: &str) -> Result<Pr
     // Create environments directory
     fs::create_dir_all(project_path.join("environments"))?;
 
+    // Propose services from the repos already cloned into the services directory
+    let discovered = discover_services(project_path, services_path)?;
+
     // Create default environments
     let mut environments = HashMap::new();
     for env_name in ["dev", "staging", "prod"] {
@@ -316,7 +349,10 @@ pub fn initialize_existing_project(path: &str, services_path: &str) -> Result<Pr
             env_name.to_string(),
             Environment {
                 shared_env: HashMap::new(),
-                services: Vec::new(),
+                services: discovered.clone(),
+                extends: None,
+                overrides: None,
+                secrets: None,
             },
         );
         write_json(&env_file(project_path, env_name), &environments[env_name])?;
@@ -324,6 +360,7 @@ pub fn initialize_existing_project(path: &str, services_path: &str) -> Result<Pr
 
     let project = Project {
         project: ProjectInfo {
+            id: Some(new_project_id()),
             name: name.to_string(),
             workspace_path: project_path.to_string_lossy().to_string(),
             tilt: Tilt {
@@ -338,7 +375,7 @@ pub fn initialize_existing_project(path: &str, services_path: &str) -> Result<Pr
 
     // Generate Tiltfiles for all environments
     for env_name in ["dev", "staging", "prod"] {
-        generate_tiltfiles(&project, env_name).map_err(|e| std::io::Error::other(e.to_string()))?;
+        generate_tiltfiles(&project, env_name)?;
     }
 
     Ok(project)
@@ -352,27 +389,20 @@ pub fn update_service(
     updated_service: Service,
 ) -> Result<Project, AppError> {
     let project_path = Path::new(workspace_path);
-    let mut project: Project = read_json(&project_file(project_path))?;
+    let mut project = read_project(project_path)?;
 
     // Get the environment
-    let environment = project.environments.get_mut(env).ok_or_else(|| {
-        AppError::Io(io::Error::new(
-            io::ErrorKind::NotFound,
-            format!("Environment {} not found", env),
-        ))
-    })?;
+    let environment = project
+        .environments
+        .get_mut(env)
+        .ok_or_else(|| AppError::env_not_found(env))?;
 
     // Find and update the service
     let service_index = environment
         .services
         .iter()
         .position(|s| s.name == service_name)
-        .ok_or_else(|| {
-            AppError::Io(io::Error::new(
-                io::ErrorKind::NotFound,
-                format!("Service {} not found in environment {}", service_name, env),
-            ))
-        })?;
+        .ok_or_else(|| AppError::service_not_found(service_name, Some(env)))?;
 
     environment.services[service_index] = updated_service.clone();
 
@@ -385,6 +415,371 @@ pub fn update_service(
     Ok(project)
 }
 
+/// Enable or disable a service in an environment and regenerate its Tiltfiles.
+/// A service inherited from a parent environment gets an `enabled` override
+/// instead of a local copy.
+pub fn set_service_enabled(
+    workspace_path: &str,
+    env: &str,
+    service_name: &str,
+    enabled: bool,
+) -> Result<Project, AppError> {
+    let project_path = Path::new(workspace_path);
+    let mut project = read_project(project_path)?;
+
+    let inherited = resolve_environment(&project, env)?
+        .services
+        .iter()
+        .any(|s| s.name == service_name);
+
+    let environment = project
+        .environments
+        .get_mut(env)
+        .ok_or_else(|| AppError::env_not_found(env))?;
+
+    let patch = environment
+        .overrides
+        .as_mut()
+        .and_then(|o| o.get_mut(service_name))
+        .filter(|o| o.enabled.is_some());
+
+    // An existing override wins over the definition, so change it in place
+    if let Some(patch) = patch {
+        patch.enabled = Some(enabled);
+    } else if let Some(service) = environment
+        .services
+        .iter_mut()
+        .find(|s| s.name == service_name)
+    {
+        service.enabled = enabled;
+    } else if inherited {
+        environment
+            .overrides
+            .get_or_insert_with(HashMap::new)
+            .entry(service_name.to_string())
+            .or_default()
+            .enabled = Some(enabled);
+    } else {
+        return Err(AppError::service_not_found(service_name, Some(env)));
+    }
+
+    write_environment(project_path, &project, env)?;
+    generate_tiltfiles(&project, env)?;
+
+    Ok(project)
+}
+
+/// Result of removing a service, with the services that depended on it
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ServiceRemoval {
+    pub project: Project,
+    pub dependents: Vec<String>,
+}
+
+/// Add a new service to an environment. Cloning its repository is left to
+/// the caller, which gets the checkout back when one is needed.
+pub fn add_service(
+    workspace_path: &str,
+    env: &str,
+    service: Service,
+) -> Result<(Project, Option<RepoCheckout>), AppError> {
+    let project_path = Path::new(workspace_path);
+    let mut project = read_project(project_path)?;
+    let services_path = project
+        .project
+        .services_path
+        .clone()
+        .unwrap_or_else(|| "repos".to_string());
+
+    validate_service_name(&service.name)?;
+
+    let environment = project
+        .environments
+        .get_mut(env)
+        .ok_or_else(|| AppError::env_not_found(env))?;
+
+    if environment.services.iter().any(|s| s.name == service.name) {
+        return Err(AppError::ServiceExists {
+            service: service.name.clone(),
+            env: env.to_string(),
+        });
+    }
+
+    environment.services.push(service.clone());
+
+    let service_dir = project_path.join(&services_path).join(&service.name);
+    fs::create_dir_all(&service_dir)?;
+    let checkout = pending_checkout(&service_dir, &service);
+
+    if let Some(path) = &service.path {
+        assert_service_path(Path::new(path))?;
+    }
+
+    write_environment(project_path, &project, env)?;
+    generate_tiltfiles(&project, env)?;
+
+    Ok((project, checkout))
+}
+
+/// Remove a service from an environment.
+///
+/// Services that depend on it have the dependency dropped and are reported back
+/// so the caller can warn about them. The service directory is only deleted when
+/// requested and no other environment still uses it.
+pub fn remove_service(
+    workspace_path: &str,
+    env: &str,
+    service_name: &str,
+    delete_directory: bool,
+) -> Result<ServiceRemoval, AppError> {
+    let project_path = Path::new(workspace_path);
+    let mut project = read_project(project_path)?;
+    let services_path = project
+        .project
+        .services_path
+        .clone()
+        .unwrap_or_else(|| "repos".to_string());
+
+    ensure_env_stopped(workspace_path, env)?;
+
+    let environment = project
+        .environments
+        .get_mut(env)
+        .ok_or_else(|| AppError::env_not_found(env))?;
+
+    let before = environment.services.len();
+    environment.services.retain(|s| s.name != service_name);
+    if environment.services.len() == before {
+        // Dropping the override would leave the inherited service running
+        if environment
+            .overrides
+            .as_ref()
+            .is_some_and(|o| o.contains_key(service_name))
+        {
+            return Err(AppError::Invalid(format!(
+                "Service {} is inherited by {} and can only be removed where it is defined",
+                service_name, env
+            )));
+        }
+        return Err(AppError::service_not_found(service_name, Some(env)));
+    }
+    if let Some(overrides) = environment.overrides.as_mut() {
+        overrides.remove(service_name);
+    }
+
+    let mut dependents = Vec::new();
+    for service in environment.services.iter_mut() {
+        if let Some(deps) = service.depends_on.as_mut() {
+            if deps.iter().any(|d| d == service_name) {
+                deps.retain(|d| d != service_name);
+                dependents.push(service.name.clone());
+            }
+        }
+    }
+
+    write_environment(project_path, &project, env)?;
+
+    let service_dir = project_path.join(&services_path).join(service_name);
+    let env_file_path = service_dir.join(format!(".env.{}", env));
+    if env_file_path.exists() {
+        fs::remove_file(env_file_path)?;
+    }
+
+    let tiltfile = service_tiltfile(project_path, env, service_name);
+    if tiltfile.exists() {
+        fs::remove_file(tiltfile)?;
+    }
+
+    let still_used = project
+        .environments
+        .values()
+        .any(|e| e.services.iter().any(|s| s.name == service_name));
+    if delete_directory && !still_used && service_dir.exists() {
+        fs::remove_dir_all(&service_dir)?;
+    }
+
+    generate_tiltfiles(&project, env)?;
+
+    Ok(ServiceRemoval {
+        project,
+        dependents,
+    })
+}
+
+/// Rename a service in every environment that has it.
+///
+/// `depends_on` references are rewritten across all environments, the per-service
+/// Tiltfiles are regenerated, stored secrets follow the new name, and the
+/// service directory moves with it since the generator finds it by name.
+pub fn rename_service(
+    data_dir: &Path,
+    workspace_path: &str,
+    service_name: &str,
+    new_name: &str,
+) -> Result<Project, AppError> {
+    let project_path = Path::new(workspace_path);
+    let mut project = read_project(project_path)?;
+    let services_path = project
+        .project
+        .services_path
+        .clone()
+        .unwrap_or_else(|| "repos".to_string());
+
+    validate_service_name(new_name)?;
+
+    let affected: Vec<String> = project
+        .environments
+        .iter()
+        .filter(|(_, e)| {
+            e.services.iter().any(|s| s.name == service_name)
+                || e.overrides
+                    .as_ref()
+                    .is_some_and(|o| o.contains_key(service_name))
+        })
+        .map(|(k, _)| k.clone())
+        .collect();
+
+    if affected.is_empty() {
+        return Err(AppError::service_not_found(service_name, None));
+    }
+
+    for env in &affected {
+        ensure_env_stopped(workspace_path, env)?;
+
+        if project.environments[env]
+            .services
+            .iter()
+            .any(|s| s.name == new_name)
+        {
+            return Err(AppError::ServiceExists {
+                service: new_name.to_string(),
+                env: env.clone(),
+            });
+        }
+    }
+
+    let old_dir = project_path.join(&services_path).join(service_name);
+    let new_dir = project_path.join(&services_path).join(new_name);
+
+    if new_dir.exists() {
+        return Err(AppError::file(
+            &new_dir,
+            io::ErrorKind::AlreadyExists.into(),
+        ));
+    }
+
+    if old_dir.exists() {
+        fs::rename(&old_dir, &new_dir)?;
+    }
+    let manifests = rename_k8s_deployment(&new_dir, service_name, new_name)?;
+
+    let env_names: Vec<String> = project.environments.keys().cloned().collect();
+
+    for env in &env_names {
+        let environment = project.environments.get_mut(env).unwrap();
+        let mut changed = false;
+
+        for service in environment.services.iter_mut() {
+            if service.name == service_name {
+                service.name = new_name.to_string();
+                if let (Some(k8s), Some((old, new))) = (service.k8s.as_mut(), &manifests) {
+                    if &k8s.manifests == old {
+                        k8s.manifests = new.clone();
+                    }
+                }
+                changed = true;
+            }
+
+            if let Some(deps) = service.depends_on.as_mut() {
+                for dep in deps.iter_mut().filter(|d| d.as_str() == service_name) {
+                    *dep = new_name.to_string();
+                    changed = true;
+                }
+            }
+        }
+
+        if let Some(overrides) = environment.overrides.as_mut() {
+            if let Some(patch) = overrides.remove(service_name) {
+                overrides.insert(new_name.to_string(), patch);
+                changed = true;
+            }
+        }
+
+        if changed {
+            write_environment(project_path, &project, env)?;
+        }
+    }
+
+    // Environments that inherit the service change too, so regenerate all of them
+    for env in &env_names {
+        let old_tiltfile = service_tiltfile(project_path, env, service_name);
+        if old_tiltfile.exists() {
+            fs::remove_file(old_tiltfile)?;
+        }
+
+        generate_tiltfiles(&project, env)?;
+    }
+
+    rename_service_secrets(data_dir, &project, service_name, new_name)?;
+
+    Ok(project)
+}
+
+/// Service names become directory names and Tiltfile function names
+fn validate_service_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Invalid(format!(
+            "Invalid service name '{}': use letters, digits, '-' or '_'",
+            name
+        )))
+    }
+}
+
+fn service_tiltfile(project_path: &Path, env: &str, service_name: &str) -> PathBuf {
+    project_path
+        .join("tilt")
+        .join(env)
+        .join("services")
+        .join(format!("{}.tilt.py", service_name))
+}
+
+/// Checkout of the service's repository, when it has one and its directory
+/// is still empty
+fn pending_checkout(service_dir: &Path, service: &Service) -> Option<RepoCheckout> {
+    let repo = service.repo.as_ref()?;
+
+    let is_empty = service_dir
+        .read_dir()
+        .map(|mut i| i.next().is_none())
+        .unwrap_or(true);
+
+    is_empty.then(|| RepoCheckout {
+        service: service.name.clone(),
+        url: repo.url.clone(),
+        branch: repo.branch.clone(),
+        dir: service_dir.to_path_buf(),
+    })
+}
+
+/// Persist one environment to both its own file and `project.json`
+pub fn write_environment(
+    project_path: &Path,
+    project: &Project,
+    env: &str,
+) -> Result<(), AppError> {
+    if let Some(environment) = project.environments.get(env) {
+        write_json(&env_file(project_path, env), environment)?;
+    }
+    write_json(&project_file(project_path), project)
+}
+
 // pub fn load_environment(root: &str, env: &str) -> Result<Environment, AppError> {
 //     read_json(&env_file(root.as_ref(), env))
 // }
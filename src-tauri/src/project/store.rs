@@ -1,21 +1,38 @@
 use serde::{de::DeserializeOwned, Serialize};
-use std::{fs, path::Path};
+use std::{fs, io, path::Path};
 
 use crate::backend::errors::AppError;
+use crate::backend::project::Project;
+use crate::project::paths::project_file;
 
 pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, AppError> {
-    let data = fs::read_to_string(path)?;
-    Ok(serde_json::from_str(&data)?)
+    let data = fs::read_to_string(path).map_err(|e| AppError::file(path, e))?;
+    serde_json::from_str(&data).map_err(|source| AppError::InvalidJson {
+        path: path.to_path_buf(),
+        source,
+    })
 }
 
 pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), AppError> {
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+        fs::create_dir_all(parent).map_err(|e| AppError::file(parent, e))?;
     }
-    fs::write(path, serde_json::to_string_pretty(value)?)?;
+    fs::write(path, serde_json::to_string_pretty(value)?).map_err(|e| AppError::file(path, e))?;
     Ok(())
 }
 
+/// Read the `project.json` of a workspace
+pub fn read_project(root: &Path) -> Result<Project, AppError> {
+    read_json(&project_file(root)).map_err(|e| match e {
+        AppError::File { source, .. } if source.kind() == io::ErrorKind::NotFound => {
+            AppError::ProjectNotFound {
+                path: root.to_path_buf(),
+            }
+        }
+        e => e,
+    })
+}
+
 pub fn rename_project(
     current_dir: &Path,
     new_dir: &Path,
@@ -1,12 +1,22 @@
 use serde::{Deserialize, Serialize};
 use tauri::{
-    menu::{Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder},
+    image::Image,
+    menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder},
     tray::TrayIconBuilder,
     AppHandle, Emitter, Manager,
 };
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 use tokio::sync::RwLock;
 
+use crate::app_state::{add_recent_project, load_state};
+use crate::backend::errors::IpcError;
+use crate::backend::host::TrayHealth;
+use crate::backend::inheritance::resolve_environment;
 use crate::backend::project::Project;
+use crate::backend::session_history::StopInitiator;
+use crate::backend::tilt_manager::{read_state, restart_tilt, start_tilt, stop_tilt, TiltLaunch};
+use crate::backend::tilt_resources::set_resource_enabled;
+use crate::project::{load_project_info, set_service_enabled};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TiltState {
@@ -15,11 +25,120 @@ pub struct TiltState {
     pub web_ui_url: Option<String>,
 }
 
+/// How the tray shows an environment's health
+impl TrayHealth {
+    fn label(&self) -> &'static str {
+        match self {
+            TrayHealth::Idle => "Idle",
+            TrayHealth::Starting => "Starting",
+            TrayHealth::Healthy => "All resources healthy",
+            TrayHealth::Degraded => "Some resources failing",
+            TrayHealth::Failed => "Failed",
+        }
+    }
+
+    fn badge(&self) -> Option<[u8; 3]> {
+        match self {
+            TrayHealth::Idle => None,
+            TrayHealth::Starting => Some([59, 130, 246]),
+            TrayHealth::Healthy => Some([34, 197, 94]),
+            TrayHealth::Degraded => Some([245, 158, 11]),
+            TrayHealth::Failed => Some([239, 68, 68]),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TrayState {
     pub current_project: Option<Project>,
     pub current_env: Option<String>,
     pub tilt_state: Option<TiltState>,
+    /// Owned by the backend, frontend updates keep it
+    #[serde(default)]
+    pub health: TrayHealth,
+}
+
+/// The app icon with a colored status dot in the bottom-right corner
+fn status_icon(base: &Image<'_>, health: TrayHealth) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+
+    if let Some([r, g, b]) = health.badge() {
+        let radius = width.min(height) as f32 * 0.22;
+        let (cx, cy) = (width as f32 - radius - 1.0, height as f32 - radius - 1.0);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+                if dx * dx + dy * dy <= radius * radius {
+                    let i = ((y * width + x) * 4) as usize;
+                    rgba[i..i + 4].copy_from_slice(&[r, g, b, 255]);
+                }
+            }
+        }
+    }
+
+    Image::new_owned(rgba, width, height)
+}
+
+fn tooltip(state: &TrayState) -> String {
+    match (&state.current_project, &state.current_env) {
+        (Some(project), Some(env)) => format!(
+            "Tilt Orchestrator - {} ({}): {}",
+            project.project.name,
+            env,
+            state.health.label()
+        ),
+        _ => "Tilt Orchestrator".to_string(),
+    }
+}
+
+/// Rebuild the menu, icon and tooltip from the tray state
+fn refresh_tray(app: &AppHandle, state: &TrayState) -> tauri::Result<()> {
+    let menu = build_menu(app, state)?;
+
+    if let Some(tray) = app.tray_by_id("main") {
+        tray.set_menu(Some(menu))?;
+        tray.set_tooltip(Some(tooltip(state)))?;
+        if let Some(icon) = app.default_window_icon() {
+            tray.set_icon(Some(status_icon(icon, state.health)))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Update the tray health when it shows the given environment.
+/// Called from `tilt_manager` so the tray tracks Tilt without the webview.
+pub async fn set_tray_health(app: &AppHandle, workspace: &str, env: &str, health: TrayHealth) {
+    let Some(state_handle) = app.try_state::<RwLock<TrayState>>() else {
+        return;
+    };
+    let mut state = state_handle.write().await;
+
+    let shown = state
+        .current_project
+        .as_ref()
+        .is_some_and(|p| p.project.workspace_path == workspace)
+        && state.current_env.as_deref() == Some(env);
+
+    if !shown || state.health == health {
+        return;
+    }
+
+    state.health = health;
+    let is_running = health != TrayHealth::Idle;
+    let tilt_state = state.tilt_state.get_or_insert_with(|| TiltState {
+        status: String::new(),
+        is_running,
+        web_ui_url: None,
+    });
+    tilt_state.is_running = is_running;
+    tilt_state.status = if is_running { "running" } else { "stopped" }.to_string();
+
+    if let Err(e) = refresh_tray(app, &state) {
+        eprintln!("Failed to update tray: {}", e);
+    }
 }
 
 fn build_menu(app: &AppHandle, state: &TrayState) -> tauri::Result<Menu<tauri::Wry>> {
@@ -31,7 +150,27 @@ fn build_menu(app: &AppHandle, state: &TrayState) -> tauri::Result<Menu<tauri::W
     // 2. Project Name
     if let Some(ref project) = state.current_project {
         let project_name = format!("Project: {}", project.project.name);
-        menu_builder = menu_builder.text("project_name", project_name).separator();
+        menu_builder = menu_builder.text("project_name", project_name);
+
+        // Environments, the shown one checked
+        let mut env_names: Vec<&String> = project.environments.keys().collect();
+        env_names.sort();
+
+        let mut env_submenu = SubmenuBuilder::new(app, "Environment");
+        for env_name in env_names {
+            let label = if env_running(&project.project.workspace_path, env_name) {
+                format!("{} (running)", env_name)
+            } else {
+                env_name.clone()
+            };
+            let env_item = CheckMenuItemBuilder::new(label)
+                .id(format!("env_select_{}", env_name))
+                .checked(state.current_env.as_deref() == Some(env_name.as_str()))
+                .build(app)?;
+            env_submenu = env_submenu.item(&env_item);
+        }
+
+        menu_builder = menu_builder.item(&env_submenu.build()?).separator();
 
         // 3. Tilt Controls Submenu
         let is_running = state
@@ -72,25 +211,24 @@ fn build_menu(app: &AppHandle, state: &TrayState) -> tauri::Result<Menu<tauri::W
 
         // 4. Services Submenu
         if let Some(ref env_name) = state.current_env {
-            if let Some(environment) = project.environments.get(env_name) {
+            if let Ok(environment) = resolve_environment(project, env_name) {
                 if !environment.services.is_empty() {
                     let mut services_submenu = SubmenuBuilder::new(app, "Services");
 
                     for service in &environment.services {
                         let service_name = service.name.clone();
 
-                        // TODO: Implement toggling service status properly including regenerating the tilt files
-                        // let enable_text = if service.enabled { "Disable" } else { "Enable" };
-                        // let enable_item = MenuItemBuilder::new(enable_text)
-                        //     .id(&format!("service_toggle_{}", service_name))
-                        //     .build(app)?;
+                        let enable_text = if service.enabled { "Disable" } else { "Enable" };
+                        let enable_item = MenuItemBuilder::new(enable_text)
+                            .id(format!("service_toggle_{}", service_name))
+                            .build(app)?;
 
                         let editor_item = MenuItemBuilder::new("Open in Editor")
                             .id(format!("service_editor_{}", service_name))
                             .build(app)?;
 
                         let service_submenu = SubmenuBuilder::new(app, &service_name)
-                            // .item(&enable_item)
+                            .item(&enable_item)
                             .item(&editor_item)
                             .build()?;
 
@@ -115,6 +253,26 @@ fn build_menu(app: &AppHandle, state: &TrayState) -> tauri::Result<Menu<tauri::W
             .separator();
     }
 
+    // Recent projects, from the saved app state
+    let recent_projects = load_state(app).recent_projects;
+    if !recent_projects.is_empty() {
+        let current_path = state
+            .current_project
+            .as_ref()
+            .map(|p| p.project.workspace_path.as_str());
+
+        let mut recent_submenu = SubmenuBuilder::new(app, "Recent Projects");
+        for recent in &recent_projects {
+            let recent_item = CheckMenuItemBuilder::new(&recent.name)
+                .id(format!("recent_project_{}", recent.path))
+                .checked(current_path == Some(recent.path.as_str()))
+                .build(app)?;
+            recent_submenu = recent_submenu.item(&recent_item);
+        }
+
+        menu_builder = menu_builder.item(&recent_submenu.build()?).separator();
+    }
+
     // Quit
     menu_builder = menu_builder.text("quit", "Quit");
 
@@ -127,6 +285,7 @@ pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
 
     let _tray = TrayIconBuilder::with_id("main")
         .icon(app.default_window_icon().unwrap().clone())
+        .tooltip(tooltip(&state))
         .menu(&menu)
         .on_menu_event(handle_menu_event)
         .build(app)?;
@@ -144,31 +303,33 @@ fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
                 let _ = window.set_focus();
             }
         }
-        "tilt_start" => {
-            // Emit the frontend event to start Tilt
-            app.emit("start-tilt", ()).unwrap();
-        }
-        "tilt_stop" => {
-            app.emit("stop-tilt", ()).unwrap();
-        }
-        "tilt_restart" => {
-            app.emit("restart-tilt", ()).unwrap();
+        "tilt_start" | "tilt_stop" | "tilt_restart" => {
+            let action = match id {
+                "tilt_start" => TiltAction::Start,
+                "tilt_stop" => TiltAction::Stop,
+                _ => TiltAction::Restart,
+            };
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                run_tilt_action(&app_handle, action).await;
+            });
         }
         "tilt_web_ui" => {
-            // Open Tilt Web UI in browser
-            // let url = "http://localhost:10350";
+            // Every environment serves its web UI on its own port
             let app_handle = app.clone();
             tauri::async_runtime::spawn(async move {
                 let state = app_handle.state::<RwLock<TrayState>>();
                 let state = state.read().await.clone();
 
-                if let Some(tilt_state) = state.tilt_state {
-                    if let Some(web_ui_url) = tilt_state.web_ui_url {
-                        println!("{}", web_ui_url);
-                        if !web_ui_url.is_empty() {
-                            let _ = tauri_plugin_opener::open_url(&web_ui_url, None::<&str>);
-                        }
-                    }
+                let url = state
+                    .current_project
+                    .as_ref()
+                    .zip(state.current_env.as_deref())
+                    .and_then(|(project, env)| web_ui_url(&project.project.workspace_path, env))
+                    .or_else(|| state.tilt_state.and_then(|t| t.web_ui_url))
+                    .filter(|url| !url.is_empty());
+                if let Some(url) = url {
+                    let _ = tauri_plugin_opener::open_url(&url, None::<&str>);
                 }
             });
         }
@@ -178,14 +339,332 @@ fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
         _ => {
             // Handle service toggle and editor commands
             if id.starts_with("service_toggle_") {
-                let service_name = id.strip_prefix("service_toggle_").unwrap();
-                app.emit("toggle-service", service_name).unwrap();
+                let service_name = id.strip_prefix("service_toggle_").unwrap().to_string();
+                let app_handle = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = toggle_service(&app_handle, &service_name).await {
+                        eprintln!("Failed to toggle service {}: {}", service_name, e);
+                    }
+                });
             } else if id.starts_with("service_editor_") {
                 let service_name = id.strip_prefix("service_editor_").unwrap();
                 app.emit("open-service-in-editor", service_name).unwrap();
+            } else if let Some(env) = id.strip_prefix("env_select_") {
+                let env = env.to_string();
+                let app_handle = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    let workspace = {
+                        let state = app_handle.state::<RwLock<TrayState>>();
+                        let state = state.read().await;
+                        state
+                            .current_project
+                            .as_ref()
+                            .map(|p| p.project.workspace_path.clone())
+                    };
+                    if let Some(workspace) = workspace {
+                        if let Err(e) =
+                            switch_tray_target(&app_handle, &workspace, Some(&env)).await
+                        {
+                            eprintln!("Failed to switch to environment {}: {}", env, e);
+                        }
+                    }
+                });
+            } else if let Some(path) = id.strip_prefix("recent_project_") {
+                let path = path.to_string();
+                let app_handle = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = switch_tray_target(&app_handle, &path, None).await {
+                        eprintln!("Failed to open project {}: {}", path, e);
+                    }
+                });
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum TiltAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+/// Outcome of a Tilt action taken from the tray, sent to the frontend
+#[derive(Debug, Clone, Serialize)]
+struct TiltActionResult {
+    workspace_path: String,
+    env: String,
+    action: TiltAction,
+    /// Status of the environment after the action
+    status: String,
+    error: Option<String>,
+}
+
+/// Start, stop or restart the tray's environment and tell any open window.
+/// Works the same whether or not the main window is open.
+async fn run_tilt_action(app: &AppHandle, action: TiltAction) {
+    let (workspace, env) = {
+        let state = app.state::<RwLock<TrayState>>();
+        let state = state.read().await;
+        match (&state.current_project, &state.current_env) {
+            (Some(project), Some(env)) => (project.project.workspace_path.clone(), env.clone()),
+            _ => return,
+        }
+    };
+    let tilt_path = load_state(app).preferences.tilt_path;
+
+    // The files on disk may be newer than the project the tray was given
+    let prepare = || -> anyhow::Result<TiltLaunch> {
+        let project = load_project_info(&workspace)?;
+        TiltLaunch::prepare(app, &project, &env)
+    };
+
+    let result: anyhow::Result<()> = match action {
+        TiltAction::Start => match prepare() {
+            Ok(launch) if launch.enabled_services.is_empty() => Err(anyhow::anyhow!(
+                "Add or enable at least one service to start Tilt"
+            )),
+            Ok(launch) => start_tilt(&workspace, &env, app, tilt_path.as_deref(), launch)
+                .await
+                .map(|_| ())
+                .map_err(Into::into),
+            Err(e) => Err(e),
+        },
+        TiltAction::Stop => stop_tilt(&workspace, &env, app, StopInitiator::Tray)
+            .await
+            .map_err(Into::into),
+        TiltAction::Restart => match prepare() {
+            Ok(launch) => restart_tilt(
+                &workspace,
+                &env,
+                app,
+                tilt_path.as_deref(),
+                launch,
+                StopInitiator::Tray,
+            )
+            .await
+            .map(|_| ())
+            .map_err(Into::into),
+            Err(e) => Err(e),
+        },
+    };
+
+    if let Err(e) = &result {
+        eprintln!("Tray failed to {:?} Tilt for {}: {}", action, env, e);
+    }
+
+    let status = read_state(&workspace, &env).tilt.status;
+    let _ = app.emit(
+        "tray-tilt-action",
+        TiltActionResult {
+            workspace_path: workspace,
+            env,
+            action,
+            status,
+            error: result.err().map(|e| e.to_string()),
+        },
+    );
+}
+
+/// Web UI of the environment's Tilt, on the port it was started with
+fn web_ui_url(workspace: &str, env: &str) -> Option<String> {
+    read_state(workspace, env)
+        .tilt
+        .port
+        .map(|port| format!("http://localhost:{}", port))
+}
+
+fn env_running(workspace: &str, env: &str) -> bool {
+    matches!(
+        read_state(workspace, env).tilt.status.as_str(),
+        "running" | "starting"
+    )
+}
+
+/// What the tray switched to, sent to the frontend
+#[derive(Debug, Clone, Serialize)]
+struct TraySelection {
+    project: Project,
+    env: String,
+}
+
+/// Show another environment or project in the tray and the frontend.
+/// Without `env` a running environment of the project is picked, or its first one.
+async fn switch_tray_target(
+    app: &AppHandle,
+    workspace: &str,
+    env: Option<&str>,
+) -> Result<(), String> {
+    let state_handle = app.state::<RwLock<TrayState>>();
+    let current = {
+        let state = state_handle.read().await;
+        state
+            .current_project
+            .as_ref()
+            .map(|p| p.project.workspace_path.clone())
+            .zip(state.current_env.clone())
+    };
+
+    let project = load_project_info(workspace).map_err(|e| e.to_string())?;
+    let env = match env {
+        Some(env) if project.environments.contains_key(env) => env.to_string(),
+        Some(env) => return Err(format!("Environment {} not found", env)),
+        None => {
+            let mut names: Vec<&String> = project.environments.keys().collect();
+            names.sort();
+            names
+                .iter()
+                .find(|name| env_running(workspace, name))
+                .or(names.first())
+                .map(|name| name.to_string())
+                .ok_or_else(|| format!("{} has no environments", project.project.name))?
+        }
+    };
+
+    if let Some((current_workspace, current_env)) = current {
+        let switching = current_workspace != workspace || current_env != env;
+        if switching
+            && env_running(&current_workspace, &current_env)
+            && confirm_stop(app, &current_env).await
+        {
+            stop_tilt(&current_workspace, &current_env, app, StopInitiator::Tray)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    add_recent_project(app, project.project.name.clone(), workspace.to_string())
+        .map_err(|e| e.to_string())?;
+
+    {
+        let running = env_running(workspace, &env);
+        let mut state = state_handle.write().await;
+        *state = TrayState {
+            current_project: Some(project.clone()),
+            current_env: Some(env.clone()),
+            tilt_state: Some(TiltState {
+                status: if running { "running" } else { "stopped" }.to_string(),
+                is_running: running,
+                web_ui_url: web_ui_url(workspace, &env),
+            }),
+            // Resource health is unknown until the next poll
+            health: if running {
+                TrayHealth::Starting
+            } else {
+                TrayHealth::Idle
+            },
+        };
+        refresh_tray(app, &state).map_err(|e| e.to_string())?;
+    }
+
+    app.emit("tray-selection-changed", TraySelection { project, env })
+        .map_err(|e| e.to_string())
+}
+
+/// Ask whether the running environment should be stopped before switching away
+async fn confirm_stop(app: &AppHandle, env: &str) -> bool {
+    let dialog = app
+        .dialog()
+        .message(format!(
+            "Tilt is running for {}. Stop it before switching?",
+            env
+        ))
+        .title("Switch environment")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Stop".to_string(),
+            "Keep running".to_string(),
+        ));
+
+    tauri::async_runtime::spawn_blocking(move || dialog.blocking_show())
+        .await
+        .unwrap_or(false)
+}
+
+/// Flip a service of the tray's environment, regenerate its Tiltfiles and
+/// apply the change to a running Tilt, without going through the webview
+async fn toggle_service(app: &AppHandle, service_name: &str) -> Result<(), String> {
+    let state_handle = app.state::<RwLock<TrayState>>();
+    let (workspace, env) = {
+        let state = state_handle.read().await;
+        match (&state.current_project, &state.current_env) {
+            (Some(project), Some(env)) => (project.project.workspace_path.clone(), env.clone()),
+            _ => return Ok(()),
+        }
+    };
+
+    // The files on disk may be newer than the project the tray was given
+    let project = load_project_info(&workspace).map_err(|e| e.to_string())?;
+    let enabled = !resolve_environment(&project, &env)
+        .map_err(|e| e.to_string())?
+        .services
+        .iter()
+        .find(|s| s.name == service_name)
+        .map(|s| s.enabled)
+        .ok_or_else(|| format!("Service {} not found in environment {}", service_name, env))?;
+
+    let tilt_path = load_state(app).preferences.tilt_path;
+    let running = env_running(&workspace, &env);
+    let port = read_state(&workspace, &env).tilt.port;
+
+    // Disable in Tilt before the service leaves the Tiltfile so its workloads
+    // are torn down. An enabled service is picked up when Tilt reloads the
+    // regenerated Tiltfile, and enabled in Tilt below in case it was disabled there.
+    let hot_applied = !running
+        || enabled
+        || set_resource_enabled(app, tilt_path.as_deref(), port, service_name, false)
+            .await
+            .is_ok();
+
+    let updated = match set_service_enabled(&workspace, &env, service_name, enabled) {
+        Ok(project) => project,
+        Err(e) => {
+            if running && !enabled && hot_applied {
+                let _ =
+                    set_resource_enabled(app, tilt_path.as_deref(), port, service_name, true).await;
             }
+            return Err(e.to_string());
+        }
+    };
+
+    // Tilt keeps a resource disabled across Tiltfile reloads. One it does not
+    // know yet fails here and arrives with the reload instead.
+    if running && enabled {
+        let _ = set_resource_enabled(app, tilt_path.as_deref(), port, service_name, true).await;
+    }
+
+    if !hot_applied {
+        let launch = TiltLaunch::prepare(app, &updated, &env).map_err(|e| e.to_string())?;
+        restart_tilt(
+            &workspace,
+            &env,
+            app,
+            tilt_path.as_deref(),
+            launch,
+            StopInitiator::Tray,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    {
+        let mut state = state_handle.write().await;
+        let shown = state
+            .current_project
+            .as_ref()
+            .is_some_and(|p| p.project.workspace_path == workspace)
+            && state.current_env.as_deref() == Some(env.as_str());
+
+        if shown {
+            state.current_project = Some(updated.clone());
+            refresh_tray(app, &state).map_err(|e| e.to_string())?;
         }
     }
+
+    // Lets an open window pick up the change
+    app.emit("project-updated", &updated)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -194,7 +673,7 @@ pub async fn update_tray_menu(
     project: Option<Project>,
     env: Option<String>,
     tilt_state: Option<TiltState>,
-) -> Result<(), String> {
+) -> Result<(), IpcError> {
     // let state = TrayState {
     //     current_project: project.clone(),
     //     current_env: env,
@@ -203,18 +682,26 @@ pub async fn update_tray_menu(
 
     let state_handle = app.state::<RwLock<TrayState>>();
     let mut state = state_handle.write().await;
+
+    // Health belongs to the environment, a different one starts over
+    let same_env = state.current_env == env
+        && state
+            .current_project
+            .as_ref()
+            .map(|p| &p.project.workspace_path)
+            == project.as_ref().map(|p| &p.project.workspace_path);
+    let health = if same_env {
+        state.health
+    } else {
+        TrayHealth::default()
+    };
+
     *state = TrayState {
         current_project: project,
         current_env: env,
         tilt_state,
+        health,
     };
 
-    let menu = build_menu(&app, &state).map_err(|e| e.to_string())?;
-
-    // Get the tray icon and update its menu
-    if let Some(tray) = app.tray_by_id("main") {
-        tray.set_menu(Some(menu)).map_err(|e| e.to_string())?;
-    }
-
-    Ok(())
+    refresh_tray(&app, &state).map_err(|e| IpcError::from(anyhow::Error::from(e)))
 }
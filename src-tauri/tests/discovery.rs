@@ -0,0 +1,105 @@
+use std::fs;
+
+use tempfile::TempDir;
+use tilt_orchestrator_lib::model::Service;
+use tilt_orchestrator_lib::project::discovery::discover_services;
+
+/// Write `files` into `repos/<service>` and discover the services
+fn discover(files: &[(&str, &str, &str)]) -> Vec<Service> {
+    let dir = TempDir::new().unwrap();
+    for (service, file, content) in files {
+        let path = dir.path().join("repos").join(service).join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    discover_services(dir.path(), "repos").unwrap()
+}
+
+fn only(services: &[Service]) -> &Service {
+    assert_eq!(services.len(), 1);
+    &services[0]
+}
+
+#[test]
+fn the_first_exposed_port_is_used() {
+    let services = discover(&[("api", "Dockerfile", "FROM rust\nEXPOSE 80/tcp 443\n")]);
+
+    let api = only(&services);
+    assert_eq!(api.port, 80);
+    assert_eq!(api.docker.as_ref().unwrap().dockerfile, "Dockerfile");
+}
+
+#[test]
+fn ports_come_from_the_final_stage() {
+    let dockerfile = "\
+FROM node:20 AS build
+EXPOSE 9229
+RUN npm run build
+
+FROM nginx
+EXPOSE ${PORT}
+expose 8081/udp
+";
+    let services = discover(&[("web", "Dockerfile", dockerfile)]);
+
+    assert_eq!(only(&services).port, 8081);
+}
+
+#[test]
+fn node_services_default_to_port_3000() {
+    let services = discover(&[("web", "package.json", "{}"), ("api", "Cargo.toml", "")]);
+
+    let ports: Vec<_> = services.iter().map(|s| (s.name.as_str(), s.port)).collect();
+    assert_eq!(ports, [("api", 8080), ("web", 3000)]);
+}
+
+#[test]
+fn charts_and_kustomizations_are_detected() {
+    let services = discover(&[
+        ("api", "chart/Chart.yaml", "name: api"),
+        ("web", "k8s/kustomization.yaml", "resources: []"),
+        ("worker", "k8s/deployment.yaml", "kind: Deployment"),
+        ("docs", "README.md", "nothing to deploy"),
+    ]);
+
+    let names: Vec<_> = services.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["api", "web", "worker"]);
+    assert_eq!(services[0].helm.as_ref().unwrap().chart, "./chart");
+    assert_eq!(services[1].kustomize.as_ref().unwrap().path, "k8s");
+    // The kustomization already covers its directory's manifests
+    assert!(services[1].k8s.is_none());
+    assert_eq!(services[2].k8s.as_ref().unwrap().manifests, "k8s");
+}
+
+#[test]
+fn the_origin_remote_becomes_the_repo() {
+    let config = "\
+[core]
+\tbare = false
+[remote \"upstream\"]
+\turl = https://example.com/upstream/api.git
+[remote \"origin\"]
+\turl = https://example.com/me/api.git
+\tfetch = +refs/heads/*:refs/remotes/origin/*
+";
+    let services = discover(&[
+        ("api", "Dockerfile", "FROM rust"),
+        ("api", ".git/config", config),
+        ("api", ".git/HEAD", "ref: refs/heads/main\n"),
+    ]);
+
+    let repo = only(&services).repo.as_ref().unwrap();
+    assert_eq!(repo.url, "https://example.com/me/api.git");
+    assert_eq!(repo.branch.as_deref(), Some("main"));
+}
+
+#[test]
+fn a_repo_without_an_origin_has_no_remote() {
+    let services = discover(&[
+        ("api", "Dockerfile", "FROM rust"),
+        ("api", ".git/config", "[core]\n\tbare = false\n"),
+        ("api", ".git/HEAD", "ref: refs/heads/main\n"),
+    ]);
+
+    assert!(only(&services).repo.is_none());
+}
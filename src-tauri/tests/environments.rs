@@ -0,0 +1,41 @@
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+use tilt_orchestrator_lib::project::create_project;
+use tilt_orchestrator_lib::project::environments::{
+    create_environment, delete_environment, rename_environment,
+};
+
+#[test]
+fn runtime_files_and_directories_follow_the_environment() {
+    let dir = TempDir::new().unwrap();
+    let data_dir = dir.path().join("data");
+    let project = create_project("demo", dir.path().join("ws").to_str().unwrap(), None).unwrap();
+    let root = project.project.workspace_path;
+    create_environment(&root, "dev_eu").unwrap();
+
+    let tooling = Path::new(&root).join(".tooling");
+    fs::create_dir_all(&tooling).unwrap();
+    for file in ["dev_runtime.json", "dev_tilt.log", "dev_eu_runtime.json"] {
+        fs::write(tooling.join(file), "{}").unwrap();
+    }
+    fs::create_dir_all(tooling.join("cache/dev")).unwrap();
+    fs::write(tooling.join("cache/dev/state"), "x").unwrap();
+
+    rename_environment(&data_dir, &root, "dev", "local").unwrap();
+
+    assert!(tooling.join("local_runtime.json").exists());
+    assert!(tooling.join("local_tilt.log").exists());
+    assert!(tooling.join("cache/local/state").exists());
+    assert!(!tooling.join("cache/dev").exists());
+    assert!(tooling.join("dev_eu_runtime.json").exists());
+
+    delete_environment(&data_dir, &root, "local").unwrap();
+
+    assert!(!tooling.join("local_runtime.json").exists());
+    assert!(!tooling.join("local_tilt.log").exists());
+    assert!(!tooling.join("cache/local").exists());
+    assert!(tooling.join("cache").exists());
+    assert!(tooling.join("dev_eu_runtime.json").exists());
+}
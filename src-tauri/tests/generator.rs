@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+use tilt_orchestrator_lib::generator::generate_tiltfiles;
+use tilt_orchestrator_lib::model::{Helm, K8s, Project, Service, ServiceOverride};
+use tilt_orchestrator_lib::project::create_project;
+
+fn service(name: &str) -> Service {
+    Service {
+        name: name.to_string(),
+        path: None,
+        port: 8080,
+        enabled: true,
+        repo: None,
+        docker: None,
+        k8s: None,
+        env: None,
+        depends_on: None,
+        helm: None,
+        kustomize: None,
+        replicas: None,
+        secrets: None,
+    }
+}
+
+/// `dev` defines the services, `staging` and `prod` extend it and override
+/// the replicas of every service
+fn project(dir: &TempDir, services: Vec<Service>) -> Project {
+    let mut project = create_project("demo", dir.path().to_str().unwrap(), None).unwrap();
+    let root = project.project.workspace_path.clone();
+
+    for svc in &services {
+        fs::create_dir_all(Path::new(&root).join("repos").join(&svc.name)).unwrap();
+    }
+    let replicas = |count| {
+        let overrides = services
+            .iter()
+            .map(|svc| {
+                let patch = ServiceOverride {
+                    replicas: Some(count),
+                    ..Default::default()
+                };
+                (svc.name.clone(), patch)
+            })
+            .collect::<HashMap<_, _>>();
+        Some(overrides)
+    };
+
+    for (env, overrides) in [("staging", replicas(3)), ("prod", replicas(5))] {
+        let environment = project.environments.get_mut(env).unwrap();
+        environment.extends = Some("dev".to_string());
+        environment.overrides = overrides;
+    }
+    project.environments.get_mut("dev").unwrap().services = services;
+    project
+}
+
+fn service_tiltfile(project: &Project, env: &str, service: &str) -> String {
+    generate_tiltfiles(project, env).unwrap();
+    let path = Path::new(&project.project.workspace_path)
+        .join("tilt")
+        .join(env)
+        .join("services")
+        .join(format!("{}.tilt.py", service));
+    fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn each_environment_gets_its_own_replicas() {
+    let dir = TempDir::new().unwrap();
+    let api = Service {
+        k8s: Some(K8s {
+            manifests: "k8s".to_string(),
+        }),
+        ..service("api")
+    };
+    let project = project(&dir, vec![api]);
+
+    let dev = service_tiltfile(&project, "dev", "api");
+    let staging = service_tiltfile(&project, "staging", "api");
+    let prod = service_tiltfile(&project, "prod", "api");
+
+    assert!(!dev.contains("_with_replicas"));
+    assert!(staging.contains(r#"_with_replicas("\n---\n".join("#));
+    assert!(staging.contains(r#""api", 3))"#));
+    assert!(prod.contains(r#""api", 5))"#));
+
+    // The manifests every environment shares keep their own count
+    let deployment = fs::read_to_string(
+        Path::new(&project.project.workspace_path).join("repos/api/k8s/api-deployment.yaml"),
+    )
+    .unwrap();
+    assert!(deployment.contains("  replicas: 1\n"));
+}
+
+#[test]
+fn helm_services_get_their_replicas_too() {
+    let dir = TempDir::new().unwrap();
+    let web = Service {
+        helm: Some(Helm {
+            chart: "charts/web".to_string(),
+            values: None,
+            namespace: None,
+        }),
+        ..service("web")
+    };
+    let project = project(&dir, vec![web]);
+
+    let staging = service_tiltfile(&project, "staging", "web");
+
+    assert!(staging.contains("k8s_yaml(_with_replicas(helm(\n        \"charts/web\","));
+    assert!(staging.contains(r#""web", 3))"#));
+}
+
+#[test]
+fn charts_in_the_service_directory_resolve_through_the_services_path() {
+    let dir = TempDir::new().unwrap();
+    let helm = |chart: &str| {
+        Some(Helm {
+            chart: chart.to_string(),
+            values: None,
+            namespace: None,
+        })
+    };
+    let services = vec![
+        Service {
+            helm: helm("."),
+            ..service("api")
+        },
+        Service {
+            helm: helm("./chart"),
+            ..service("web")
+        },
+        Service {
+            helm: helm("bitnami/redis"),
+            ..service("redis")
+        },
+    ];
+    let mut project = project(&dir, services);
+    project.project.services_path = Some("services".to_string());
+    for name in ["api", "web", "redis"] {
+        fs::create_dir_all(
+            Path::new(&project.project.workspace_path)
+                .join("services")
+                .join(name),
+        )
+        .unwrap();
+    }
+
+    assert!(service_tiltfile(&project, "dev", "api").contains(r#""../../services/api","#));
+    assert!(service_tiltfile(&project, "dev", "web").contains(r#""../../services/web/chart","#));
+    assert!(service_tiltfile(&project, "dev", "redis").contains(r#""bitnami/redis","#));
+}
+
+#[test]
+fn env_changes_reach_the_generated_files() {
+    let dir = TempDir::new().unwrap();
+    let api = Service {
+        k8s: Some(K8s {
+            manifests: "k8s".to_string(),
+        }),
+        env: Some(HashMap::from([("MODE".to_string(), "debug".to_string())])),
+        ..service("api")
+    };
+    let mut project = project(&dir, vec![api]);
+    generate_tiltfiles(&project, "dev").unwrap();
+
+    let service_dir = Path::new(&project.project.workspace_path).join("repos/api");
+    let deployment = service_dir.join("k8s/api-deployment.yaml");
+    // Edits outside the ConfigMap data are kept
+    let edited = fs::read_to_string(&deployment)
+        .unwrap()
+        .replace("memory: \"256Mi\"", "memory: \"1Gi\"");
+    fs::write(&deployment, edited).unwrap();
+
+    project.environments.get_mut("dev").unwrap().services[0].env = Some(HashMap::from([
+        ("MODE".to_string(), "release".to_string()),
+        ("LEVEL".to_string(), "info".to_string()),
+    ]));
+    generate_tiltfiles(&project, "dev").unwrap();
+
+    let dotenv = fs::read_to_string(service_dir.join(".env.dev")).unwrap();
+    assert_eq!(dotenv, "LEVEL=info\nMODE=release");
+    let yaml = fs::read_to_string(&deployment).unwrap();
+    assert!(yaml.contains("data:\n  LEVEL: \"info\"\n  MODE: \"release\"\n---\n"));
+    assert!(!yaml.contains("debug"));
+    assert!(yaml.contains("memory: \"1Gi\""));
+}
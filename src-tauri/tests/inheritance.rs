@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use tilt_orchestrator_lib::inheritance::resolve_environment;
+use tilt_orchestrator_lib::model::{
+    Environment, Project, ProjectInfo, Service, ServiceOverride, Tilt, TiltMode,
+};
+
+fn project(overrides: ServiceOverride) -> Project {
+    let api = Service {
+        name: "api".to_string(),
+        path: None,
+        port: 8080,
+        enabled: true,
+        repo: None,
+        docker: None,
+        k8s: None,
+        env: None,
+        depends_on: None,
+        helm: None,
+        kustomize: None,
+        replicas: None,
+        secrets: None,
+    };
+    let environment = |services, extends: Option<&str>, overrides| Environment {
+        shared_env: HashMap::new(),
+        services,
+        extends: extends.map(str::to_string),
+        overrides,
+        secrets: None,
+    };
+
+    Project {
+        project: ProjectInfo {
+            id: None,
+            name: "demo".to_string(),
+            workspace_path: "/tmp/demo".to_string(),
+            services_path: None,
+            tilt: Tilt {
+                mode: TiltMode::Root,
+            },
+        },
+        environments: HashMap::from([
+            ("dev".to_string(), environment(vec![api], None, None)),
+            (
+                "staging".to_string(),
+                environment(
+                    Vec::new(),
+                    Some("dev"),
+                    Some(HashMap::from([("api".to_string(), overrides)])),
+                ),
+            ),
+        ]),
+    }
+}
+
+#[test]
+fn overriding_the_branch_of_a_service_without_a_repo_is_invalid() {
+    let project = project(ServiceOverride {
+        branch: Some("release".to_string()),
+        ..Default::default()
+    });
+
+    let error = resolve_environment(&project, "staging").unwrap_err();
+    assert!(error.to_string().contains("which has no repo"));
+}
+
+#[test]
+fn overriding_helm_values_of_a_service_without_helm_is_invalid() {
+    let project = project(ServiceOverride {
+        helm_values: Some(HashMap::from([(
+            "replicaCount".to_string(),
+            serde_json::json!(2),
+        )])),
+        ..Default::default()
+    });
+
+    let error = resolve_environment(&project, "staging").unwrap_err();
+    assert!(error.to_string().contains("which has no Helm chart"));
+}
+
+#[test]
+fn other_overrides_still_apply() {
+    let project = project(ServiceOverride {
+        port: Some(9090),
+        ..Default::default()
+    });
+
+    let staging = resolve_environment(&project, "staging").unwrap();
+    assert_eq!(staging.services[0].port, 9090);
+}
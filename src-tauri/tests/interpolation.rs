@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use tilt_orchestrator_lib::interpolation::{interpolate_env, InterpolationContext};
+use tilt_orchestrator_lib::model::Service;
+
+fn service(name: &str, port: u16) -> Service {
+    Service {
+        name: name.to_string(),
+        path: None,
+        port,
+        enabled: true,
+        repo: None,
+        docker: None,
+        k8s: None,
+        env: None,
+        depends_on: None,
+        helm: None,
+        kustomize: None,
+        replicas: None,
+        secrets: None,
+    }
+}
+
+fn vars(entries: &[(&str, &str)]) -> HashMap<String, String> {
+    entries
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// Interpolate `entries` for the `api` service of `dev`, next to a `db` service,
+/// with `TOKEN` stored as a secret
+fn interpolate(entries: &[(&str, &str)]) -> Result<HashMap<String, String>, Vec<String>> {
+    let services = [service("api", 8080), service("db", 5432)];
+    let secret_keys = ["TOKEN".to_string()];
+    let ctx = InterpolationContext {
+        env_name: "dev",
+        service: &services[0],
+        services: &services,
+        secret_keys: &secret_keys,
+    };
+
+    interpolate_env(&vars(entries), &ctx)
+        .map_err(|errors| errors.iter().map(ToString::to_string).collect())
+}
+
+#[test]
+fn references_resolve_through_other_keys() {
+    let env = interpolate(&[
+        ("HOST", "localhost"),
+        ("URL", "http://${HOST}:${service.port}/${env.name}"),
+    ])
+    .unwrap();
+
+    assert_eq!(env["URL"], "http://localhost:8080/dev");
+}
+
+#[test]
+fn cycles_are_reported_with_their_path() {
+    let errors = interpolate(&[("A", "${B}"), ("B", "${A}"), ("C", "fine")]).unwrap_err();
+
+    assert_eq!(
+        errors,
+        [
+            "api.A: Reference cycle: A -> B -> A",
+            "api.B: Reference cycle: B -> A -> B",
+        ]
+    );
+}
+
+#[test]
+fn defaults_apply_to_undefined_and_empty_values() {
+    let env = interpolate(&[
+        ("EMPTY", ""),
+        ("SET", "value"),
+        ("FROM_UNDEFINED", "${MISSING:-fallback}"),
+        ("FROM_EMPTY", "${EMPTY:-fallback}"),
+        ("FROM_SET", "${SET:-fallback}"),
+        ("NESTED", "${MISSING:-${SET}}"),
+    ])
+    .unwrap();
+
+    assert_eq!(env["FROM_UNDEFINED"], "fallback");
+    assert_eq!(env["FROM_EMPTY"], "fallback");
+    assert_eq!(env["FROM_SET"], "value");
+    assert_eq!(env["NESTED"], "value");
+}
+
+#[test]
+fn double_dollar_keeps_a_literal_reference() {
+    let env = interpolate(&[("HOST", "localhost"), ("TEMPLATE", "$${HOST} is ${HOST}")]).unwrap();
+
+    assert_eq!(env["TEMPLATE"], "${HOST} is localhost");
+}
+
+#[test]
+fn other_services_ports_can_be_referenced() {
+    let env = interpolate(&[("DB_URL", "postgres://db:${services.db.port}")]).unwrap();
+    assert_eq!(env["DB_URL"], "postgres://db:5432");
+
+    let errors = interpolate(&[("CACHE", "${services.cache.port}")]).unwrap_err();
+    assert_eq!(
+        errors,
+        ["api.CACHE: Undefined variable ${services.cache.port}"]
+    );
+}
+
+#[test]
+fn secrets_cannot_be_referenced() {
+    let errors = interpolate(&[("AUTH", "Bearer ${TOKEN}")]).unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("${TOKEN} refers to a secret"));
+}
@@ -0,0 +1,82 @@
+use std::fs;
+use std::time::Duration;
+
+use tempfile::TempDir;
+use tilt_orchestrator_lib::git::{clone_repos_in_job, RepoCheckout};
+use tilt_orchestrator_lib::jobs::{
+    cancel_job, get_job, job_logs, spawn_job, Job, JobKind, JobStatus, RepoStatus,
+};
+use tilt_orchestrator_lib::process::{ScriptStep, ScriptedRunner};
+
+/// Poll until `done` holds for the job, jobs report through a background task
+async fn wait_for(id: &str, done: impl Fn(&Job) -> bool) -> Job {
+    for _ in 0..200 {
+        let job = get_job(id).unwrap();
+        if done(&job) {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("job {} did not get there: {:?}", id, get_job(id));
+}
+
+#[tokio::test]
+async fn cancelling_a_clone_kills_git_and_removes_the_partial_clone() {
+    let dir = TempDir::new().unwrap();
+    let target = dir.path().join("api");
+    let checkouts = vec![
+        RepoCheckout {
+            service: "api".to_string(),
+            url: "https://example.com/api.git".to_string(),
+            branch: None,
+            dir: target.clone(),
+        },
+        RepoCheckout {
+            service: "web".to_string(),
+            url: "https://example.com/web.git".to_string(),
+            branch: None,
+            dir: dir.path().join("web"),
+        },
+    ];
+
+    // Git never finishes on its own
+    let runner = ScriptedRunner::new();
+    runner.on_spawn(
+        "git",
+        vec![ScriptStep::Stderr("Cloning into 'api'...".to_string())],
+    );
+    let job_runner = runner.clone();
+    let job = spawn_job(
+        JobKind::CloneRepo,
+        "api",
+        None,
+        None,
+        move |job| async move {
+            clone_repos_in_job(&job_runner, &checkouts, &job).await?;
+            Ok(())
+        },
+    );
+
+    wait_for(&job.id, |_| {
+        job_logs(&job.id)
+            .unwrap()
+            .contains(&"Cloning into 'api'...".to_string())
+    })
+    .await;
+    fs::create_dir_all(target.join(".git")).unwrap();
+
+    cancel_job(&job.id).unwrap();
+    let job = wait_for(&job.id, |job| job.status != JobStatus::Running).await;
+
+    assert_eq!(job.status, JobStatus::Cancelled);
+    let statuses: Vec<_> = job.repos.iter().map(|repo| repo.status).collect();
+    assert_eq!(statuses, [RepoStatus::Cancelled, RepoStatus::Cancelled]);
+    assert!(!target.exists());
+
+    let programs: Vec<_> = runner
+        .invocations()
+        .into_iter()
+        .map(|run| run.program)
+        .collect();
+    assert_eq!(programs, ["git", "kill"]);
+}
@@ -0,0 +1,131 @@
+use tilt_orchestrator_lib::log_records::{detect_level, parse_record, LogFilter, LogLevel};
+
+#[test]
+fn levels_are_read_from_json_logs() {
+    assert_eq!(
+        detect_level(r#"{"level":"warn","msg":"slow query"}"#),
+        Some(LogLevel::Warn)
+    );
+    assert_eq!(
+        detect_level(r#"{"severity":"ERROR","message":"boom"}"#),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(detect_level(r#"{"lvl":"dbg"}"#), Some(LogLevel::Debug));
+    // A JSON line without a level is not guessed from its text
+    assert_eq!(detect_level(r#"{"msg":"ERROR in handler"}"#), None);
+}
+
+#[test]
+fn levels_are_read_from_plain_lines() {
+    assert_eq!(
+        detect_level("ERROR could not connect"),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(
+        detect_level("[warn] disk almost full"),
+        Some(LogLevel::Warn)
+    );
+    assert_eq!(detect_level("info: server started"), Some(LogLevel::Info));
+    assert_eq!(
+        detect_level(r#"time=12:00 level="debug" msg=hi"#),
+        Some(LogLevel::Debug)
+    );
+    assert_eq!(
+        detect_level("E1018 12:00:00.000000 1 main.go:10] failed"),
+        Some(LogLevel::Error)
+    );
+    // Level words in running text are not markers
+    assert_eq!(detect_level("no error occurred"), None);
+    assert_eq!(detect_level("Listening on :8080"), None);
+}
+
+#[test]
+fn records_keep_their_header_and_resource() {
+    let record = parse_record("2026-10-18T12:00:00.000+00:00 stderr api │ ERROR boom");
+
+    assert_eq!(
+        record.timestamp.as_deref(),
+        Some("2026-10-18T12:00:00.000+00:00")
+    );
+    assert_eq!(record.resource.as_deref(), Some("api"));
+    assert_eq!(record.level, Some(LogLevel::Error));
+    assert_eq!(record.line, "api │ ERROR boom");
+    assert_eq!(record.message, "ERROR boom");
+}
+
+#[test]
+fn filters_combine_their_conditions() {
+    let lines = [
+        "2026-10-18T12:00:00.000+00:00 stdout api │ INFO started",
+        "2026-10-18T12:05:00.000+00:00 stderr api │ ERROR Connection refused",
+        "2026-10-18T12:05:00.000+00:00 stderr web │ ERROR connection refused",
+        "2026-10-18T12:10:00.000+00:00 stdout api │ retrying",
+    ];
+    let matching = |filter: LogFilter| {
+        let filter = filter.compile().unwrap();
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| filter.matches(&parse_record(line)))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(matching(LogFilter::default()), [0, 1, 2, 3]);
+    assert_eq!(
+        matching(LogFilter {
+            resources: Some(vec!["api".to_string()]),
+            ..Default::default()
+        }),
+        [0, 1, 3]
+    );
+    // Lines without a level are dropped by a level filter
+    assert_eq!(
+        matching(LogFilter {
+            level: Some(LogLevel::Warn),
+            ..Default::default()
+        }),
+        [1, 2]
+    );
+    assert_eq!(
+        matching(LogFilter {
+            since: Some("2026-10-18T12:01:00+00:00".to_string()),
+            until: Some("2026-10-18T12:06:00+00:00".to_string()),
+            ..Default::default()
+        }),
+        [1, 2]
+    );
+    assert_eq!(
+        matching(LogFilter {
+            resources: Some(vec!["api".to_string()]),
+            text: Some("connection".to_string()),
+            ..Default::default()
+        }),
+        [1]
+    );
+}
+
+#[test]
+fn invalid_filters_are_rejected() {
+    let pattern = LogFilter {
+        pattern: Some("(unclosed".to_string()),
+        ..Default::default()
+    };
+    assert!(pattern
+        .compile()
+        .err()
+        .unwrap()
+        .to_string()
+        .contains("Invalid log pattern"));
+
+    let since = LogFilter {
+        since: Some("yesterday".to_string()),
+        ..Default::default()
+    };
+    assert!(since
+        .compile()
+        .err()
+        .unwrap()
+        .to_string()
+        .contains("Invalid since time"));
+}
@@ -0,0 +1,100 @@
+use std::fs;
+
+use tempfile::TempDir;
+use tilt_orchestrator_lib::log_records::LogFilter;
+use tilt_orchestrator_lib::log_search::{search_logs, LogMatch, SearchTarget};
+use tilt_orchestrator_lib::redaction::Redactor;
+
+/// An older archived session and the live log of the current one
+fn workspace() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let tooling = dir.path().join(".tooling");
+    fs::create_dir_all(&tooling).unwrap();
+    fs::write(
+        tooling.join("dev_tilt.20261017_120000_000.001.log"),
+        "api │ GET /a.c 200\napi │ GET /abc 200\n",
+    )
+    .unwrap();
+    fs::write(
+        tooling.join("dev_tilt.log"),
+        "api │ starting\napi │ GET /a.c 500\napi │ token=hunter22\napi │ done\n",
+    )
+    .unwrap();
+    dir
+}
+
+fn search(dir: &TempDir, filter: LogFilter, context: usize) -> Vec<LogMatch> {
+    let targets = [SearchTarget {
+        env: "dev".to_string(),
+        current_session: Some("20261018_120000_000".to_string()),
+        redactor: Redactor::new(["hunter22".to_string()], &[]).unwrap(),
+    }];
+    let mut matches = Vec::new();
+    let total = search_logs(
+        dir.path().to_str().unwrap(),
+        &targets,
+        &filter.compile().unwrap(),
+        context,
+        1,
+        |batch| matches.extend(batch),
+    )
+    .unwrap();
+
+    assert_eq!(total, matches.len());
+    matches
+}
+
+#[test]
+fn text_is_matched_literally() {
+    let dir = workspace();
+    let matches = search(
+        &dir,
+        LogFilter {
+            text: Some("/A.C".to_string()),
+            ..Default::default()
+        },
+        0,
+    );
+
+    // Newest session first, and `.` is not a wildcard
+    let found: Vec<_> = matches.iter().map(|m| m.line.as_str()).collect();
+    assert_eq!(found, ["api │ GET /a.c 500", "api │ GET /a.c 200"]);
+    assert_eq!(matches[0].session, "20261018_120000_000");
+    assert_eq!(matches[0].line_number, 2);
+    assert_eq!(matches[1].session, "20261017_120000_000");
+}
+
+#[test]
+fn patterns_are_matched_as_regex() {
+    let dir = workspace();
+    let matches = search(
+        &dir,
+        LogFilter {
+            pattern: Some(r"/a.c 200$".to_string()),
+            ..Default::default()
+        },
+        0,
+    );
+
+    let found: Vec<_> = matches.iter().map(|m| m.line.as_str()).collect();
+    assert_eq!(found, ["api │ GET /a.c 200", "api │ GET /abc 200"]);
+}
+
+#[test]
+fn matches_carry_redacted_context() {
+    let dir = workspace();
+    let matches = search(
+        &dir,
+        LogFilter {
+            text: Some("500".to_string()),
+            ..Default::default()
+        },
+        1,
+    );
+
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].before, ["api │ starting"]);
+    assert_eq!(matches[0].after.len(), 1);
+    assert!(!matches[0].after[0].contains("hunter22"));
+    assert_eq!(matches[0].resource.as_deref(), Some("api"));
+}
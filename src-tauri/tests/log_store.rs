@@ -0,0 +1,174 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tempfile::TempDir;
+use tilt_orchestrator_lib::log_records::{record_header, LogStream};
+use tilt_orchestrator_lib::log_store::{
+    archive_live_log, log_path, prune_sessions, session_files, LogSource, LogWriter,
+};
+use tilt_orchestrator_lib::LogRetention;
+
+fn workspace(dir: &TempDir) -> &str {
+    dir.path().to_str().unwrap()
+}
+
+fn archive(dir: &TempDir, session: &str, part: usize) -> PathBuf {
+    dir.path()
+        .join(".tooling")
+        .join(format!("dev_tilt.{}.{:03}.log", session, part))
+}
+
+fn lines(path: &Path) -> usize {
+    fs::read_to_string(path).unwrap().lines().count()
+}
+
+#[test]
+fn the_live_log_rotates_once_it_reaches_the_limit() {
+    let dir = TempDir::new().unwrap();
+    let ws = workspace(&dir);
+    let line = "api │ listening";
+    let line_bytes = (record_header(LogStream::Stdout).len() + line.len() + 1) as u64;
+    let retention = LogRetention {
+        max_file_bytes: 3 * line_bytes,
+        ..Default::default()
+    };
+    let session = "20261018_120000_000";
+    let mut writer = LogWriter::create(ws, "dev", session, &retention).unwrap();
+
+    writer.write_line(LogStream::Stdout, line).unwrap();
+    writer.write_line(LogStream::Stdout, line).unwrap();
+    assert!(!archive(&dir, session, 1).exists());
+    assert_eq!(lines(&log_path(ws, "dev")), 2);
+
+    // The line reaching the limit still goes in the part it fills
+    writer.write_line(LogStream::Stdout, line).unwrap();
+    assert_eq!(lines(&archive(&dir, session, 1)), 3);
+    assert_eq!(lines(&log_path(ws, "dev")), 0);
+
+    for _ in 0..4 {
+        writer.write_line(LogStream::Stdout, line).unwrap();
+    }
+    assert_eq!(lines(&archive(&dir, session, 2)), 3);
+    assert_eq!(lines(&log_path(ws, "dev")), 1);
+
+    assert_eq!(
+        session_files(ws, "dev", session, Some(session)).unwrap(),
+        [
+            archive(&dir, session, 1),
+            archive(&dir, session, 2),
+            log_path(ws, "dev"),
+        ]
+    );
+
+    // Archiving the live log at the next start continues the numbering
+    archive_live_log(ws, "dev", Some(session)).unwrap();
+    assert_eq!(lines(&archive(&dir, session, 3)), 1);
+    assert!(!log_path(ws, "dev").exists());
+}
+
+#[test]
+fn pruning_keeps_the_newest_sessions_and_the_current_one() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir_all(dir.path().join(".tooling")).unwrap();
+    let sessions = [
+        "20261014_120000_000",
+        "20261015_120000_000",
+        "20261016_120000_000",
+        "20261017_120000_000",
+    ];
+    for session in sessions {
+        fs::write(archive(&dir, session, 1), "line\n").unwrap();
+        fs::write(archive(&dir, session, 2), "line\n").unwrap();
+    }
+    let retention = LogRetention {
+        max_sessions: 2,
+        max_age_days: None,
+        ..Default::default()
+    };
+
+    prune_sessions(workspace(&dir), "dev", &retention, Some(sessions[0])).unwrap();
+
+    let kept: Vec<bool> = sessions
+        .iter()
+        .map(|session| archive(&dir, session, 1).exists() && archive(&dir, session, 2).exists())
+        .collect();
+    assert_eq!(kept, [true, false, true, true]);
+}
+
+#[test]
+fn pruning_drops_sessions_past_the_age_limit() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir_all(dir.path().join(".tooling")).unwrap();
+    let (old, recent) = ("20260101_120000_000", "20261017_120000_000");
+    for session in [old, recent] {
+        fs::write(archive(&dir, session, 1), "line\n").unwrap();
+    }
+    let month_ago = SystemTime::now() - Duration::from_secs(30 * 24 * 60 * 60);
+    File::options()
+        .write(true)
+        .open(archive(&dir, old, 1))
+        .unwrap()
+        .set_modified(month_ago)
+        .unwrap();
+    let retention = LogRetention {
+        max_sessions: 10,
+        max_age_days: Some(14),
+        ..Default::default()
+    };
+
+    prune_sessions(workspace(&dir), "dev", &retention, None).unwrap();
+
+    assert!(!archive(&dir, old, 1).exists());
+    assert!(archive(&dir, recent, 1).exists());
+}
+
+/// Two archived parts and a live file whose last line is still being written
+fn split_session(dir: &TempDir) -> Vec<PathBuf> {
+    let session = "20261018_120000_000";
+    fs::create_dir_all(dir.path().join(".tooling")).unwrap();
+    fs::write(archive(dir, session, 1), "a\nb\n").unwrap();
+    fs::write(archive(dir, session, 2), "c\nd\n").unwrap();
+    fs::write(log_path(workspace(dir), "dev"), "e\nf").unwrap();
+    session_files(workspace(dir), "dev", session, Some(session)).unwrap()
+}
+
+#[test]
+fn tail_reads_back_across_parts() {
+    let dir = TempDir::new().unwrap();
+    let source = LogSource::open(split_session(&dir)).unwrap();
+
+    let (start, lines, end) = source.tail(source.size(), 3, Some).unwrap();
+
+    assert_eq!(lines, ["c", "d", "e"]);
+    assert_eq!(start, 4);
+    // The unfinished line is left for the next read
+    assert_eq!(end, source.size() - 1);
+
+    let (start, lines, _) = source.tail(start, 10, Some).unwrap();
+    assert_eq!(lines, ["a", "b"]);
+    assert_eq!(start, 0);
+}
+
+#[test]
+fn reading_resumes_from_a_cursor() {
+    let dir = TempDir::new().unwrap();
+    let files = split_session(&dir);
+    let source = LogSource::open(files.clone()).unwrap();
+
+    let (lines, cursor) = source
+        .read_lines(source.line_offset(1).unwrap(), 2, Some)
+        .unwrap();
+    assert_eq!(lines, ["b", "c"]);
+    assert_eq!(cursor, 6);
+
+    let (lines, cursor) = source.read_lines(cursor, 10, Some).unwrap();
+    assert_eq!(lines, ["d", "e"]);
+
+    // Tilt finishes the line and writes another
+    fs::write(log_path(workspace(&dir), "dev"), "e\nf\ng\n").unwrap();
+    let source = LogSource::open(files).unwrap();
+    let (lines, cursor) = source.read_lines(cursor, 10, Some).unwrap();
+    assert_eq!(lines, ["f", "g"]);
+    assert_eq!(cursor, source.size());
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tilt_orchestrator_lib::notifier::{
+    debounced, forget_environment, is_muted, transitions, NotificationKind, ResourceHealth,
+};
+use tilt_orchestrator_lib::NotificationPreferences;
+
+fn health(update_status: &str, runtime_status: &str) -> ResourceHealth {
+    ResourceHealth {
+        update_status: update_status.to_string(),
+        runtime_status: runtime_status.to_string(),
+    }
+}
+
+#[test]
+fn failures_notify_once_when_they_start() {
+    let ok = health("ok", "ok");
+
+    assert_eq!(
+        transitions(&ok, &health("error", "ok")),
+        vec![NotificationKind::BuildFailed]
+    );
+    assert_eq!(
+        transitions(&ok, &health("ok", "error")),
+        vec![NotificationKind::RuntimeError]
+    );
+    assert_eq!(
+        transitions(&ok, &health("error", "error")),
+        vec![
+            NotificationKind::BuildFailed,
+            NotificationKind::RuntimeError
+        ]
+    );
+    assert!(transitions(&health("error", "ok"), &health("error", "ok")).is_empty());
+    assert!(transitions(&ok, &ok).is_empty());
+}
+
+#[test]
+fn recovery_needs_the_resource_to_run_again() {
+    let failing = health("error", "error");
+
+    assert_eq!(
+        transitions(&failing, &health("ok", "ok")),
+        vec![NotificationKind::Recovered]
+    );
+    assert!(transitions(&failing, &health("in_progress", "pending")).is_empty());
+    // A resource seen for the first time has nothing to recover from
+    assert!(transitions(&ResourceHealth::default(), &health("ok", "ok")).is_empty());
+}
+
+#[test]
+fn muting_covers_everything_projects_or_single_services() {
+    let prefs = NotificationPreferences::default();
+    assert!(!is_muted(&prefs, "/work/demo", "api"));
+
+    let disabled = NotificationPreferences {
+        enabled: false,
+        ..NotificationPreferences::default()
+    };
+    assert!(is_muted(&disabled, "/work/demo", "api"));
+
+    let project_muted = NotificationPreferences {
+        muted_projects: vec!["/work/demo".to_string()],
+        ..NotificationPreferences::default()
+    };
+    assert!(is_muted(&project_muted, "/work/demo", "api"));
+    assert!(!is_muted(&project_muted, "/work/other", "api"));
+
+    let service_muted = NotificationPreferences {
+        muted_services: HashMap::from([("/work/demo".to_string(), vec!["api".to_string()])]),
+        ..NotificationPreferences::default()
+    };
+    assert!(is_muted(&service_muted, "/work/demo", "api"));
+    assert!(!is_muted(&service_muted, "/work/demo", "web"));
+    assert!(!is_muted(&service_muted, "/work/other", "api"));
+}
+
+/// Check the debounce for `resource` in `env` of `workspace`, with a 60s window
+fn repeated(workspace: &str, env: &str, resource: &str, kind: NotificationKind) -> bool {
+    debounced(workspace, env, resource, kind, Duration::from_secs(60))
+}
+
+#[test]
+fn repeats_are_debounced_per_resource_and_kind() {
+    use NotificationKind::*;
+    let workspace = "/work/debounce";
+
+    assert!(!repeated(workspace, "dev", "api", BuildFailed));
+    assert!(repeated(workspace, "dev", "api", BuildFailed));
+    assert!(!repeated(workspace, "dev", "api", RuntimeError));
+    assert!(!repeated(workspace, "dev", "web", BuildFailed));
+    assert!(!repeated(workspace, "qa", "api", BuildFailed));
+
+    // Once the window has passed the notification goes out again
+    for _ in 0..2 {
+        assert!(!debounced(
+            workspace,
+            "dev",
+            "db",
+            BuildFailed,
+            Duration::ZERO
+        ));
+    }
+}
+
+#[test]
+fn forgetting_an_environment_resets_its_debounce() {
+    use NotificationKind::*;
+    let workspace = "/work/forget";
+
+    assert!(!repeated(workspace, "dev", "api", Recovered));
+    assert!(!repeated(workspace, "qa", "api", Recovered));
+
+    forget_environment(workspace, "dev");
+
+    assert!(!repeated(workspace, "dev", "api", Recovered));
+    assert!(repeated(workspace, "qa", "api", Recovered));
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+use tilt_orchestrator_lib::inheritance::resolve_environment;
+use tilt_orchestrator_lib::model::{Service, ServiceOverride};
+use tilt_orchestrator_lib::project::environments::promote_environment;
+use tilt_orchestrator_lib::project::{create_project, load_project_info, write_environment};
+use tilt_orchestrator_lib::promotion::PromoteSelection;
+
+fn service(name: &str, env: &[(&str, &str)]) -> Service {
+    Service {
+        name: name.to_string(),
+        path: None,
+        port: 8080,
+        enabled: true,
+        repo: None,
+        docker: None,
+        k8s: None,
+        env: Some(
+            env.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        ),
+        depends_on: None,
+        helm: None,
+        kustomize: None,
+        replicas: None,
+        secrets: None,
+    }
+}
+
+/// `staging` extends `dev`, which defines `LOG_LEVEL` and the `worker` service.
+/// `prod` has neither, so both show up as removed when promoting prod → staging.
+/// Staging redefines `api` and overrides its `DEBUG`.
+fn project(dir: &TempDir) -> String {
+    let mut project = create_project("demo", dir.path().to_str().unwrap(), None).unwrap();
+    let root = project.project.workspace_path.clone();
+
+    let dev = project.environments.get_mut("dev").unwrap();
+    dev.shared_env
+        .insert("LOG_LEVEL".to_string(), "debug".to_string());
+    dev.services = vec![service("api", &[("DEBUG", "1")]), service("worker", &[])];
+    let prod = project.environments.get_mut("prod").unwrap();
+    prod.services = vec![service("api", &[])];
+    let staging = project.environments.get_mut("staging").unwrap();
+    staging.extends = Some("dev".to_string());
+    staging.services = vec![service("api", &[("DEBUG", "1")])];
+    staging.overrides = Some(HashMap::from([(
+        "api".to_string(),
+        ServiceOverride {
+            env: Some(HashMap::from([("DEBUG".to_string(), "2".to_string())])),
+            ..Default::default()
+        },
+    )]));
+
+    for name in ["api", "worker"] {
+        fs::create_dir_all(Path::new(&root).join("repos").join(name)).unwrap();
+    }
+    for env in ["dev", "staging", "prod"] {
+        write_environment(Path::new(&root), &project, env).unwrap();
+    }
+    root
+}
+
+#[test]
+fn removing_an_inherited_key_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    let root = project(&dir);
+
+    let selection = PromoteSelection {
+        shared_env: vec!["LOG_LEVEL".to_string()],
+        ..Default::default()
+    };
+    let error = promote_environment(&root, "prod", "staging", &selection).unwrap_err();
+
+    assert!(error.to_string().contains("inherited from dev"));
+    let project = load_project_info(&root).unwrap();
+    assert!(project.environments["staging"].shared_env.is_empty());
+}
+
+#[test]
+fn removing_an_inherited_service_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    let root = project(&dir);
+
+    let selection = PromoteSelection {
+        services: vec!["worker".to_string()],
+        ..Default::default()
+    };
+    let error = promote_environment(&root, "prod", "staging", &selection).unwrap_err();
+
+    assert!(error.to_string().contains("inherited from dev"));
+}
+
+#[test]
+fn removing_a_key_set_by_an_override_takes_effect() {
+    let dir = TempDir::new().unwrap();
+    let root = project(&dir);
+
+    let selection = PromoteSelection {
+        service_env: HashMap::from([("api".to_string(), vec!["DEBUG".to_string()])]),
+        ..Default::default()
+    };
+    let project = promote_environment(&root, "prod", "staging", &selection).unwrap();
+
+    let staging = resolve_environment(&project, "staging").unwrap();
+    let api = staging.services.iter().find(|s| s.name == "api").unwrap();
+    assert!(!api.env.as_ref().unwrap().contains_key("DEBUG"));
+}
@@ -0,0 +1,53 @@
+use tilt_orchestrator_lib::redaction::{is_sensitive_key, Redactor};
+use tilt_orchestrator_lib::secrets::MASK;
+
+#[test]
+fn values_shorter_than_four_characters_are_kept() {
+    let redactor = Redactor::new(["abc".to_string(), "abcd".to_string()], &[]).unwrap();
+
+    assert_eq!(redactor.redact("abc"), "abc");
+    assert_eq!(redactor.redact("key abcd"), format!("key {}", MASK));
+}
+
+#[test]
+fn longer_values_are_masked_first() {
+    let redactor = Redactor::new(["s3cret".to_string(), "s3cret-suffix".to_string()], &[]).unwrap();
+
+    assert_eq!(
+        redactor.redact("token s3cret-suffix"),
+        format!("token {}", MASK)
+    );
+}
+
+#[test]
+fn sensitive_keys_are_found_by_suffix() {
+    for key in ["API_TOKEN", "db_password", "Stripe_Key"] {
+        assert!(is_sensitive_key(key), "{}", key);
+    }
+    for key in ["TOKEN_URL", "MONKEY", "PASSWORD_HINT", "LOG_LEVEL"] {
+        assert!(!is_sensitive_key(key), "{}", key);
+    }
+}
+
+#[test]
+fn redacting_twice_changes_nothing_more() {
+    let redactor = Redactor::new(
+        ["hunter22".to_string()],
+        &[r"Bearer \S+".to_string(), r"\d{16}".to_string()],
+    )
+    .unwrap();
+    let line = "auth Bearer abc.def card 4111111111111111 pass hunter22";
+
+    let once = redactor.redact(line);
+    assert!(!once.contains("abc.def") && !once.contains("4111") && !once.contains("hunter22"));
+    assert_eq!(redactor.redact(&once), once);
+}
+
+#[test]
+fn invalid_patterns_are_rejected() {
+    let error = Redactor::new(Vec::new(), &["(unclosed".to_string()]).unwrap_err();
+
+    assert!(error
+        .to_string()
+        .contains("Invalid log redaction pattern (unclosed"));
+}
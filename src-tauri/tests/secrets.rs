@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+use tilt_orchestrator_lib::model::{K8s, Project, Service};
+use tilt_orchestrator_lib::project::environments::{
+    clone_environment, create_environment, delete_environment, rename_environment,
+};
+use tilt_orchestrator_lib::project::{
+    add_service, create_project, load_project_info, rename_service, update_service,
+    write_environment,
+};
+use tilt_orchestrator_lib::secrets::{secret_values, set_secret};
+
+fn service(name: &str) -> Service {
+    Service {
+        name: name.to_string(),
+        path: None,
+        port: 8080,
+        enabled: true,
+        repo: None,
+        docker: None,
+        k8s: None,
+        env: None,
+        depends_on: None,
+        helm: None,
+        kustomize: None,
+        replicas: None,
+        secrets: None,
+    }
+}
+
+/// A project with `TOKEN` stored for its `api` service in `dev`
+fn project_with_secret(workspace: &Path, data_dir: &Path) -> Project {
+    let project = create_project("demo", workspace.to_str().unwrap(), Some("repos")).unwrap();
+    let root = project.project.workspace_path;
+    let (mut project, _) = add_service(&root, "dev", service("api")).unwrap();
+
+    set_secret(
+        data_dir,
+        &mut project,
+        "dev",
+        Some("api"),
+        "TOKEN",
+        "s3cret",
+        None,
+    )
+    .unwrap();
+    write_environment(Path::new(&root), &project, "dev").unwrap();
+    project
+}
+
+fn token(data_dir: &Path, project: &Project, env: &str, service: &str) -> Option<String> {
+    secret_values(data_dir, project, env)
+        .unwrap()
+        .get(service)?
+        .get("TOKEN")
+        .cloned()
+}
+
+#[test]
+fn renaming_an_environment_keeps_its_secrets() {
+    let (workspace, data_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let project = project_with_secret(workspace.path(), data_dir.path());
+
+    let project = rename_environment(
+        data_dir.path(),
+        &project.project.workspace_path,
+        "dev",
+        "qa",
+    )
+    .unwrap();
+
+    assert_eq!(
+        token(data_dir.path(), &project, "qa", "api").as_deref(),
+        Some("s3cret")
+    );
+}
+
+#[test]
+fn cloning_an_environment_copies_its_secrets() {
+    let (workspace, data_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let project = project_with_secret(workspace.path(), data_dir.path());
+
+    let project = clone_environment(
+        data_dir.path(),
+        &project.project.workspace_path,
+        "dev",
+        "preview",
+    )
+    .unwrap();
+
+    for env in ["dev", "preview"] {
+        assert_eq!(
+            token(data_dir.path(), &project, env, "api").as_deref(),
+            Some("s3cret")
+        );
+    }
+}
+
+#[test]
+fn deleting_an_environment_drops_its_secrets() {
+    let (workspace, data_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let project = project_with_secret(workspace.path(), data_dir.path());
+    let root = project.project.workspace_path.clone();
+    clone_environment(data_dir.path(), &root, "dev", "preview").unwrap();
+
+    delete_environment(data_dir.path(), &root, "preview").unwrap();
+
+    // A new environment of the same name declaring the same secret has no value for it
+    let mut project = create_environment(&root, "preview").unwrap();
+    let dev = project.environments["dev"].clone();
+    project.environments.insert("preview".to_string(), dev);
+    write_environment(Path::new(&root), &project, "preview").unwrap();
+    assert_eq!(token(data_dir.path(), &project, "preview", "api"), None);
+    assert_eq!(
+        token(data_dir.path(), &project, "dev", "api").as_deref(),
+        Some("s3cret")
+    );
+}
+
+#[test]
+fn secret_files_outside_the_workspace_are_rejected() {
+    let (workspace, data_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let mut project = project_with_secret(workspace.path(), data_dir.path());
+    let outside = workspace.path().join("outside.env");
+
+    for file in ["../outside.env", outside.to_str().unwrap(), ""] {
+        let error = set_secret(
+            data_dir.path(),
+            &mut project,
+            "dev",
+            None,
+            "DB_PASSWORD",
+            "hunter22",
+            Some(file.to_string()),
+        )
+        .unwrap_err();
+        assert!(
+            error.to_string().contains("inside the workspace"),
+            "{}",
+            file
+        );
+    }
+    assert!(!outside.exists());
+}
+
+#[test]
+fn renaming_a_service_keeps_its_secrets() {
+    let (workspace, data_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let project = project_with_secret(workspace.path(), data_dir.path());
+
+    let project = rename_service(
+        data_dir.path(),
+        &project.project.workspace_path,
+        "api",
+        "backend",
+    )
+    .unwrap();
+
+    assert_eq!(
+        token(data_dir.path(), &project, "dev", "backend").as_deref(),
+        Some("s3cret")
+    );
+}
+
+#[test]
+fn moving_the_project_folder_keeps_its_secrets() {
+    let (workspace, data_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let project = project_with_secret(workspace.path(), data_dir.path());
+
+    let moved = workspace.path().join("moved");
+    fs::rename(&project.project.workspace_path, &moved).unwrap();
+    let project = load_project_info(moved.to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        token(data_dir.path(), &project, "dev", "api").as_deref(),
+        Some("s3cret")
+    );
+}
+
+#[test]
+fn existing_service_files_get_the_secret_instead_of_the_plain_value() {
+    let (workspace, data_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let project =
+        create_project("demo", workspace.path().to_str().unwrap(), Some("repos")).unwrap();
+    let root = project.project.workspace_path;
+
+    let api = Service {
+        k8s: Some(K8s {
+            manifests: "k8s".to_string(),
+        }),
+        env: Some(HashMap::from([
+            ("TOKEN".to_string(), "plain".to_string()),
+            ("MODE".to_string(), "debug".to_string()),
+        ])),
+        ..service("api")
+    };
+    let (mut project, _) = add_service(&root, "dev", api).unwrap();
+    let deployment = Path::new(&root).join("repos/api/k8s/api-deployment.yaml");
+    assert!(fs::read_to_string(&deployment)
+        .unwrap()
+        .contains("TOKEN: \"plain\""));
+
+    set_secret(
+        data_dir.path(),
+        &mut project,
+        "dev",
+        Some("api"),
+        "TOKEN",
+        "s3cret",
+        None,
+    )
+    .unwrap();
+    write_environment(Path::new(&root), &project, "dev").unwrap();
+    let api = project.environments["dev"].services[0].clone();
+    update_service(&root, "dev", "api", api).unwrap();
+
+    let yaml = fs::read_to_string(&deployment).unwrap();
+    assert!(yaml.contains("        - secretRef:\n            name: api-secrets\n"));
+    assert!(!yaml.contains("TOKEN"));
+    assert!(yaml.contains("MODE: \"debug\""));
+
+    let dotenv = fs::read_to_string(Path::new(&root).join("repos/api/.env.dev")).unwrap();
+    assert_eq!(dotenv, "MODE=debug");
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+use tilt_orchestrator_lib::model::{K8s, Service, ServiceOverride};
+use tilt_orchestrator_lib::project::store::read_project;
+use tilt_orchestrator_lib::project::{
+    add_service, create_project, remove_service, rename_service, write_environment,
+};
+
+fn service(name: &str, manifests: &str) -> Service {
+    Service {
+        name: name.to_string(),
+        path: None,
+        port: 8080,
+        enabled: true,
+        repo: None,
+        docker: None,
+        k8s: Some(K8s {
+            manifests: manifests.to_string(),
+        }),
+        env: None,
+        depends_on: None,
+        helm: None,
+        kustomize: None,
+        replicas: None,
+        secrets: None,
+    }
+}
+
+#[test]
+fn renaming_a_service_moves_its_directory_and_deployment() {
+    let dir = TempDir::new().unwrap();
+    let project = create_project("demo", dir.path().to_str().unwrap(), None).unwrap();
+    let root = project.project.workspace_path;
+    add_service(&root, "dev", service("api", "k8s/api-deployment.yaml")).unwrap();
+
+    let data_dir = dir.path().join("data");
+    let project = rename_service(&data_dir, &root, "api", "backend").unwrap();
+
+    let repos = Path::new(&root).join("repos");
+    assert!(!repos.join("api").exists());
+    let deployment = repos.join("backend/k8s/backend-deployment.yaml");
+    let yaml = fs::read_to_string(&deployment).unwrap();
+    assert!(yaml.contains("  name: backend\n"));
+    assert!(yaml.contains("name: backend-config"));
+    assert!(!yaml.contains(": api\n") && !yaml.contains("api-config"));
+
+    let backend = &project.environments["dev"].services[0];
+    assert_eq!(
+        backend.k8s.as_ref().unwrap().manifests,
+        "k8s/backend-deployment.yaml"
+    );
+    let tiltfile =
+        fs::read_to_string(Path::new(&root).join("tilt/dev/services/backend.tilt.py")).unwrap();
+    assert!(tiltfile.contains("repos/backend/k8s/backend-deployment.yaml"));
+}
+
+#[test]
+fn removing_an_inherited_service_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    let project = create_project("demo", dir.path().to_str().unwrap(), None).unwrap();
+    let root = project.project.workspace_path;
+    let (mut project, _) = add_service(&root, "dev", service("api", "k8s")).unwrap();
+
+    let staging = project.environments.get_mut("staging").unwrap();
+    staging.extends = Some("dev".to_string());
+    staging.overrides = Some(HashMap::from([(
+        "api".to_string(),
+        ServiceOverride {
+            port: Some(9090),
+            ..Default::default()
+        },
+    )]));
+    write_environment(Path::new(&root), &project, "staging").unwrap();
+
+    let error = remove_service(&root, "staging", "api", false).unwrap_err();
+    assert!(error.to_string().contains("inherited"));
+    let project = read_project(Path::new(&root)).unwrap();
+    assert!(project.environments["staging"]
+        .overrides
+        .as_ref()
+        .unwrap()
+        .contains_key("api"));
+}
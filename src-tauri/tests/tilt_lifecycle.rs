@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use tempfile::TempDir;
+use tilt_orchestrator_lib::host::{TiltHost, TrayHealth};
+use tilt_orchestrator_lib::model::{Project, Service};
+use tilt_orchestrator_lib::process::{ProcessOutput, ScriptStep, ScriptedRunner};
+use tilt_orchestrator_lib::project::{add_service, create_project, write_environment};
+use tilt_orchestrator_lib::secrets::set_secret;
+use tilt_orchestrator_lib::session_history::{read_history, record_start, StopInitiator};
+use tilt_orchestrator_lib::tilt_manager::{
+    read_state, reconcile_tilt_state, start_tilt, stop_tilt, TiltLaunch, TiltRun,
+};
+use tilt_orchestrator_lib::tilt_resources::UiResource;
+
+/// Runs Tilt from a script and remembers the exits it was told about
+#[derive(Clone)]
+struct TestHost {
+    runner: ScriptedRunner,
+    data_dir: PathBuf,
+    exits: Arc<Mutex<Vec<Option<i32>>>>,
+}
+
+impl TiltHost for TestHost {
+    type Runner = ScriptedRunner;
+
+    fn data_dir(&self) -> io::Result<PathBuf> {
+        Ok(self.data_dir.clone())
+    }
+
+    fn runner(&self) -> ScriptedRunner {
+        self.runner.clone()
+    }
+
+    async fn tilt_exited(&self, _workspace: &str, _env: &str, exit_code: Option<i32>) {
+        self.exits.lock().unwrap().push(exit_code);
+    }
+
+    async fn health_changed(&self, _workspace: &str, _env: &str, _health: TrayHealth) {}
+
+    async fn resources_changed(&self, _workspace: &str, _env: &str, _resources: &[UiResource]) {}
+}
+
+struct Fixture {
+    _dir: TempDir,
+    host: TestHost,
+    project: Project,
+    workspace: String,
+}
+
+/// A project whose `api` service has `TOKEN` stored as a secret and an
+/// `API_TOKEN` built from the environment name, with Tilt printing `steps`
+fn fixture(steps: Vec<ScriptStep>) -> Fixture {
+    let dir = TempDir::new().unwrap();
+    let data_dir = dir.path().join("data");
+    let project = create_project("demo", dir.path().join("ws").to_str().unwrap(), None).unwrap();
+    let workspace = project.project.workspace_path.clone();
+
+    let api = Service {
+        name: "api".to_string(),
+        path: None,
+        port: 8080,
+        enabled: true,
+        repo: None,
+        docker: None,
+        k8s: None,
+        env: Some(HashMap::from([(
+            "API_TOKEN".to_string(),
+            "${env.name}-api-token".to_string(),
+        )])),
+        depends_on: None,
+        helm: None,
+        kustomize: None,
+        replicas: None,
+        secrets: None,
+    };
+    let (mut project, _) = add_service(&workspace, "dev", api).unwrap();
+    set_secret(
+        &data_dir,
+        &mut project,
+        "dev",
+        Some("api"),
+        "TOKEN",
+        "s3cret",
+        None,
+    )
+    .unwrap();
+    write_environment(Path::new(&workspace), &project, "dev").unwrap();
+
+    let runner = ScriptedRunner::new();
+    runner.on_output("tilt", ProcessOutput::ok("v0.33.0"));
+    runner.on_spawn("tilt", steps);
+
+    Fixture {
+        _dir: dir,
+        host: TestHost {
+            runner,
+            data_dir,
+            exits: Arc::default(),
+        },
+        project,
+        workspace,
+    }
+}
+
+impl Fixture {
+    async fn start(&self) -> TiltRun {
+        let launch = TiltLaunch::prepare(&self.host, &self.project, "dev").unwrap();
+        start_tilt(&self.workspace, "dev", &self.host, Some("tilt"), launch)
+            .await
+            .unwrap()
+            .expect("Tilt was not running")
+    }
+}
+
+#[tokio::test]
+async fn start_runs_tilt_on_its_own_port() {
+    let fixture = fixture(vec![ScriptStep::Stdout("Tilt started".to_string())]);
+    let run = fixture.start().await;
+
+    let state = read_state(&fixture.workspace, "dev").tilt;
+    assert_eq!(state.status, "starting");
+    assert_eq!(state.pid, Some(run.pid));
+    assert_eq!(state.session.as_deref(), Some(run.session.as_str()));
+
+    let port = state.port.expect("no port recorded").to_string();
+    let up = fixture
+        .host
+        .runner
+        .invocations()
+        .into_iter()
+        .find(|run| run.args.first().map(String::as_str) == Some("up"))
+        .expect("tilt up was not run");
+    assert!(up.args.ends_with(&["--port".to_string(), port]));
+
+    let history = read_history(&fixture.workspace, "dev");
+    assert_eq!(history.last().unwrap().id, run.session);
+    assert_eq!(history.last().unwrap().enabled_services, vec!["api"]);
+
+    stop_tilt(
+        &fixture.workspace,
+        "dev",
+        &fixture.host,
+        StopInitiator::User,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn ports_recorded_for_other_environments_are_skipped() {
+    let fixture = fixture(vec![ScriptStep::Stdout("Tilt started".to_string())]);
+    // Another app instance started staging on the first port nothing listens on
+    let port = (10350..10450)
+        .find(|port| TcpListener::bind((Ipv4Addr::LOCALHOST, *port)).is_ok())
+        .unwrap();
+    fs::write(
+        Path::new(&fixture.workspace).join(".tooling/staging_runtime.json"),
+        format!(
+            r#"{{"tilt":{{"status":"running","pid":4242,"session":"other","port":{}}}}}"#,
+            port
+        ),
+    )
+    .unwrap();
+
+    fixture.start().await;
+
+    let state = read_state(&fixture.workspace, "dev").tilt;
+    assert_ne!(state.port, Some(port));
+    stop_tilt(
+        &fixture.workspace,
+        "dev",
+        &fixture.host,
+        StopInitiator::User,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn starting_twice_at_once_runs_one_tilt() {
+    let fixture = fixture(vec![ScriptStep::Stdout("Tilt started".to_string())]);
+    let launch = || TiltLaunch::prepare(&fixture.host, &fixture.project, "dev").unwrap();
+
+    let (first, second) = tokio::join!(
+        start_tilt(
+            &fixture.workspace,
+            "dev",
+            &fixture.host,
+            Some("tilt"),
+            launch()
+        ),
+        start_tilt(
+            &fixture.workspace,
+            "dev",
+            &fixture.host,
+            Some("tilt"),
+            launch()
+        ),
+    );
+
+    let runs: Vec<_> = [first.unwrap(), second.unwrap()]
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(runs.len(), 1);
+    let ups = fixture
+        .host
+        .runner
+        .invocations()
+        .into_iter()
+        .filter(|run| run.args.first().map(String::as_str) == Some("up"))
+        .count();
+    assert_eq!(ups, 1);
+
+    stop_tilt(
+        &fixture.workspace,
+        "dev",
+        &fixture.host,
+        StopInitiator::User,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn an_unexpected_exit_is_recorded_as_a_crash() {
+    let fixture = fixture(vec![
+        ScriptStep::Stderr("Tiltfile failed to load".to_string()),
+        ScriptStep::Exit(Some(1)),
+    ]);
+    let run = fixture.start().await;
+
+    assert_eq!(run.wait().await, Some(1));
+
+    let record = read_history(&fixture.workspace, "dev").pop().unwrap();
+    assert_eq!(record.stopped_by, Some(StopInitiator::Crash));
+    assert_eq!(record.exit_code, Some(1));
+    assert_eq!(read_state(&fixture.workspace, "dev").tilt.status, "stopped");
+    assert_eq!(*fixture.host.exits.lock().unwrap(), vec![Some(1)]);
+}
+
+#[tokio::test]
+async fn stopping_records_who_asked() {
+    let fixture = fixture(vec![ScriptStep::Stdout("Tilt started".to_string())]);
+    let run = fixture.start().await;
+    let pid = run.pid.to_string();
+
+    stop_tilt(
+        &fixture.workspace,
+        "dev",
+        &fixture.host,
+        StopInitiator::Tray,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(run.wait().await, None);
+    let record = read_history(&fixture.workspace, "dev").pop().unwrap();
+    assert_eq!(record.stopped_by, Some(StopInitiator::Tray));
+    assert!(record.stopped_at.is_some());
+
+    let state = read_state(&fixture.workspace, "dev").tilt;
+    assert_eq!(state.status, "stopped");
+    assert_eq!(state.pid, None);
+    assert!(fixture.host.exits.lock().unwrap().is_empty());
+    assert!(fixture
+        .host
+        .runner
+        .invocations()
+        .iter()
+        .any(|run| run.program == "kill" && run.args == [pid.clone()]));
+}
+
+#[tokio::test]
+async fn a_failed_kill_keeps_tilt_running() {
+    let fixture = fixture(vec![ScriptStep::Stdout("Tilt started".to_string())]);
+    fixture
+        .host
+        .runner
+        .on_output("kill", ProcessOutput::failed(1, "Operation not permitted"));
+    let run = fixture.start().await;
+
+    let result = stop_tilt(
+        &fixture.workspace,
+        "dev",
+        &fixture.host,
+        StopInitiator::User,
+    )
+    .await;
+
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("Operation not permitted"));
+    let state = read_state(&fixture.workspace, "dev").tilt;
+    assert_eq!(state.status, "starting");
+    assert_eq!(state.pid, Some(run.pid));
+    let record = read_history(&fixture.workspace, "dev").pop().unwrap();
+    assert_eq!(record.stopped_by, None);
+    assert_eq!(record.stopped_at, None);
+}
+
+#[tokio::test]
+async fn captured_output_is_redacted() {
+    let fixture = fixture(vec![
+        ScriptStep::Stdout("api: connecting with s3cret".to_string()),
+        ScriptStep::Stderr("api: API_TOKEN=dev-api-token".to_string()),
+        ScriptStep::Exit(Some(0)),
+    ]);
+    fixture.start().await.wait().await;
+
+    let log =
+        fs::read_to_string(Path::new(&fixture.workspace).join(".tooling/dev_tilt.log")).unwrap();
+    assert!(log.contains("api: connecting with"));
+    assert!(log.contains("api: API_TOKEN="));
+    assert!(!log.contains("s3cret"));
+    assert!(!log.contains("dev-api-token"));
+}
+
+#[tokio::test]
+async fn tilt_found_gone_is_recorded_by_the_watchdog() {
+    let fixture = fixture(Vec::new());
+    // A run this process never saw exit, such as one from before the app restarted
+    record_start(
+        &fixture.workspace,
+        "dev",
+        "earlier",
+        None,
+        Vec::new(),
+        false,
+    )
+    .unwrap();
+    fs::write(
+        Path::new(&fixture.workspace).join(".tooling/dev_runtime.json"),
+        r#"{"tilt":{"status":"running","pid":4242,"session":"earlier"}}"#,
+    )
+    .unwrap();
+
+    let status = reconcile_tilt_state(&fixture.workspace, "dev", &fixture.host)
+        .await
+        .unwrap();
+
+    assert_eq!(status.status, "stopped");
+    let record = read_history(&fixture.workspace, "dev").pop().unwrap();
+    assert_eq!(record.stopped_by, Some(StopInitiator::Watchdog));
+    assert_eq!(*fixture.host.exits.lock().unwrap(), vec![None]);
+}
+
+#[tokio::test]
+async fn secrets_are_only_on_disk_while_tilt_runs() {
+    let fixture = fixture(vec![ScriptStep::Stdout("Tilt started".to_string())]);
+    let secrets = Path::new(&fixture.workspace).join(".tooling/secrets/dev");
+    fixture.start().await;
+
+    let file = secrets.join("api.env");
+    assert!(fs::read_to_string(&file).unwrap().contains("TOKEN=s3cret"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    stop_tilt(
+        &fixture.workspace,
+        "dev",
+        &fixture.host,
+        StopInitiator::User,
+    )
+    .await
+    .unwrap();
+    assert!(!secrets.exists());
+}
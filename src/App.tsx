@@ -1,6 +1,7 @@
 import { useEffect, useState } from "react"
 import { AppStateProvider } from "@/providers/AppStateProvider"
 import { invoke } from "@tauri-apps/api/core"
+import { listen } from "@tauri-apps/api/event"
 import { ask, message } from "@tauri-apps/plugin-dialog"
 
 import { AppState } from "@/types/app"
@@ -35,6 +36,7 @@ function App() {
   const [hasCheckedTilt, setHasCheckedTilt] = useState(false)
   const [pendingProjectPath, setPendingProjectPath] = useState<string>("")
   const [hasValidTilt, setHasValidTilt] = useState<boolean>(false)
+  const [trayEnv, setTrayEnv] = useState<{ name: string } | null>(null)
 
   useEffect(() => {
     // Close splashscreen when React app is ready
@@ -47,6 +49,32 @@ function App() {
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [])
 
+  useEffect(() => {
+    // The tray changes the project without going through this window
+    const unlisten = listen<Project>("project-updated", (event) => {
+      setCurrentProject((current) =>
+        current?.project.workspace_path === event.payload.project.workspace_path
+          ? event.payload
+          : current
+      )
+    })
+
+    // Switching environment or project from the tray
+    const unlistenSelection = listen<{ project: Project; env: string }>(
+      "tray-selection-changed",
+      (event) => {
+        setCurrentProject(event.payload.project)
+        setTrayEnv({ name: event.payload.env })
+        setCurrentScreen("project-view")
+      }
+    )
+
+    return () => {
+      unlisten.then((fn) => fn())
+      unlistenSelection.then((fn) => fn())
+    }
+  }, [])
+
   const checkTiltConfiguration = async () => {
     if (hasCheckedTilt) return
     setHasCheckedTilt(true)
@@ -87,13 +115,7 @@ function App() {
   const handleOpenProject = async (path: string, _name: string) => {
     try {
       // First check if it's a valid TO project
-      const validation = await isValidProject(path).then((response: any) => {
-        if (typeof response === "string") {
-          return JSON.parse(response)
-        } else {
-          return response
-        }
-      })
+      const validation = await isValidProject(path)
 
       if (!validation.valid) {
         // Not a valid TO project - ask user if they want to initialize it
@@ -116,7 +138,7 @@ function App() {
       // Valid project - open it
       const project = await openProject(path)
 
-      setCurrentProject(JSON.parse(project as string))
+      setCurrentProject(project)
       setCurrentScreen("project-view")
     } catch (err) {
       // console.error("Failed to load project", err)
@@ -209,7 +231,9 @@ function App() {
             )}
             {currentScreen === "project-view" && currentProject && (
               <ProjectView
+                key={currentProject.project.workspace_path}
                 project={currentProject}
+                requestedEnv={trayEnv}
                 onBack={handleBackToLanding}
                 onEdit={handleEditProject}
               />
@@ -1,76 +1,176 @@
 import { invoke } from "@tauri-apps/api/core"
+import { listen } from "@tauri-apps/api/event"
 
-import { Project } from "../types/project"
-import { TiltInstallation, TiltStatus } from "../types/tilt"
+import {
+  EnvDiff,
+  Environment,
+  InterpolationError,
+  Project,
+  PromoteSelection,
+  SecretEntry,
+  Service,
+  ValueOrigin,
+} from "../types/project"
+import {
+  LogQuery,
+  LogSearchQuery,
+  LogSession,
+  ResourceStats,
+  SessionRecord,
+  StopInitiator,
+  TiltInstallation,
+  TiltLog,
+  TiltStatus,
+} from "../types/tilt"
+import type { Commands } from "./bindings/commands"
+import type { Job } from "./bindings/Job"
+import { BackendError, toBackendError } from "./errors"
+
+export type { Job }
+
+export type Command = keyof Commands
+
+/**
+ * Run a backend command, its args and result types come from the Rust side.
+ * Rejects with a `BackendError`.
+ */
+export async function callBackend<C extends Command>(
+  command: C,
+  args: Commands[C]["request"]
+): Promise<Commands[C]["response"]> {
+  try {
+    return await invoke<Commands[C]["response"]>("call_backend", {
+      command,
+      args,
+    })
+  } catch (error) {
+    throw toBackendError(error)
+  }
+}
+
+/** The commands the backend has registered, as reported by the backend */
+export async function listCommands() {
+  return callBackend("listCommands", {})
+}
+
+/** Running jobs and the ones finished since the app started, newest first */
+export async function listJobs() {
+  return callBackend("listJobs", {})
+}
+
+export async function getJob(jobId: string) {
+  return callBackend("getJob", { job_id: jobId })
+}
+
+export async function getJobLogs(jobId: string) {
+  return callBackend("getJobLogs", { job_id: jobId })
+}
+
+/** Ask a running job to stop, it finishes as `cancelled` */
+export async function cancelJob(jobId: string) {
+  return callBackend("cancelJob", { job_id: jobId })
+}
+
+/**
+ * Follow a job until it finishes. `onUpdate` gets every change on the way.
+ * Rejects with a `BackendError` when the job fails or is cancelled.
+ */
+export async function waitForJob(
+  jobId: string,
+  onUpdate?: (job: Job) => void
+): Promise<Job> {
+  let finish: (job: Job) => void = () => {}
+  const finished = new Promise<Job>((resolve) => (finish = resolve))
+  const update = (job: Job) => {
+    if (job.id !== jobId) return
+    onUpdate?.(job)
+    if (job.status !== "running") finish(job)
+  }
+
+  const unlisten = await listen<Job>("job-updated", (event) =>
+    update(event.payload)
+  )
+  try {
+    // It may have finished before the listener was in place
+    update(await getJob(jobId))
+    const job = await finished
+    if (job.status !== "succeeded") {
+      throw job.error
+        ? new BackendError(job.error)
+        : toBackendError(`Job ${job.status}`)
+    }
+    return job
+  } finally {
+    unlisten()
+  }
+}
 
 export async function checkTiltInstalled(): Promise<TiltInstallation> {
-  return invoke("call_backend", {
-    command: "checkTiltInstalled",
-    args: {},
-  }) as Promise<TiltInstallation>
+  return callBackend("checkTiltInstalled", {})
 }
 
 export async function validateExecutablePath(
   path: string
 ): Promise<{ valid: boolean; version: string }> {
-  return invoke("call_backend", {
-    command: "validateExecutablePath",
-    args: { path },
-  }) as Promise<{ valid: boolean; version: string }>
+  return callBackend("validateExecutablePath", { path })
 }
 
 export async function createProject(
   name: string,
   workspace: string,
-  services_path: string
+  services_path: string,
+  onUpdate?: (job: Job) => void
 ): Promise<Project> {
-  return invoke("call_backend", {
-    command: "createProject",
-    args: {
-      name,
-      workspace_path: workspace,
-      services_path,
-    },
-  }) as Promise<Project>
+  const job = await callBackend("createProject", {
+    name,
+    workspace_path: workspace,
+    services_path,
+  })
+  const finished = await waitForJob(job.id, onUpdate)
+  return finished.result as unknown as Project
 }
 
-export async function openProject(workspace_path: string): Promise<any> {
-  return invoke("call_backend", {
-    command: "openProject",
-    args: { workspace_path },
-  }) as Promise<Project>
+export async function openProject(workspace_path: string): Promise<Project> {
+  return callBackend("openProject", { workspace_path }) as Promise<Project>
 }
 
 export async function isValidProject(
   path: string
 ): Promise<{ valid: boolean }> {
-  return invoke("call_backend", {
-    command: "isValidProject",
-    args: { path },
-  }) as Promise<{ valid: boolean }>
+  return callBackend("isValidProject", { path })
 }
 
 export async function initializeExistingProject(
   path: string,
   servicesPath: string
 ): Promise<Project> {
-  return invoke("call_backend", {
-    command: "initializeExistingProject",
-    args: { path, services_path: servicesPath },
+  return callBackend("initializeExistingProject", {
+    path,
+    services_path: servicesPath,
   }) as Promise<Project>
 }
 
+export async function discoverServices(
+  path: string,
+  servicesPath: string
+): Promise<Service[]> {
+  return callBackend("discoverServices", {
+    path,
+    services_path: servicesPath,
+  }) as Promise<Service[]>
+}
+
 export async function updateProject(
   workspace_path: string,
-  project: Project
+  project: Project,
+  onUpdate?: (job: Job) => void
 ): Promise<Project> {
-  return invoke("call_backend", {
-    command: "updateProject",
-    args: {
-      workspace_path,
-      project,
-    },
-  }) as Promise<Project>
+  const job = await callBackend("updateProject", {
+    workspace_path,
+    project,
+  })
+  const finished = await waitForJob(job.id, onUpdate)
+  return finished.result as unknown as Project
 }
 
 export async function updateService(
@@ -79,80 +179,265 @@ export async function updateService(
   serviceName: string,
   service: any
 ): Promise<Project> {
-  return invoke("call_backend", {
-    command: "updateService",
-    args: {
-      workspace_path,
-      env,
-      service_name: serviceName,
-      service,
-    },
+  return callBackend("updateService", {
+    workspace_path,
+    env,
+    service_name: serviceName,
+    service,
+  }) as Promise<Project>
+}
+
+export async function addService(
+  workspace_path: string,
+  env: string,
+  service: Service,
+  onUpdate?: (job: Job) => void
+): Promise<Project> {
+  const job = await callBackend("addService", {
+    workspace_path,
+    env,
+    service,
+  })
+  const finished = await waitForJob(job.id, onUpdate)
+  return finished.result as unknown as Project
+}
+
+export async function removeService(
+  workspace_path: string,
+  env: string,
+  serviceName: string,
+  deleteDirectory = false
+): Promise<{ project: Project; dependents: string[] }> {
+  return callBackend("removeService", {
+    workspace_path,
+    env,
+    service_name: serviceName,
+    delete_directory: deleteDirectory,
+  }) as Promise<{ project: Project; dependents: string[] }>
+}
+
+export async function renameService(
+  workspace_path: string,
+  serviceName: string,
+  newName: string
+): Promise<Project> {
+  return callBackend("renameService", {
+    workspace_path,
+    service_name: serviceName,
+    new_name: newName,
+  }) as Promise<Project>
+}
+
+export async function createEnvironment(
+  workspace_path: string,
+  env: string
+): Promise<Project> {
+  return callBackend("createEnvironment", {
+    workspace_path,
+    env,
+  }) as Promise<Project>
+}
+
+export async function cloneEnvironment(
+  workspace_path: string,
+  source: string,
+  target: string
+): Promise<Project> {
+  return callBackend("cloneEnvironment", {
+    workspace_path,
+    source,
+    target,
+  }) as Promise<Project>
+}
+
+export async function renameEnvironment(
+  workspace_path: string,
+  env: string,
+  newName: string
+): Promise<Project> {
+  return callBackend("renameEnvironment", {
+    workspace_path,
+    env,
+    new_name: newName,
+  }) as Promise<Project>
+}
+
+export async function deleteEnvironment(
+  workspace_path: string,
+  env: string
+): Promise<Project> {
+  return callBackend("deleteEnvironment", {
+    workspace_path,
+    env,
   }) as Promise<Project>
 }
 
+export async function diffEnvironments(
+  project: Project,
+  source: string,
+  target: string
+): Promise<EnvDiff> {
+  return callBackend("diffEnvironments", {
+    project,
+    source,
+    target,
+  }) as Promise<EnvDiff>
+}
+
+export async function promoteEnvironment(
+  workspace_path: string,
+  source: string,
+  target: string,
+  selection: PromoteSelection
+): Promise<Project> {
+  return callBackend("promoteEnvironment", {
+    workspace_path,
+    source,
+    target,
+    selection,
+  }) as Promise<Project>
+}
+
+export async function setSecret(
+  workspace_path: string,
+  env: string,
+  key: string,
+  value: string,
+  options: { service?: string; file?: string } = {}
+): Promise<Project> {
+  return callBackend("setSecret", {
+    workspace_path,
+    env,
+    key,
+    value,
+    ...options,
+  }) as Promise<Project>
+}
+
+export async function removeSecret(
+  workspace_path: string,
+  env: string,
+  key: string,
+  service?: string
+): Promise<Project> {
+  return callBackend("removeSecret", {
+    workspace_path,
+    env,
+    key,
+    service,
+  }) as Promise<Project>
+}
+
+export async function listSecrets(
+  project: Project,
+  env: string
+): Promise<SecretEntry[]> {
+  return callBackend("listSecrets", { project, env })
+}
+
 export async function openInEditor(
-  projectPath: string,
-  servicePath: string,
+  project: Project,
+  repoName: string,
   editor?: string
 ) {
-  return invoke("call_backend", {
-    command: "openInEditor",
-    args: {
-      project: { workspacePath: projectPath },
-      repoName: servicePath,
-      editor,
-    },
-  })
+  return callBackend("openInEditor", { project, repo_name: repoName, editor })
 }
 
 export async function generateTiltfiles(project: Project, env: string) {
-  return invoke("call_backend", {
-    command: "generateTiltfiles",
-    args: { project, env },
-  })
+  const job = await callBackend("generateTiltfiles", { project, env })
+  return waitForJob(job.id)
 }
 
-export async function startTilt(project: Project, env: string) {
-  return invoke("call_backend", {
-    command: "startTilt",
-    args: { project, env },
-  })
+export async function resolveEnvironment(
+  project: Project,
+  env: string
+): Promise<Environment> {
+  return callBackend("resolveEnvironment", {
+    project,
+    env,
+  }) as Promise<Environment>
 }
 
-export async function stopTilt(project: Project, env: string) {
-  return invoke("call_backend", {
-    command: "stopTilt",
-    args: { project, env },
-  })
+export async function explainEnvironment(
+  project: Project,
+  env: string
+): Promise<ValueOrigin[]> {
+  return callBackend("explainEnvironment", { project, env })
 }
 
-export async function restartTilt(project: Project, env: string) {
-  return invoke("call_backend", {
-    command: "restartTilt",
-    args: { project, env },
-  })
+export async function validateInterpolation(
+  project: Project,
+  env: string
+): Promise<{ valid: boolean; errors: InterpolationError[] }> {
+  return callBackend("validateInterpolation", { project, env })
 }
 
-export async function getTiltState(project: Project, env: string) {
-  const response = (await invoke("call_backend", {
-    command: "reconcileTiltState",
-    args: { project, env },
-  })) as string
+export async function startTilt(
+  project: Project,
+  env: string,
+  onUpdate?: (job: Job) => void
+) {
+  const job = await callBackend("startTilt", { project, env })
+  return waitForJob(job.id, onUpdate)
+}
 
-  return typeof response === "string" && response.includes("status")
-    ? (JSON.parse(response) as TiltStatus)
-    : response
+export async function stopTilt(
+  project: Project,
+  env: string,
+  initiator?: StopInitiator
+) {
+  return callBackend("stopTilt", { project, env, initiator })
 }
 
-export async function getTiltLogs(
+export async function restartTilt(
   project: Project,
   env: string,
-  lines?: number
+  initiator?: StopInitiator
 ) {
-  return invoke("call_backend", {
-    command: "getTiltLogs",
-    args: { project, env, lines },
-  }) as Promise<string>
+  return callBackend("restartTilt", { project, env, initiator })
+}
+
+export async function getBuildMetrics(
+  project: Project,
+  env: string,
+  sessions?: number
+): Promise<ResourceStats[]> {
+  return callBackend("getBuildMetrics", { project, env, sessions })
+}
+
+export async function getSessionHistory(
+  project: Project,
+  env: string
+): Promise<SessionRecord[]> {
+  return callBackend("getSessionHistory", { project, env })
+}
+
+export async function getTiltState(
+  project: Project,
+  env: string
+): Promise<TiltStatus> {
+  return callBackend("reconcileTiltState", { project, env })
+}
+
+export async function getTiltLogs(
+  project: Project,
+  env: string,
+  lines?: number,
+  query: LogQuery = {}
+): Promise<TiltLog> {
+  return callBackend("getTiltLogs", { project, env, lines, ...query })
+}
+
+/** Results arrive as `log-search-batch` events carrying the returned id */
+export async function searchLogs(project: Project, query: LogSearchQuery) {
+  return callBackend("searchLogs", { project, ...query })
+}
+
+export async function listLogSessions(
+  project: Project,
+  env: string
+): Promise<LogSession[]> {
+  return callBackend("listLogSessions", { project, env })
 }
 
 export async function reorderServices(
@@ -160,11 +445,21 @@ export async function reorderServices(
   env: string,
   newOrder: string[]
 ) {
-  return invoke("reorderServices", { project, env, newOrder })
+  return callBackend("reorderServices", {
+    project,
+    env,
+    new_order: newOrder,
+  }) as Promise<Project>
 }
 
-export async function cloneRepo(url: string, path: string, branch?: string) {
-  return invoke("cloneRepo", { url, path, branch })
+export async function cloneRepo(
+  url: string,
+  path: string,
+  branch?: string,
+  onUpdate?: (job: Job) => void
+) {
+  const job = await callBackend("cloneRepo", { url, path, branch })
+  return waitForJob(job.id, onUpdate)
 }
 
 export function getRecentProjects() {
@@ -178,5 +473,7 @@ export function getRecentProjects() {
 }
 
 export function removeRecentProject(path: string) {
-  return invoke("remove_recent_project_cmd", { path })
+  return invoke("remove_recent_project_cmd", { path }).catch((error) => {
+    throw toBackendError(error)
+  })
 }
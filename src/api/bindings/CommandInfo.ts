@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A registered command with the TypeScript names of its types
+ */
+export type CommandInfo = { name: string, request: string, response: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Creates the project in a job, whose result is the new project
+ */
+export type CreateProject = { name: string, workspace_path: string, services_path: string, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Project } from "./Project";
+
+export type DiffEnvironments = { project: Project, source: string, target: string, };
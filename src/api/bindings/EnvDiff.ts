@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EnvVarChange } from "./EnvVarChange";
+import type { ServiceChange } from "./ServiceChange";
+
+export type EnvDiff = { source: string, target: string, shared_env: Array<EnvVarChange>, services: Array<ServiceChange>, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChangeKind } from "./ChangeKind";
+
+/**
+ * A single env var that differs between two environments.
+ * `added` means present in the source only, `removed` in the target only.
+ */
+export type EnvVarChange = { key: string, change: ChangeKind, source_value: string | null, target_value: string | null, };
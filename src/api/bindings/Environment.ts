@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SecretRef } from "./SecretRef";
+import type { Service } from "./Service";
+import type { ServiceOverride } from "./ServiceOverride";
+
+export type Environment = { shared_env: { [key in string]?: string }, services: Array<Service>, 
+/**
+ * Name of the environment this one inherits services and shared env from
+ */
+extends?: string | null, 
+/**
+ * Per-service patches applied on top of the inherited services
+ */
+overrides?: { [key in string]?: ServiceOverride } | null, 
+/**
+ * Shared env entries whose values are kept out of the project files
+ */
+secrets?: Array<SecretRef> | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Stable identifier of an error kind, safe for the frontend to match on
+ */
+export type ErrorCode = "io" | "not_found" | "permission_denied" | "already_exists" | "invalid_json" | "invalid_input" | "project_not_found" | "env_not_found" | "env_exists" | "env_running" | "service_not_found" | "service_exists" | "tilt_not_installed" | "git_failed" | "job_not_found" | "cancelled" | "unknown_command" | "internal";
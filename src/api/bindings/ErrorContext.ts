@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What an error was about, each part only when it applies
+ */
+export type ErrorContext = { env: string | null, service: string | null, path: string | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Project } from "./Project";
+
+export type GetBuildMetrics = { project: Project, env: string, 
+/**
+ * Only the most recent sessions, all kept sessions when not set
+ */
+sessions?: number, };
@@ -0,0 +1,41 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LogLevel } from "./LogLevel";
+import type { Project } from "./Project";
+
+export type GetTiltLogs = { project: Project, env: string, lines?: number, 
+/**
+ * Read forwards from this byte offset
+ */
+cursor?: number, 
+/**
+ * Read forwards from this line number, zero based
+ */
+from_line?: number, 
+/**
+ * Read the lines ending at this byte offset
+ */
+before?: number, 
+/**
+ * A past session from `list_log_sessions`, defaults to the current one
+ */
+session?: string, 
+/**
+ * Only lines from these resources
+ */
+resources?: Array<string>, 
+/**
+ * Only lines at or above this level, lines without a level are dropped
+ */
+level?: LogLevel, 
+/**
+ * RFC 3339 bounds on capture time
+ */
+since?: string, until?: string, 
+/**
+ * Case-insensitive substring of the line
+ */
+text?: string, 
+/**
+ * Regex matched against the line
+ */
+pattern?: string, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+export type Helm = { 
+/**
+ * `.` or `./<dir>` for a chart in the service's directory, anything else
+ * is passed to Tilt as is
+ */
+chart: string, values?: { [key in string]?: JsonValue } | null, namespace?: string | null, };
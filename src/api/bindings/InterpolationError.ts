@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A reference that could not be resolved, reported per env key
+ */
+export type InterpolationError = { service: string, key: string, message: string, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErrorCode } from "./ErrorCode";
+import type { ErrorContext } from "./ErrorContext";
+
+/**
+ * An error as it crosses IPC and the control socket
+ */
+export type IpcError = { code: ErrorCode, message: string, 
+/**
+ * What the user can do about it
+ */
+hint: string | null, context: ErrorContext, };
@@ -0,0 +1,36 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IpcError } from "./IpcError";
+import type { JobKind } from "./JobKind";
+import type { JobStatus } from "./JobStatus";
+import type { RepoProgress } from "./RepoProgress";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * An operation running in the background. IPC returns it as soon as it
+ * starts, every change after that is published as a `job` event.
+ */
+export type Job = { id: string, kind: JobKind, 
+/**
+ * What it works on, such as the repository URL
+ */
+title: string, workspace_path: string | null, env: string | null, status: JobStatus, 
+/**
+ * The step it is at
+ */
+message: string | null, 
+/**
+ * Steps done out of `total`, when the job knows how many there are
+ */
+done: number, total: number | null, 
+/**
+ * One entry per repository when it clones several
+ */
+repos: Array<RepoProgress>, started_at: string, finished_at: string | null, 
+/**
+ * Why it failed or that it was cancelled
+ */
+error: IpcError | null, 
+/**
+ * What it produced, such as the created project
+ */
+result: JsonValue | null, };
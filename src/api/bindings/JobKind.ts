@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a job does
+ */
+export type JobKind = "add_service" | "clone_repo" | "create_project" | "generate_tiltfiles" | "start_tilt" | "update_project";
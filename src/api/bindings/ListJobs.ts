@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Running jobs and the ones finished since the app started
+ */
+export type ListJobs = Record<string, never>;
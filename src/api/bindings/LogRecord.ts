@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LogLevel } from "./LogLevel";
+import type { LogStream } from "./LogStream";
+
+/**
+ * One captured log line split into its parts
+ */
+export type LogRecord = { 
+/**
+ * Capture time, missing for logs written before records were stamped
+ */
+timestamp: string | null, resource: string | null, level: LogLevel | null, stream: LogStream | null, 
+/**
+ * The line as Tilt printed it
+ */
+line: string, 
+/**
+ * The line without the resource prefix
+ */
+message: string, };
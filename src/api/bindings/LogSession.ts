@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A Tilt session's logs: the archived parts plus the live file for the current session
+ */
+export type LogSession = { id: string, started_at: string | null, parts: number, size_bytes: number, current: boolean, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Percentiles = { count: number, p50: number, p90: number, p99: number, max: number, mean: number, };
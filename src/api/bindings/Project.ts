@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Environment } from "./Environment";
+import type { ProjectInfo } from "./ProjectInfo";
+
+export type Project = { project: ProjectInfo, environments: { [key in string]?: Environment }, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Tilt } from "./Tilt";
+
+export type ProjectInfo = { 
+/**
+ * Stable across renames and moves, keys the project's stored secrets.
+ * Projects created before it existed get one when a secret is set.
+ */
+id?: string | null, name: string, workspace_path: string, tilt: Tilt, services_path?: string | null, };
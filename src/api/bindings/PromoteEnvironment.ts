@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PromoteSelection } from "./PromoteSelection";
+
+export type PromoteEnvironment = { workspace_path: string, source: string, target: string, selection: PromoteSelection, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The parts of a diff to apply to the target environment
+ */
+export type PromoteSelection = { 
+/**
+ * Shared env keys to copy (or remove) from the source
+ */
+shared_env?: Array<string>, 
+/**
+ * Services to copy (or remove) as a whole
+ */
+services?: Array<string>, 
+/**
+ * Individual service env keys to copy (or remove), keyed by service name
+ */
+service_env?: { [key in string]?: Array<string> }, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where one repository of a multi-repo clone is at
+ */
+export type RepoStatus = "pending" | "cloning" | "cloned" | "failed" | "cancelled";
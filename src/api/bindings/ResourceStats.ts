@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Percentiles } from "./Percentiles";
+import type { TrendPoint } from "./TrendPoint";
+
+/**
+ * Aggregates of one resource over the requested sessions
+ */
+export type ResourceStats = { resource: string, updates: number, failures: number, build_duration: Percentiles, time_to_ready: Percentiles, 
+/**
+ * Oldest session first
+ */
+trend: Array<TrendPoint>, 
+/**
+ * Change of the latest session's median against the average of the
+ * earlier ones, in percent. Positive means builds got slower.
+ */
+trend_change_pct: number | null, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Project } from "./Project";
+import type { StopInitiator } from "./StopInitiator";
+
+export type RestartTilt = { project: Project, env: string, initiator?: StopInitiator, };
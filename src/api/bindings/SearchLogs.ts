@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LogLevel } from "./LogLevel";
+import type { Project } from "./Project";
+
+export type SearchLogs = { project: Project, 
+/**
+ * Every environment of the project when not set
+ */
+env?: string, context?: number, batch_size?: number, 
+/**
+ * Only lines from these resources
+ */
+resources?: Array<string>, 
+/**
+ * Only lines at or above this level, lines without a level are dropped
+ */
+level?: LogLevel, 
+/**
+ * RFC 3339 bounds on capture time
+ */
+since?: string, until?: string, 
+/**
+ * Case-insensitive substring of the line
+ */
+text?: string, 
+/**
+ * Regex matched against the line
+ */
+pattern?: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A secret as reported to the frontend, never carrying the value itself
+ */
+export type SecretEntry = { service: string | null, key: string, file: string | null, has_value: boolean, value: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An env entry marked secret. The value lives in the local encrypted store,
+ * or in a gitignored dotenv file when `file` is set.
+ */
+export type SecretRef = { key: string, file?: string | null, };
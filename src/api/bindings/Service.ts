@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Docker } from "./Docker";
+import type { Helm } from "./Helm";
+import type { K8s } from "./K8s";
+import type { Kustomize } from "./Kustomize";
+import type { Repo } from "./Repo";
+import type { SecretRef } from "./SecretRef";
+
+export type Service = { name: string, path?: string | null, port: number, enabled: boolean, repo?: Repo | null, docker?: Docker | null, k8s?: K8s | null, env?: { [key in string]?: string } | null, depends_on?: Array<string> | null, helm?: Helm | null, kustomize?: Kustomize | null, replicas?: number | null, 
+/**
+ * Env entries whose values are kept out of the project files
+ */
+secrets?: Array<SecretRef> | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChangeKind } from "./ChangeKind";
+import type { EnvVarChange } from "./EnvVarChange";
+import type { Service } from "./Service";
+
+export type ServiceChange = { name: string, change: ChangeKind, 
+/**
+ * Top-level service fields that differ, excluding `env`
+ */
+fields: Array<string>, env: Array<EnvVarChange>, source: Service | null, target: Service | null, };
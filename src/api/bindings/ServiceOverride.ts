@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * Partial service definition layered over an inherited service
+ */
+export type ServiceOverride = { port?: number | null, enabled?: boolean | null, env?: { [key in string]?: string } | null, branch?: string | null, helm_values?: { [key in string]?: JsonValue } | null, replicas?: number | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Project } from "./Project";
+
+/**
+ * Result of removing a service, with the services that depended on it
+ */
+export type ServiceRemoval = { project: Project, dependents: Array<string>, };
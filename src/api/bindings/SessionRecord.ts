@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StopInitiator } from "./StopInitiator";
+
+/**
+ * One Tilt run of an environment
+ */
+export type SessionRecord = { 
+/**
+ * Same id as the session's logs
+ */
+id: string, started_at: string, stopped_at: string | null, tilt_version: string | null, 
+/**
+ * Hash of every generated file under `tilt/<env>` at start
+ */
+tiltfile_hash: string | null, enabled_services: Array<string>, exit_code: number | null, 
+/**
+ * Restarts in a row that led to this session, 0 for a fresh start
+ */
+restart_count: number, stopped_by: StopInitiator | null, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SetSecret = { workspace_path: string, env: string, service?: string, key: string, value: string, file?: string, };
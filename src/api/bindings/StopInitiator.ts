@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Who ended a Tilt session
+ */
+export type StopInitiator = "user" | "tray" | "watchdog" | "crash";
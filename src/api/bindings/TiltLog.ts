@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LogRecord } from "./LogRecord";
+
+/**
+ * A page of log lines, one entry per line
+ */
+export type TiltLog = { logs: Array<string>, 
+/**
+ * The same lines parsed into resource, level, stream and capture time
+ */
+records: Array<LogRecord>, 
+/**
+ * Byte offset of the first returned line, pass as `before` to page backwards
+ */
+cursor: number, 
+/**
+ * Byte offset after the last returned line, pass as `cursor` to page forwards
+ */
+next_cursor: number, 
+/**
+ * Size of the log in bytes when it was read
+ */
+size: number, };
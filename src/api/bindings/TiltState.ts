@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type TiltState = { status: string, pid: number | null, 
+/**
+ * Log session of the current or most recent run
+ */
+session: string | null, 
+/**
+ * Port of the Tilt API and web UI, every environment gets its own
+ */
+port: number | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Median build time of a resource in one session
+ */
+export type TrendPoint = { session: string, builds: number, failures: number, median_ms: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ValueKind } from "./ValueKind";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * A single effective value and the environment it came from
+ */
+export type ValueOrigin = { 
+/**
+ * Dotted path such as `shared_env.LOG_LEVEL` or `services.api.port`
+ */
+key: string, value: JsonValue, env: string, kind: ValueKind, };
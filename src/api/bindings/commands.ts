@@ -0,0 +1,111 @@
+// Generated by `npm run bindings`, do not edit
+
+import type { AddService } from "./AddService"
+import type { CancelJob } from "./CancelJob"
+import type { CheckTiltInstalled } from "./CheckTiltInstalled"
+import type { CloneEnvironment } from "./CloneEnvironment"
+import type { CloneRepo } from "./CloneRepo"
+import type { CommandInfo } from "./CommandInfo"
+import type { CreateEnvironment } from "./CreateEnvironment"
+import type { CreateProject } from "./CreateProject"
+import type { DeleteEnvironment } from "./DeleteEnvironment"
+import type { DiffEnvironments } from "./DiffEnvironments"
+import type { DiscoverServices } from "./DiscoverServices"
+import type { EnvDiff } from "./EnvDiff"
+import type { Environment } from "./Environment"
+import type { ExecutableVersion } from "./ExecutableVersion"
+import type { ExplainEnvironment } from "./ExplainEnvironment"
+import type { GenerateTiltfiles } from "./GenerateTiltfiles"
+import type { GetBuildMetrics } from "./GetBuildMetrics"
+import type { GetJob } from "./GetJob"
+import type { GetJobLogs } from "./GetJobLogs"
+import type { GetSessionHistory } from "./GetSessionHistory"
+import type { GetTiltLogs } from "./GetTiltLogs"
+import type { GetTiltState } from "./GetTiltState"
+import type { InitializeExistingProject } from "./InitializeExistingProject"
+import type { InterpolationReport } from "./InterpolationReport"
+import type { IsValidProject } from "./IsValidProject"
+import type { Job } from "./Job"
+import type { ListCommands } from "./ListCommands"
+import type { ListJobs } from "./ListJobs"
+import type { ListLogSessions } from "./ListLogSessions"
+import type { ListSecrets } from "./ListSecrets"
+import type { LogSession } from "./LogSession"
+import type { OpenInEditor } from "./OpenInEditor"
+import type { OpenProject } from "./OpenProject"
+import type { Project } from "./Project"
+import type { ProjectValidity } from "./ProjectValidity"
+import type { PromoteEnvironment } from "./PromoteEnvironment"
+import type { ReconcileTiltState } from "./ReconcileTiltState"
+import type { RemoveSecret } from "./RemoveSecret"
+import type { RemoveService } from "./RemoveService"
+import type { RenameEnvironment } from "./RenameEnvironment"
+import type { RenameService } from "./RenameService"
+import type { ReorderServices } from "./ReorderServices"
+import type { ResolveEnvironment } from "./ResolveEnvironment"
+import type { ResourceStats } from "./ResourceStats"
+import type { RestartTilt } from "./RestartTilt"
+import type { SearchLogs } from "./SearchLogs"
+import type { SearchStarted } from "./SearchStarted"
+import type { SecretEntry } from "./SecretEntry"
+import type { Service } from "./Service"
+import type { ServiceRemoval } from "./ServiceRemoval"
+import type { SessionRecord } from "./SessionRecord"
+import type { SetSecret } from "./SetSecret"
+import type { StartTilt } from "./StartTilt"
+import type { State } from "./State"
+import type { StopTilt } from "./StopTilt"
+import type { TiltInstallation } from "./TiltInstallation"
+import type { TiltLog } from "./TiltLog"
+import type { TitleStatus } from "./TitleStatus"
+import type { UpdateProject } from "./UpdateProject"
+import type { UpdateService } from "./UpdateService"
+import type { ValidateExecutablePath } from "./ValidateExecutablePath"
+import type { ValidateInterpolation } from "./ValidateInterpolation"
+import type { ValueOrigin } from "./ValueOrigin"
+
+export type Commands = {
+  listCommands: { request: ListCommands; response: Array<CommandInfo> }
+  checkTiltInstalled: { request: CheckTiltInstalled; response: TiltInstallation }
+  validateExecutablePath: { request: ValidateExecutablePath; response: ExecutableVersion }
+  generateTiltfiles: { request: GenerateTiltfiles; response: Job }
+  resolveEnvironment: { request: ResolveEnvironment; response: Environment }
+  explainEnvironment: { request: ExplainEnvironment; response: Array<ValueOrigin> }
+  validateInterpolation: { request: ValidateInterpolation; response: InterpolationReport }
+  startTilt: { request: StartTilt; response: Job }
+  stopTilt: { request: StopTilt; response: null }
+  restartTilt: { request: RestartTilt; response: null }
+  getTiltState: { request: GetTiltState; response: State }
+  reconcileTiltState: { request: ReconcileTiltState; response: TitleStatus }
+  getBuildMetrics: { request: GetBuildMetrics; response: Array<ResourceStats> }
+  getTiltLogs: { request: GetTiltLogs; response: TiltLog }
+  listLogSessions: { request: ListLogSessions; response: Array<LogSession> }
+  getSessionHistory: { request: GetSessionHistory; response: Array<SessionRecord> }
+  searchLogs: { request: SearchLogs; response: SearchStarted }
+  cloneRepo: { request: CloneRepo; response: Job }
+  createProject: { request: CreateProject; response: Job }
+  openProject: { request: OpenProject; response: Project }
+  isValidProject: { request: IsValidProject; response: ProjectValidity }
+  initializeExistingProject: { request: InitializeExistingProject; response: Project }
+  discoverServices: { request: DiscoverServices; response: Array<Service> }
+  updateProject: { request: UpdateProject; response: Job }
+  updateService: { request: UpdateService; response: Project }
+  addService: { request: AddService; response: Job }
+  removeService: { request: RemoveService; response: ServiceRemoval }
+  renameService: { request: RenameService; response: Project }
+  createEnvironment: { request: CreateEnvironment; response: Project }
+  cloneEnvironment: { request: CloneEnvironment; response: Project }
+  renameEnvironment: { request: RenameEnvironment; response: Project }
+  deleteEnvironment: { request: DeleteEnvironment; response: Project }
+  diffEnvironments: { request: DiffEnvironments; response: EnvDiff }
+  promoteEnvironment: { request: PromoteEnvironment; response: Project }
+  setSecret: { request: SetSecret; response: Project }
+  removeSecret: { request: RemoveSecret; response: Project }
+  listSecrets: { request: ListSecrets; response: Array<SecretEntry> }
+  openInEditor: { request: OpenInEditor; response: null }
+  reorderServices: { request: ReorderServices; response: Project }
+  listJobs: { request: ListJobs; response: Array<Job> }
+  getJob: { request: GetJob; response: Job }
+  getJobLogs: { request: GetJobLogs; response: Array<string> }
+  cancelJob: { request: CancelJob; response: Job }
+}
@@ -0,0 +1,51 @@
+import type { ErrorCode } from "./bindings/ErrorCode"
+import type { ErrorContext } from "./bindings/ErrorContext"
+import type { IpcError } from "./bindings/IpcError"
+
+export type { ErrorCode, ErrorContext, IpcError }
+
+/** A failed backend command, with the code to match on instead of the message */
+export class BackendError extends Error {
+  readonly code: ErrorCode
+  /** What the user can do about it */
+  readonly hint: string | null
+  readonly context: ErrorContext
+
+  constructor(error: IpcError) {
+    super(error.message)
+    this.name = "BackendError"
+    this.code = error.code
+    this.hint = error.hint
+    this.context = error.context
+  }
+
+  /** The message followed by the hint, ready to show to the user */
+  toString() {
+    return this.hint ? `${this.message}. ${this.hint}` : this.message
+  }
+}
+
+function isIpcError(value: unknown): value is IpcError {
+  return (
+    typeof value === "object" &&
+    value !== null &&
+    "code" in value &&
+    "message" in value
+  )
+}
+
+/** Whatever a Tauri command rejected with, as a `BackendError` */
+export function toBackendError(error: unknown): BackendError {
+  if (error instanceof BackendError) {
+    return error
+  }
+  if (isIpcError(error)) {
+    return new BackendError(error)
+  }
+  return new BackendError({
+    code: "internal",
+    message: error instanceof Error ? error.message : String(error),
+    hint: null,
+    context: { env: null, service: null, path: null },
+  })
+}
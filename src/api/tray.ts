@@ -2,6 +2,8 @@ import { invoke } from "@tauri-apps/api/core"
 
 import type { Project } from "@/types/project"
 
+import { toBackendError } from "./errors"
+
 export interface TiltState {
   status: string
   is_running: boolean
@@ -9,7 +11,8 @@ export interface TiltState {
 }
 
 /**
- * Update the system tray menu with current project, environment, and Tilt state
+ * Update the system tray menu with current project, environment, and Tilt state.
+ * Rejects with a `BackendError`.
  */
 export async function updateTrayMenu(
   project: Project | null,
@@ -20,5 +23,7 @@ export async function updateTrayMenu(
     project,
     env,
     tiltState,
+  }).catch((error) => {
+    throw toBackendError(error)
   })
 }
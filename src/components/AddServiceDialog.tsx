@@ -1,5 +1,6 @@
 import { useEffect, useMemo, useState } from "react"
-import { cloneRepo } from "@/api/api"
+import { cancelJob, cloneRepo, type Job } from "@/api/api"
+import { toBackendError } from "@/api/errors"
 import { Download, GitBranch, Loader2, Plus, Trash2 } from "lucide-react"
 
 import { Service } from "@/types/project"
@@ -62,6 +63,7 @@ export default function AddServiceDialog({
   const [shouldClone, setShouldClone] = useState(true)
   const [isCloning, setIsCloning] = useState(false)
   const [cloneError, setCloneError] = useState<string>("")
+  const [cloneJob, setCloneJob] = useState<Job | null>(null)
   const [hasRepo, setHasRepo] = useState<boolean>(false)
 
   // Initialize form when editing
@@ -142,13 +144,24 @@ export default function AddServiceDialog({
       setIsCloning(true)
       try {
         const targetPath = servicePath
-        await cloneRepo(service.repo.url, targetPath, service.repo.branch)
-      } catch (error: any) {
-        setCloneError(`Failed to clone repository: ${error}`)
-        setIsCloning(false)
+        await cloneRepo(
+          service.repo.url,
+          targetPath,
+          service.repo.branch,
+          setCloneJob
+        )
+      } catch (cause) {
+        const error = toBackendError(cause)
+        setCloneError(
+          error.code === "cancelled"
+            ? "Clone cancelled"
+            : `Failed to clone repository: ${error}`
+        )
         return
+      } finally {
+        setCloneJob(null)
+        setIsCloning(false)
       }
-      setIsCloning(false)
     }
 
     const env = envVars.reduce(
@@ -363,6 +376,11 @@ export default function AddServiceDialog({
                     Clone repository when adding service
                   </Label>
                 </div>
+                {cloneJob?.message && (
+                  <p className="text-sm text-slate-600 dark:text-slate-400">
+                    {cloneJob.message}
+                  </p>
+                )}
                 {cloneError && (
                   <p className="text-sm text-red-600 dark:text-red-400">
                     {cloneError}
@@ -691,8 +709,12 @@ export default function AddServiceDialog({
         </div>
 
         <DialogFooter>
-          <Button variant="outline" onClick={handleCancel} disabled={isCloning}>
-            Cancel
+          <Button
+            variant="outline"
+            onClick={cloneJob ? () => cancelJob(cloneJob.id) : handleCancel}
+            disabled={isCloning && !cloneJob}
+          >
+            {cloneJob ? "Cancel Clone" : "Cancel"}
           </Button>
           <Button
             onClick={handleSubmit}
@@ -62,11 +62,7 @@ export default function ConfigureExistingProject({
 
     try {
       const project = await initializeExistingProject(projectPath, servicesPath)
-      const normalizedProject: Project =
-        typeof project === "string"
-          ? (JSON.parse(project) as Project)
-          : (project as Project)
-      onInitialized(normalizedProject)
+      onInitialized(project)
     } catch (err) {
       setError(
         err instanceof Error ? err.message : "Failed to initialize project"
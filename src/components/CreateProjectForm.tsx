@@ -1,4 +1,6 @@
 import { useState } from "react"
+import { cancelJob, createProject, type Job } from "@/api/api"
+import { toBackendError } from "@/api/errors"
 import { invoke } from "@tauri-apps/api/core"
 import { message, open } from "@tauri-apps/plugin-dialog"
 import { ArrowLeft, FolderOpen, Loader2 } from "lucide-react"
@@ -30,6 +32,7 @@ export default function CreateProjectForm({
   const [servicesPath, setServicesPath] = useState("repos")
   const [loading, setLoading] = useState(false)
   const [error, setError] = useState("")
+  const [job, setJob] = useState<Job | null>(null)
 
   const handleSelectWorkspace = async () => {
     try {
@@ -61,16 +64,12 @@ export default function CreateProjectForm({
     setLoading(true)
 
     try {
-      const _project = await invoke("call_backend", {
-        command: "createProject",
-        args: {
-          name: projectName,
-          workspace_path: workspacePath,
-          services_path: servicesPath,
-        },
-      })
-
-      const project: Project = JSON.parse(_project as string) as Project
+      const project = await createProject(
+        projectName,
+        workspacePath,
+        servicesPath,
+        setJob
+      )
 
       await invoke("add_recent_project_cmd", {
         name: project.project.name,
@@ -83,14 +82,18 @@ export default function CreateProjectForm({
       })
 
       onProjectCreated(project)
-    } catch (error: any) {
-      setError(error?.toString() || "Failed to create project")
+    } catch (cause) {
+      const error = toBackendError(cause)
+      setError(error.toString())
       console.error("Failed to create project:", error)
-      await message(`Failed to create project: ${error}`, {
-        title: "Tilt Orchestrator",
-        kind: "error",
-      })
+      if (error.code !== "cancelled") {
+        await message(`Failed to create project: ${error}`, {
+          title: "Tilt Orchestrator",
+          kind: "error",
+        })
+      }
     } finally {
+      setJob(null)
       setLoading(false)
     }
   }
@@ -170,6 +173,18 @@ export default function CreateProjectForm({
                 </p>
               </div>
 
+              {job?.message && (
+                <div className="space-y-1 text-sm text-slate-600 dark:text-slate-400">
+                  <p>{job.message}</p>
+                  {job.repos.map((repo) => (
+                    <p key={repo.service} className="text-xs">
+                      {repo.service}: {repo.status}
+                      {repo.error && ` (${repo.error})`}
+                    </p>
+                  ))}
+                </div>
+              )}
+
               <div className="flex gap-3">
                 <Button
                   type="submit"
@@ -179,7 +194,11 @@ export default function CreateProjectForm({
                   {loading && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
                   Create Project
                 </Button>
-                <Button type="button" variant="outline" onClick={onBack}>
+                <Button
+                  type="button"
+                  variant="outline"
+                  onClick={job ? () => cancelJob(job.id) : onBack}
+                >
                   Cancel
                 </Button>
               </div>
@@ -62,17 +62,8 @@ export default function LandingScreen({
       if (tiltInstallation) {
         installation = tiltInstallation
       } else {
-        const response: any = await checkTiltInstalled()
-        if (typeof response === "string") {
-          installation = JSON.parse(response)
-          setTiltInstallation(installation)
-        } else {
-          installation = {
-            path: "",
-            installed: false,
-            version: "",
-          }
-        }
+        installation = await checkTiltInstalled()
+        setTiltInstallation(installation)
       }
 
       if (installation.installed) {
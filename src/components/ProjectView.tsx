@@ -3,13 +3,13 @@ import {
   generateTiltfiles,
   getTiltLogs,
   getTiltState,
+  openInEditor,
   // removeRecentProject,
   startTilt,
   stopTilt,
 } from "@/api/api"
 import { useAppState } from "@/providers/AppStateProvider"
 import { useTrayIcon } from "@/providers/TrayIconProvider"
-import { invoke } from "@tauri-apps/api/core"
 import { listen } from "@tauri-apps/api/event"
 import { ask, message } from "@tauri-apps/plugin-dialog"
 import { openUrl } from "@tauri-apps/plugin-opener"
@@ -32,7 +32,7 @@ import {
 } from "lucide-react"
 
 import { Project, Service } from "@/types/project"
-import { TiltLog, TiltStatus } from "@/types/tilt"
+import { TrayTiltAction } from "@/types/tilt"
 
 import { Badge } from "./ui/badge"
 import { Button } from "./ui/button"
@@ -48,6 +48,8 @@ import { Label } from "./ui/label"
 
 interface ProjectViewProps {
   project: Project
+  /** Environment picked outside the view, e.g. from the tray */
+  requestedEnv?: { name: string } | null
   onBack: () => void
   onEdit: () => void
 }
@@ -55,12 +57,15 @@ const TILT_STATUS_INTERVAL = 3000
 
 export default function ProjectView({
   project,
+  requestedEnv,
   onBack,
   onEdit,
 }: ProjectViewProps) {
   const { state: appState } = useAppState() // await invoke<AppState>('get_app_state');
   const [selectedEnv, setSelectedEnv] = useState<string>(
-    Object.keys(project.environments)[0] || "dev"
+    requestedEnv && project.environments[requestedEnv.name]
+      ? requestedEnv.name
+      : Object.keys(project.environments)[0] || "dev"
   )
   const [tiltStatus, setTiltStatus] = useState<
     "stopped" | "running" | "starting" | "stopping"
@@ -138,10 +143,8 @@ export default function ProjectView({
     try {
       setIsCheckingStatus(true)
       const state = await getTiltState(project, selectedEnv)
-      const tiltState =
-        typeof state === "string" ? "stopped" : (state as TiltStatus).status
 
-      setTiltStatus(tiltState as any | "running")
+      setTiltStatus(state.status as any | "running")
     } catch (error) {
       console.error("", error)
       await message(`Failed to get Tilt status: ${error}`, {
@@ -217,7 +220,7 @@ export default function ProjectView({
   const fetchLogs = async () => {
     try {
       const logs = await getTiltLogs(project, selectedEnv, 500)
-      setTiltLogs((JSON.parse(logs) as TiltLog).logs)
+      setTiltLogs(logs.logs)
 
       applyTerminalAutoScroll()
     } catch (error) {
@@ -320,14 +323,7 @@ export default function ProjectView({
       const editor = appState.preferences.default_editor || undefined
 
       const servicePath = service.path || service.name
-      await invoke("call_backend", {
-        command: "openInEditor",
-        args: {
-          project,
-          repo_name: servicePath,
-          editor,
-        },
-      })
+      await openInEditor(project, servicePath, editor)
     } catch (error) {
       console.error("Failed to open in editor:", error)
       await message(`Failed to open service in editor: ${error}`, {
@@ -353,6 +349,13 @@ export default function ProjectView({
     }
   }, [tiltStatus])
 
+  // Follow environment switches made from the tray
+  useEffect(() => {
+    if (requestedEnv && project.environments[requestedEnv.name]) {
+      setSelectedEnv(requestedEnv.name)
+    }
+  }, [requestedEnv])
+
   // Handle terminal autoscroll controller
   useEffect(() => {
     terminalAutoScrollRef.current = terminalAutoScroll
@@ -388,17 +391,30 @@ export default function ProjectView({
   }, [selectedEnv])
 
   const initListeners = async () => {
-    const startTiltListener = await listen("start-tilt", async (_) => {
-      await handleStartTilt()
-    })
+    // The tray starts, stops and restarts Tilt itself and reports the outcome
+    const tiltActionListener = await listen<TrayTiltAction>(
+      "tray-tilt-action",
+      async (event) => {
+        const result = event.payload
+        if (
+          result.workspace_path !== project.project.workspace_path ||
+          result.env !== selectedEnv
+        ) {
+          return
+        }
 
-    const stopTiltListener = await listen("stop-tilt", async (_) => {
-      await handleStopTilt()
-    })
+        setTiltStatus(result.status as typeof tiltStatus)
 
-    const restartTiltListener = await listen("restart-tilt", async (_) => {
-      await handleRestartTilt()
-    })
+        if (result.error) {
+          await message(`Failed to ${result.action} Tilt: ${result.error}`, {
+            title: "Tilt",
+            kind: "error",
+          })
+        } else if (result.status === "running") {
+          initTiltCheckerInterval()
+        }
+      }
+    )
 
     const openServiceInEditor = await listen(
       "open-service-in-editor",
@@ -419,9 +435,7 @@ export default function ProjectView({
     // });
 
     return [
-      startTiltListener,
-      stopTiltListener,
-      restartTiltListener,
+      tiltActionListener,
       openServiceInEditor,
       // disableService,
     ]
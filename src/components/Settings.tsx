@@ -1,5 +1,6 @@
 import { useEffect, useState } from "react"
 import { validateExecutablePath } from "@/api/api"
+import { toBackendError } from "@/api/errors"
 import { invoke } from "@tauri-apps/api/core"
 import {
   ArrowLeft,
@@ -88,22 +89,7 @@ export default function Settings({ onBack }: SettingsProps) {
 
     setValidatingTilt(true)
     try {
-      const rawResult = await validateExecutablePath(path)
-      let result: any
-
-      if (typeof rawResult === "string") {
-        try {
-          result = JSON.parse(rawResult)
-        } catch (parseError: any) {
-          // Surface parsing issues to the existing error handler
-          throw new Error(
-            `Failed to parse tilt validation result: ${String(rawResult)}`
-          )
-        }
-      } else {
-        // Assume a non-string response is already a parsed object
-        result = rawResult
-      }
+      const result = await validateExecutablePath(path)
       setTiltValidation({ valid: true, version: result.version })
       setCurrentTiltVersion(result.version)
       return { valid: true, version: result.version }
@@ -150,7 +136,7 @@ export default function Settings({ onBack }: SettingsProps) {
       setSaveMessage("Settings saved successfully!")
       setTimeout(() => setSaveMessage(""), 3000)
     } catch (error: any) {
-      setSaveMessage(`Failed to save: ${error.toString()}`)
+      setSaveMessage(`Failed to save: ${toBackendError(error)}`)
     } finally {
       setSaving(false)
     }
@@ -15,8 +15,7 @@ const TiltControls: React.FC<Props> = ({ project, env }) => {
   useEffect(() => {
     const interval = setInterval(async () => {
       const state = await getTiltState(project, env)
-      const parsed = typeof state === "string" ? JSON.parse(state) : state
-      setStatus(parsed.tilt.status)
+      setStatus(state.status)
     }, 2000)
     return () => clearInterval(interval)
   }, [project, env])
@@ -1,18 +1,24 @@
 import { invoke } from "@tauri-apps/api/core"
+import { listen } from "@tauri-apps/api/event"
 import { beforeEach, describe, expect, it, vi } from "vitest"
 
 import {
+  cancelJob,
   createProject,
   generateTiltfiles,
   getTiltLogs,
   getTiltState,
+  listCommands,
   openProject,
   removeRecentProject,
   startTilt,
   stopTilt,
   updateProject,
   updateService,
+  waitForJob,
+  type Job,
 } from "../api/api"
+import { BackendError } from "../api/errors"
 import type { Project } from "../types/project"
 
 vi.mock("@tauri-apps/api/core")
@@ -28,7 +34,7 @@ describe("API Functions", () => {
       workspace_path: "/test/workspace",
       services_path: "repos",
       tilt: {
-        mode: "root",
+        mode: "Root",
       },
     },
     environments: {
@@ -39,9 +45,28 @@ describe("API Functions", () => {
     },
   }
 
+  const job = (status: Job["status"], result: unknown = null): Job => ({
+    id: "job-1",
+    kind: "create_project",
+    title: "test-project",
+    workspace_path: null,
+    env: null,
+    status,
+    message: null,
+    done: 0,
+    total: null,
+    repos: [],
+    started_at: "2026-01-01T00:00:00Z",
+    finished_at: null,
+    error: null,
+    result: result as Job["result"],
+  })
+
   describe("createProject", () => {
     it("should create a project with correct parameters", async () => {
-      vi.mocked(invoke).mockResolvedValue(mockProject)
+      vi.mocked(invoke)
+        .mockResolvedValueOnce(job("running"))
+        .mockResolvedValueOnce(job("succeeded", mockProject))
 
       const result = await createProject(
         "test-project",
@@ -61,6 +86,76 @@ describe("API Functions", () => {
     })
   })
 
+  describe("jobs", () => {
+    it("should resolve once the job reports it finished", async () => {
+      let onEvent: (event: { payload: Job }) => void = () => {}
+      vi.mocked(listen).mockImplementationOnce(async (_, handler) => {
+        onEvent = handler as typeof onEvent
+        return () => {}
+      })
+      vi.mocked(invoke).mockResolvedValueOnce(job("running"))
+      const updates: Job["status"][] = []
+
+      const waiting = waitForJob("job-1", (update) =>
+        updates.push(update.status)
+      )
+      // The job as it was when the listener went in place
+      await vi.waitFor(() => expect(updates).toEqual(["running"]))
+      onEvent({ payload: { ...job("running"), id: "job-2" } })
+      onEvent({ payload: job("succeeded", "done") })
+
+      expect((await waiting).result).toBe("done")
+      expect(updates).toEqual(["running", "succeeded"])
+    })
+
+    it("should reject when the job is cancelled", async () => {
+      vi.mocked(invoke)
+        .mockResolvedValueOnce(job("running"))
+        .mockResolvedValueOnce({
+          ...job("cancelled"),
+          error: {
+            code: "cancelled",
+            message: "Cancelled",
+            hint: null,
+            context: { env: null, service: null, path: null },
+          },
+        })
+
+      const error = await startTilt(mockProject, "dev").catch((e) => e)
+
+      expect(error).toBeInstanceOf(BackendError)
+      expect(error.code).toBe("cancelled")
+    })
+
+    it("should cancel a job by id", async () => {
+      vi.mocked(invoke).mockResolvedValue(job("running"))
+
+      await cancelJob("job-1")
+
+      expect(invoke).toHaveBeenCalledWith("call_backend", {
+        command: "cancelJob",
+        args: { job_id: "job-1" },
+      })
+    })
+  })
+
+  describe("listCommands", () => {
+    it("should return the registered commands as values", async () => {
+      const commands = [
+        { name: "openProject", request: "OpenProject", response: "Project" },
+      ]
+      vi.mocked(invoke).mockResolvedValue(commands)
+
+      const result = await listCommands()
+
+      expect(invoke).toHaveBeenCalledWith("call_backend", {
+        command: "listCommands",
+        args: {},
+      })
+      expect(result).toEqual(commands)
+    })
+  })
+
   describe("openProject", () => {
     it("should open a project with workspace path", async () => {
       vi.mocked(invoke).mockResolvedValue(mockProject)
@@ -73,11 +168,31 @@ describe("API Functions", () => {
       })
       expect(result).toEqual(mockProject)
     })
+
+    it("should reject with the backend's error code", async () => {
+      vi.mocked(invoke).mockRejectedValue({
+        code: "project_not_found",
+        message: "No project found at /missing",
+        hint: "Open the folder that contains project.json",
+        context: { env: null, service: null, path: "/missing" },
+      })
+
+      const error = await openProject("/missing").catch((e) => e)
+
+      expect(error).toBeInstanceOf(BackendError)
+      expect(error.code).toBe("project_not_found")
+      expect(error.context.path).toBe("/missing")
+      expect(String(error)).toBe(
+        "No project found at /missing. Open the folder that contains project.json"
+      )
+    })
   })
 
   describe("updateProject", () => {
     it("should update a project", async () => {
-      vi.mocked(invoke).mockResolvedValue(mockProject)
+      vi.mocked(invoke)
+        .mockResolvedValueOnce(job("running"))
+        .mockResolvedValueOnce(job("succeeded", mockProject))
 
       const result = await updateProject("/test/workspace", mockProject)
 
@@ -120,7 +235,7 @@ describe("API Functions", () => {
   describe("Tilt operations", () => {
     describe("startTilt", () => {
       it("should start Tilt with project and environment", async () => {
-        vi.mocked(invoke).mockResolvedValue(undefined)
+        vi.mocked(invoke).mockResolvedValue(job("succeeded"))
 
         await startTilt(mockProject, "dev")
 
@@ -170,7 +285,13 @@ describe("API Functions", () => {
 
     describe("getTiltLogs", () => {
       it("should get Tilt logs with default limit", async () => {
-        const mockLogs = JSON.stringify({ logs: ["log1", "log2"] })
+        const mockLogs = {
+          logs: ["log1", "log2"],
+          records: [],
+          cursor: 0,
+          next_cursor: 12,
+          size: 12,
+        }
         vi.mocked(invoke).mockResolvedValue(mockLogs)
 
         const result = await getTiltLogs(mockProject, "dev")
@@ -187,7 +308,13 @@ describe("API Functions", () => {
       })
 
       it("should get Tilt logs with custom limit", async () => {
-        const mockLogs = JSON.stringify({ logs: ["log1"] })
+        const mockLogs = {
+          logs: ["log1"],
+          records: [],
+          cursor: 0,
+          next_cursor: 5,
+          size: 5,
+        }
         vi.mocked(invoke).mockResolvedValue(mockLogs)
 
         await getTiltLogs(mockProject, "dev", 100)
@@ -205,7 +332,7 @@ describe("API Functions", () => {
 
     describe("generateTiltfiles", () => {
       it("should generate Tiltfiles", async () => {
-        vi.mocked(invoke).mockResolvedValue(undefined)
+        vi.mocked(invoke).mockResolvedValue(job("succeeded"))
 
         await generateTiltfiles(mockProject, "dev")
 
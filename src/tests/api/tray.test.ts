@@ -17,7 +17,7 @@ describe("Tray API Functions", () => {
       workspace_path: "/test/workspace",
       services_path: "repos",
       tilt: {
-        mode: "root",
+        mode: "Root",
       },
     },
     environments: {
@@ -19,7 +19,7 @@ const mockProject: Project = {
     workspace_path: "/test/workspace",
     services_path: "repos",
     tilt: {
-      mode: "root",
+      mode: "Root",
     },
   },
   environments: {
@@ -56,14 +56,22 @@ const mockAppState = {
   recent_projects: [],
 }
 
+const mockTiltLog = (logs: string[]) => ({
+  logs,
+  records: [],
+  cursor: 0,
+  next_cursor: 0,
+  size: 0,
+})
+
 describe("ProjectView", () => {
   const mockOnBack = vi.fn()
   const mockOnEdit = vi.fn()
 
   beforeEach(() => {
     vi.clearAllMocks()
-    vi.mocked(api.getTiltState).mockResolvedValue("stopped")
-    vi.mocked(api.getTiltLogs).mockResolvedValue(JSON.stringify({ logs: [] }))
+    vi.mocked(api.getTiltState).mockResolvedValue({ status: "stopped" })
+    vi.mocked(api.getTiltLogs).mockResolvedValue(mockTiltLog([]))
   })
 
   const renderComponent = () => {
@@ -185,11 +193,13 @@ describe("ProjectView", () => {
     })
 
     it("should display logs when fetched", async () => {
-      const mockLogs = JSON.stringify({ logs: ["Log line 1", "Log line 2"] })
+      const mockLogs = mockTiltLog(["Log line 1", "Log line 2"])
 
       // Mock Tilt state to be running after start
       let tiltState = "stopped"
-      vi.mocked(api.getTiltState).mockImplementation(async () => tiltState)
+      vi.mocked(api.getTiltState).mockImplementation(async () => ({
+        status: tiltState,
+      }))
       vi.mocked(api.getTiltLogs).mockResolvedValue(mockLogs)
       vi.mocked(api.startTilt).mockImplementation(async () => {
         tiltState = "running"
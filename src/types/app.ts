@@ -17,5 +17,17 @@ export interface AppState {
     default_editor?: string | null
     tilt_path?: string | null
     editor_path?: string | null
+    log_redaction_patterns?: string[]
+    log_retention?: {
+      max_file_bytes: number
+      max_sessions: number
+      max_age_days: number | null
+    }
+    notifications?: {
+      enabled: boolean
+      debounce_secs: number
+      muted_projects: string[]
+      muted_services: Record<string, string[]>
+    }
   }
 }
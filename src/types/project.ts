@@ -24,20 +24,91 @@ export interface Service {
   kustomize?: {
     path: string
   }
+  replicas?: number
+  secrets?: SecretRef[] | null
+}
+
+export interface SecretRef {
+  key: string
+  file?: string | null
+}
+
+export interface SecretEntry {
+  service: string | null
+  key: string
+  file: string | null
+  has_value: boolean
+  value: string
+}
+
+export interface ServiceOverride {
+  port?: number
+  enabled?: boolean
+  env?: Record<string, string>
+  branch?: string
+  helm_values?: Record<string, any>
+  replicas?: number
+}
+
+export interface Environment {
+  shared_env: Record<string, string>
+  services: Service[]
+  extends?: string | null
+  overrides?: Record<string, ServiceOverride> | null
+  secrets?: SecretRef[] | null
+}
+
+export interface ValueOrigin {
+  key: string
+  value: any
+  env: string
+  kind: "defined" | "override"
 }
 
 export interface Project {
   project: {
+    id?: string | null
     name: string
     workspace_path: string
-    tilt: { mode: "root" | "per-repo" | "hybrid" }
+    tilt: { mode: "Root" | "PerRepo" | "Hybrid" }
     services_path?: string
   }
-  environments: Record<
-    string,
-    {
-      shared_env: Record<string, string>
-      services: Service[]
-    }
-  >
+  environments: Record<string, Environment>
+}
+
+export type ChangeKind = "added" | "removed" | "changed"
+
+export interface EnvVarChange {
+  key: string
+  change: ChangeKind
+  source_value: string | null
+  target_value: string | null
+}
+
+export interface ServiceChange {
+  name: string
+  change: ChangeKind
+  fields: string[]
+  env: EnvVarChange[]
+  source: Service | null
+  target: Service | null
+}
+
+export interface EnvDiff {
+  source: string
+  target: string
+  shared_env: EnvVarChange[]
+  services: ServiceChange[]
+}
+
+export interface PromoteSelection {
+  shared_env?: string[]
+  services?: string[]
+  service_env?: Record<string, string[]>
+}
+
+export interface InterpolationError {
+  service: string
+  key: string
+  message: string
 }
@@ -2,8 +2,44 @@ export interface TiltStatus {
   status: string
 }
 
+export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
+
+export interface LogRecord {
+  timestamp: string | null
+  resource: string | null
+  level: LogLevel | null
+  stream: "stdout" | "stderr" | null
+  line: string
+  message: string
+}
+
 export interface TiltLog {
   logs: string[]
+  records: LogRecord[]
+  cursor: number
+  next_cursor: number
+  size: number
+}
+
+export interface LogQuery {
+  cursor?: number
+  from_line?: number
+  before?: number
+  session?: string
+  resources?: string[]
+  level?: LogLevel
+  since?: string
+  until?: string
+  text?: string
+  pattern?: string
+}
+
+export interface LogSession {
+  id: string
+  started_at: string | null
+  parts: number
+  size_bytes: number
+  current: boolean
 }
 
 export interface TiltInstallation {
@@ -11,3 +47,79 @@ export interface TiltInstallation {
   path: string | null
   version: string | null
 }
+
+export interface LogMatch {
+  env: string
+  session: string
+  session_started_at: string | null
+  file: string
+  line_number: number
+  resource: string | null
+  line: string
+  before: string[]
+  after: string[]
+}
+
+export interface LogSearchBatch {
+  search_id: string
+  matches: LogMatch[]
+  total: number
+  done: boolean
+  error: string | null
+}
+
+export interface LogSearchQuery
+  extends Omit<LogQuery, "cursor" | "from_line" | "before" | "session"> {
+  env?: string
+  context?: number
+  batch_size?: number
+}
+
+export type StopInitiator = "user" | "tray" | "watchdog" | "crash"
+
+/** Outcome of a start, stop or restart from the tray */
+export interface TrayTiltAction {
+  workspace_path: string
+  env: string
+  action: "start" | "stop" | "restart"
+  status: string
+  error: string | null
+}
+
+export interface SessionRecord {
+  id: string
+  started_at: string
+  stopped_at: string | null
+  tilt_version: string | null
+  tiltfile_hash: string | null
+  enabled_services: string[]
+  exit_code: number | null
+  restart_count: number
+  stopped_by: StopInitiator | null
+}
+
+export interface Percentiles {
+  count: number
+  p50: number
+  p90: number
+  p99: number
+  max: number
+  mean: number
+}
+
+export interface TrendPoint {
+  session: string
+  builds: number
+  failures: number
+  median_ms: number
+}
+
+export interface ResourceStats {
+  resource: string
+  updates: number
+  failures: number
+  build_duration: Percentiles
+  time_to_ready: Percentiles
+  trend: TrendPoint[]
+  trend_change_pct: number | null
+}